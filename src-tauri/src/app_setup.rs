@@ -1,14 +1,28 @@
 // snaplock/src-tauri/src/app_setup.rs
 
-use crate::{handlers, state::AppState};
+use crate::constants::MAX_SHORTCUT_RETRY_DELAY_MS;
+use crate::{
+    handlers,
+    state::{AppState, MonitoringFlags},
+};
 use anyhow::Result;
+use serde::Serialize;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 use tauri::{
-    App, AppHandle, Manager,
+    App, AppHandle, Emitter, Manager,
     menu::{Menu, MenuItem, PredefinedMenuItem},
     tray::{MouseButton, TrayIconBuilder, TrayIconEvent},
 };
+use tauri_plugin_autostart::{MacosLauncher, ManagerExt};
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
 
+/// 托盘图标 ID，用于运行时切换托盘图标的可见性
+const TRAY_ID: &str = "main";
+
+static SHUTDOWN_STARTED: AtomicBool = AtomicBool::new(false);
+
 pub fn setup_system_tray(app: &AppHandle<tauri::Wry>) -> Result<tauri::tray::TrayIcon<tauri::Wry>> {
     let toggle_item = MenuItem::with_id(app, "toggle", "显示/隐藏窗口", true, None::<&str>)?;
     let start_monitoring_item =
@@ -35,25 +49,17 @@ pub fn setup_system_tray(app: &AppHandle<tauri::Wry>) -> Result<tauri::tray::Tra
         .cloned()
         .ok_or_else(|| anyhow::anyhow!("缺少默认窗口图标"))?;
 
-    let tray = TrayIconBuilder::new()
+    let tray = TrayIconBuilder::with_id(TRAY_ID)
         .menu(&menu)
         .tooltip("SnapLock")
         .icon(icon)
         .on_menu_event(|app_handle, event| match event.id().as_ref() {
-            "quit" => {
-                let app_handle_clone = app_handle.clone();
-                tauri::async_runtime::spawn(async move {
-                    if let Err(error) =
-                        handlers::stop_monitoring_command(app_handle_clone.clone()).await
-                    {
-                        log::error!("退出前清理监控状态失败: {}", error);
-                    }
-                    app_handle_clone.exit(0);
-                });
-            }
+            "quit" => request_app_exit(app_handle),
             "toggle" => {
                 if let Some(window) = app_handle.get_webview_window("main") {
                     let _ = if window.is_visible().unwrap_or(false) {
+                        // 窗口隐藏后预览不可见，释放摄像头
+                        tauri::async_runtime::spawn(crate::preview::stop_async());
                         window.hide()
                     } else {
                         window.show()
@@ -99,104 +105,287 @@ pub fn setup_system_tray(app: &AppHandle<tauri::Wry>) -> Result<tauri::tray::Tra
     Ok(tray)
 }
 
+/// 按配置应用隐身模式（不在任务栏与 Alt-Tab 中显示）及托盘图标的可见性。
+/// 托盘图标隐藏时仍可通过再次启动 SnapLock 唤出主窗口
+pub fn apply_stealth_mode(app_handle: &AppHandle<tauri::Wry>) {
+    let state = app_handle.state::<AppState>();
+    let stealth_mode = state.stealth_mode();
+
+    if let Some(window) = app_handle.get_webview_window("main") {
+        if let Err(error) = window.set_skip_taskbar(stealth_mode) {
+            log::error!("设置任务栏显示状态失败: {}", error);
+        }
+        #[cfg(target_os = "windows")]
+        if let Err(error) = set_tool_window_style(&window, stealth_mode) {
+            log::error!("设置 Alt-Tab 显示状态失败: {}", error);
+        }
+    }
+
+    if let Some(tray) = app_handle.tray_by_id(TRAY_ID) {
+        if let Err(error) = tray.set_visible(!state.hide_tray_icon()) {
+            log::error!("设置托盘图标可见性失败: {}", error);
+        }
+    }
+
+    log::info!(
+        "隐身模式: {}，隐藏托盘图标: {}",
+        stealth_mode,
+        state.hide_tray_icon()
+    );
+}
+
+/// 工具窗口样式的窗口不会出现在 Alt-Tab 列表中
+#[cfg(target_os = "windows")]
+fn set_tool_window_style(
+    window: &tauri::WebviewWindow<tauri::Wry>,
+    enabled: bool,
+) -> std::result::Result<(), String> {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GWL_EXSTYLE, GetWindowLongPtrW, SetWindowLongPtrW, WS_EX_TOOLWINDOW,
+    };
+
+    let hwnd = HWND(
+        window
+            .hwnd()
+            .map_err(|error| format!("获取窗口句柄失败: {}", error))?
+            .0,
+    );
+    unsafe {
+        let style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+        let tool_window = WS_EX_TOOLWINDOW.0 as isize;
+        let new_style = if enabled {
+            style | tool_window
+        } else {
+            style & !tool_window
+        };
+        SetWindowLongPtrW(hwnd, GWL_EXSTYLE, new_style);
+    }
+    Ok(())
+}
+
+/// 退出前的统一清理：停止监控与后台任务、结束所有录制进程并刷新日志。
+/// 窗口销毁、应用退出与退出锁定等路径都会调用，重复调用时只执行一次
+pub fn shutdown(app_handle: &AppHandle<tauri::Wry>) {
+    if SHUTDOWN_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    log::info!("正在停止所有后台任务...");
+
+    // 停止监控并中止无活动检查任务，使进行中的触发流程失效
+    app_handle.state::<Arc<MonitoringFlags>>().stop_monitoring();
+    crate::notifications::cancel_pending_alerts();
+    crate::local_api::stop();
+    crate::network_share::disconnect();
+
+    // 同步停止预录缓冲、画面变化检测、摄像头录像与屏幕录制，避免进程退出后 ffmpeg 残留
+    crate::prebuffer::stop();
+    crate::motion::stop();
+    crate::preview::stop();
+    crate::recorder::stop_all_recordings();
+
+    crate::window_geometry::persist(app_handle);
+    log::info!("后台任务已全部停止");
+    log::logger().flush();
+}
+
+/// 停止监控及所有录制进程后真正退出应用
+pub fn request_app_exit(app_handle: &AppHandle<tauri::Wry>) {
+    let app_handle_clone = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(error) = handlers::shutdown_monitoring(&app_handle_clone).await {
+            log::error!("退出前清理监控状态失败: {}", error);
+        }
+        if app_handle_clone.state::<AppState>().notify_on_startup() {
+            crate::notifications::show(
+                &app_handle_clone,
+                &crate::i18n::tr(&app_handle_clone, crate::i18n::Message::AppExited),
+            );
+        }
+        shutdown(&app_handle_clone);
+        app_handle_clone.exit(0);
+    });
+}
+
+/// 主快捷键重试耗尽后依次尝试的备用快捷键
+const BACKUP_SHORTCUTS: [&str; 4] = ["Ctrl+Alt+L", "Ctrl+Shift+L", "Alt+Shift+L", "Ctrl+Alt+S"];
+
+/// 主快捷键的最终注册结果，通过 `shortcut_registration` 事件通知前端
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ShortcutRegistration {
+    Registered {
+        shortcut: String,
+    },
+    FallbackRegistered {
+        configured: String,
+        shortcut: String,
+    },
+    Failed {
+        configured: String,
+    },
+}
+
+fn emit_shortcut_registration(app_handle: &AppHandle<tauri::Wry>, result: ShortcutRegistration) {
+    if let Err(e) = app_handle.emit("shortcut_registration", result) {
+        log::error!("无法发送快捷键注册事件: {}", e);
+    }
+}
+
+/// 注册切换警戒状态的全局快捷键
+fn register_toggle_shortcut(app_handle: &AppHandle<tauri::Wry>, shortcut_str: &str) -> Result<()> {
+    let shortcut = shortcut_str
+        .parse::<Shortcut>()
+        .map_err(|e| anyhow::anyhow!("Invalid shortcut format: {}", e))?;
+
+    let handle = app_handle.clone();
+    app_handle
+        .global_shortcut()
+        .on_shortcut(shortcut, move |app, _shortcut, _event| {
+            // 安全获取 AppState，失败时记录错误但不阻止快捷键触发
+            match app.try_state::<AppState>() {
+                Some(state) => {
+                    if state.shortcuts_disabled() {
+                        log::debug!("快捷键已禁用，忽略触发");
+                        return;
+                    }
+                }
+                None => {
+                    log::warn!("无法获取 AppState，但继续执行快捷键操作");
+                }
+            }
+
+            let handle_clone = handle.clone();
+            tauri::async_runtime::spawn(async move {
+                handlers::toggle_monitoring(&handle_clone).await;
+            });
+        })
+        .map_err(|e| anyhow::anyhow!("Failed to register shortcut: {}", e))?;
+
+    // 记录实际注册成功的快捷键，可能与配置中的不同（如回退到备用快捷键）
+    app_handle
+        .state::<AppState>()
+        .set_active_shortcut(Some(shortcut_str.to_string()));
+    Ok(())
+}
+
+/// 第 `attempt` 次重试前的等待时间：从 `interval_ms` 开始每次翻倍，不超过上限
+fn shortcut_retry_delay(interval_ms: u64, attempt: u32) -> Duration {
+    let factor = 1u64 << attempt.saturating_sub(1).min(16);
+    Duration::from_millis(
+        interval_ms
+            .saturating_mul(factor)
+            .min(MAX_SHORTCUT_RETRY_DELAY_MS),
+    )
+}
+
 pub fn register_global_shortcuts(app: &mut App<tauri::Wry>) -> Result<()> {
     let handle = app.handle().clone();
-    let handle_backup = app.handle().clone();
     let state = app.state::<AppState>();
     let shortcut_str = state.shortcut_key();
 
     log::info!("尝试注册快捷键: {}", shortcut_str);
 
-    // 尝试解析并注册主快捷键
-    match shortcut_str.parse::<Shortcut>() {
-        Ok(shortcut) => {
-            // 快捷键格式有效，尝试注册
-            match app
-                .global_shortcut()
-                .on_shortcut(shortcut, move |app, _shortcut, _event| {
-                    // 安全获取 AppState，失败时记录错误但不阻止快捷键触发
-                    match app.try_state::<AppState>() {
-                        Some(state) => {
-                            if state.shortcuts_disabled() {
-                                log::debug!("快捷键已禁用，忽略触发");
-                                return;
-                            }
-                        }
-                        None => {
-                            log::warn!("无法获取 AppState，但继续执行快捷键操作");
-                        }
-                    }
+    // 紧急快捷键独立于警戒快捷键，注册失败不影响主快捷键
+    if let Some(panic_shortcut) = state.panic_shortcut_key() {
+        if let Err(e) = register_panic_shortcut(app.handle(), &panic_shortcut) {
+            log::error!("紧急快捷键注册失败: {}", e);
+        }
+    }
 
-                    let handle_clone = handle.clone();
-                    tauri::async_runtime::spawn(async move {
-                        handlers::toggle_monitoring(&handle_clone).await;
-                    });
-                }) {
-                Ok(_) => {
-                    log::info!("✓ 主快捷键注册成功: {}", shortcut_str);
-                    return Ok(());
-                }
-                Err(e) => {
-                    log::warn!("主快捷键注册失败: {}，尝试备用快捷键", e);
-                }
-            }
+    match register_toggle_shortcut(&handle, &shortcut_str) {
+        Ok(()) => {
+            log::info!("✓ 主快捷键注册成功: {}", shortcut_str);
+            emit_shortcut_registration(
+                &handle,
+                ShortcutRegistration::Registered {
+                    shortcut: shortcut_str,
+                },
+            );
+        }
+        // 快捷键可能在启动时被其他程序短暂占用，格式有效时先在后台重试
+        Err(e)
+            if shortcut_str.parse::<Shortcut>().is_ok() && state.shortcut_retry_attempts() > 0 =>
+        {
+            log::warn!("主快捷键注册失败: {}，将在后台重试", e);
+            tauri::async_runtime::spawn(retry_global_shortcut(handle, shortcut_str));
         }
         Err(e) => {
-            log::warn!("主快捷键格式无效: {}，尝试备用快捷键", e);
+            log::warn!("主快捷键注册失败: {}，尝试备用快捷键", e);
+            register_backup_shortcut(&handle, &shortcut_str);
         }
     }
 
-    // 主快捷键失败，尝试多个备用快捷键
-    let backup_shortcuts = vec!["Ctrl+Alt+L", "Ctrl+Shift+L", "Alt+Shift+L", "Ctrl+Alt+S"];
+    // 注册失败不返回错误，让程序继续运行
+    Ok(())
+}
+
+/// 按退避间隔重试注册主快捷键，重试耗尽后再尝试备用快捷键
+async fn retry_global_shortcut(app_handle: AppHandle<tauri::Wry>, shortcut_str: String) {
+    let (attempts, interval_ms) = {
+        let state = app_handle.state::<AppState>();
+        (
+            state.shortcut_retry_attempts(),
+            state.shortcut_retry_interval_ms(),
+        )
+    };
+
+    for attempt in 1..=attempts {
+        tokio::time::sleep(shortcut_retry_delay(interval_ms, attempt)).await;
+
+        // 重试期间用户可能已在设置中更换了快捷键
+        if app_handle.state::<AppState>().shortcut_key() != shortcut_str {
+            log::info!("快捷键设置已变更，停止重试注册: {}", shortcut_str);
+            return;
+        }
 
-    for backup_shortcut in backup_shortcuts {
+        match register_toggle_shortcut(&app_handle, &shortcut_str) {
+            Ok(()) => {
+                log::info!("✓ 主快捷键第 {} 次重试注册成功: {}", attempt, shortcut_str);
+                emit_shortcut_registration(
+                    &app_handle,
+                    ShortcutRegistration::Registered {
+                        shortcut: shortcut_str,
+                    },
+                );
+                return;
+            }
+            Err(e) => log::warn!("主快捷键第 {}/{} 次重试失败: {}", attempt, attempts, e),
+        }
+    }
+
+    register_backup_shortcut(&app_handle, &shortcut_str);
+}
+
+/// 依次尝试备用快捷键，成功时替换配置中的快捷键
+fn register_backup_shortcut(app_handle: &AppHandle<tauri::Wry>, configured: &str) {
+    for backup_shortcut in BACKUP_SHORTCUTS {
         log::info!("尝试注册备用快捷键: {}", backup_shortcut);
 
-        match backup_shortcut.parse::<Shortcut>() {
-            Ok(backup) => {
-                match app.global_shortcut().on_shortcut(backup, {
-                    let handle_backup = handle_backup.clone();
-                    move |app, _shortcut, _event| {
-                        // 安全获取 AppState，失败时记录错误但不阻止快捷键触发
-                        match app.try_state::<AppState>() {
-                            Some(state) => {
-                                if state.shortcuts_disabled() {
-                                    log::debug!("快捷键已禁用，忽略触发");
-                                    return;
-                                }
-                            }
-                            None => {
-                                log::warn!("无法获取 AppState，但继续执行快捷键操作");
-                            }
-                        }
-
-                        let handle_clone = handle_backup.clone();
-                        tauri::async_runtime::spawn(async move {
-                            handlers::toggle_monitoring(&handle_clone).await;
-                        });
-                    }
-                }) {
-                    Ok(_) => {
-                        // 备用快捷键注册成功，更新状态
-                        state.set_shortcut_key(backup_shortcut.to_string());
-                        log::info!("✓ 备用快捷键注册成功: {}", backup_shortcut);
-
-                        // 保存配置
-                        if let Err(e) = crate::config::save_config(app.handle().clone()) {
-                            log::warn!("保存备用快捷键配置失败: {}", e);
-                        }
-
-                        return Ok(());
-                    }
-                    Err(e) => {
-                        log::warn!("备用快捷键 {} 注册失败: {}", backup_shortcut, e);
-                        continue;
-                    }
+        match register_toggle_shortcut(app_handle, backup_shortcut) {
+            Ok(()) => {
+                // 备用快捷键注册成功，更新状态
+                app_handle
+                    .state::<AppState>()
+                    .set_shortcut_key(backup_shortcut.to_string());
+                log::info!("✓ 备用快捷键注册成功: {}", backup_shortcut);
+
+                // 保存配置
+                if let Err(e) = crate::config::save_config(app_handle.clone()) {
+                    log::warn!("保存备用快捷键配置失败: {}", e);
                 }
+
+                emit_shortcut_registration(
+                    app_handle,
+                    ShortcutRegistration::FallbackRegistered {
+                        configured: configured.to_string(),
+                        shortcut: backup_shortcut.to_string(),
+                    },
+                );
+                return;
             }
             Err(e) => {
-                log::error!("备用快捷键 {} 格式无效: {}", backup_shortcut, e);
-                continue;
+                log::warn!("备用快捷键 {} 注册失败: {}", backup_shortcut, e);
             }
         }
     }
@@ -204,9 +393,13 @@ pub fn register_global_shortcuts(app: &mut App<tauri::Wry>) -> Result<()> {
     // 所有快捷键都失败了
     log::error!("⚠️  所有快捷键注册均失败，程序将继续运行但无法使用快捷键");
     log::info!("您仍可以通过系统托盘图标或主界面操作程序");
-
-    // 返回 Ok 而不是错误，让程序继续运行
-    Ok(())
+    app_handle.state::<AppState>().set_active_shortcut(None);
+    emit_shortcut_registration(
+        app_handle,
+        ShortcutRegistration::Failed {
+            configured: configured.to_string(),
+        },
+    );
 }
 
 /// Updates the global shortcut by unregistering the old one and registering the new one
@@ -223,38 +416,14 @@ pub async fn update_global_shortcut(
     }
 
     // 验证新快捷键格式
-    let shortcut = match new_shortcut.parse::<Shortcut>() {
-        Ok(s) => s,
-        Err(e) => {
-            log::error!("新快捷键格式无效: {} - {}", new_shortcut, e);
-            return Err(anyhow::anyhow!("Invalid shortcut format: {}", e));
-        }
-    };
+    if let Err(e) = new_shortcut.parse::<Shortcut>() {
+        log::error!("新快捷键格式无效: {} - {}", new_shortcut, e);
+        return Err(anyhow::anyhow!("Invalid shortcut format: {}", e));
+    }
 
     // Register the new shortcut
-    let handle_clone = app_handle.clone();
-    match app_handle
-        .global_shortcut()
-        .on_shortcut(shortcut, move |app, _shortcut, _event| {
-            // 安全获取 AppState，失败时记录错误但不阻止快捷键触发
-            match app.try_state::<AppState>() {
-                Some(state) => {
-                    if state.shortcuts_disabled() {
-                        log::debug!("快捷键已禁用，忽略触发");
-                        return;
-                    }
-                }
-                None => {
-                    log::warn!("无法获取 AppState，但继续执行快捷键操作");
-                }
-            }
-
-            let handle_clone = handle_clone.clone();
-            tauri::async_runtime::spawn(async move {
-                handlers::toggle_monitoring(&handle_clone).await;
-            });
-        }) {
-        Ok(_) => {
+    match register_toggle_shortcut(app_handle, new_shortcut) {
+        Ok(()) => {
             log::info!("✓ 新快捷键注册成功: {}", new_shortcut);
 
             if let Err(e) = app_handle.global_shortcut().unregister(old_shortcut) {
@@ -270,8 +439,225 @@ pub async fn update_global_shortcut(
     }
 }
 
+/// 注册紧急快捷键：立即拍照、锁屏并跳过准备期进入警戒
+pub fn register_panic_shortcut(
+    app_handle: &AppHandle<tauri::Wry>,
+    shortcut_str: &str,
+) -> Result<()> {
+    let shortcut = shortcut_str
+        .parse::<Shortcut>()
+        .map_err(|e| anyhow::anyhow!("Invalid shortcut format: {}", e))?;
+
+    let handle = app_handle.clone();
+    app_handle
+        .global_shortcut()
+        .on_shortcut(shortcut, move |app, _shortcut, _event| {
+            if let Some(state) = app.try_state::<AppState>() {
+                if state.shortcuts_disabled() {
+                    log::debug!("快捷键已禁用，忽略紧急快捷键");
+                    return;
+                }
+            }
+
+            let handle_clone = handle.clone();
+            tauri::async_runtime::spawn(async move {
+                handlers::panic_lock(&handle_clone).await;
+            });
+        })
+        .map_err(|e| anyhow::anyhow!("Failed to register panic shortcut: {}", e))?;
+
+    log::info!("✓ 紧急快捷键注册成功: {}", shortcut_str);
+    Ok(())
+}
+
+/// 更新紧急快捷键，`None` 表示不使用紧急快捷键
+pub fn update_panic_shortcut(
+    app_handle: &AppHandle<tauri::Wry>,
+    old_shortcut: Option<&str>,
+    new_shortcut: Option<&str>,
+) -> Result<()> {
+    if old_shortcut == new_shortcut {
+        return Ok(());
+    }
+
+    if let Some(new_shortcut) = new_shortcut {
+        register_panic_shortcut(app_handle, new_shortcut)?;
+    }
+
+    if let Some(old_shortcut) = old_shortcut {
+        if let Err(e) = app_handle.global_shortcut().unregister(old_shortcut) {
+            log::warn!("取消注册旧紧急快捷键失败 (忽略): {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+/// 以该参数启动时在 setup 结束后检查是否有窗口被显示，并以退出码报告结果
+pub const SILENT_STARTUP_CHECK_ARG: &str = "--check-silent-startup";
+/// 启动后立即进入警戒（仍会经过准备期）
+pub const ARM_ARG: &str = "--arm";
+/// 启动时不显示主窗口
+pub const HIDDEN_ARG: &str = "--hidden";
+
+/// 单次启动的命令行选项，供脚本部署时覆盖配置
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LaunchOptions {
+    pub arm: bool,
+    pub hidden: bool,
+}
+
+impl LaunchOptions {
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Self {
+        let mut options = Self::default();
+        for arg in args {
+            match arg.as_str() {
+                ARM_ARG => options.arm = true,
+                HIDDEN_ARG => options.hidden = true,
+                _ => {}
+            }
+        }
+        options
+    }
+}
+
+fn is_silent_startup_check(args: impl IntoIterator<Item = String>) -> bool {
+    args.into_iter().any(|arg| arg == SILENT_STARTUP_CHECK_ARG)
+}
+
+/// 应用应静默启动到托盘：主窗口在配置中默认隐藏，setup 期间不得弹出任何窗口。
+/// 以 `--check-silent-startup` 启动时校验这一点并退出（0 表示通过）。
+pub fn run_silent_startup_check(app_handle: &AppHandle<tauri::Wry>) {
+    if !is_silent_startup_check(std::env::args()) {
+        return;
+    }
+
+    let visible_windows: Vec<String> = app_handle
+        .webview_windows()
+        .into_iter()
+        .filter(|(_, window)| window.is_visible().unwrap_or(false))
+        .map(|(label, _)| label)
+        .collect();
+
+    if visible_windows.is_empty() {
+        log::info!("静默启动检查通过：启动期间没有显示任何窗口");
+        app_handle.exit(0);
+    } else {
+        log::error!(
+            "静默启动检查失败，以下窗口在启动时可见: {:?}",
+            visible_windows
+        );
+        app_handle.exit(1);
+    }
+}
+
+/// 当第二个实例启动时，按其命令行参数让已运行的实例进入警戒，未指定 `--hidden` 时显示并聚焦主窗口
+fn handle_second_instance(app_handle: &AppHandle<tauri::Wry>, args: Vec<String>) {
+    let options = LaunchOptions::from_args(args);
+    log::info!("检测到重复启动，启动参数: {:?}", options);
+
+    if options.arm {
+        let app_handle_clone = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            handlers::auto_arm_on_start(&app_handle_clone).await;
+        });
+    }
+
+    if !options.hidden {
+        focus_existing_instance(app_handle);
+    }
+}
+
+/// 显示并聚焦已运行实例的主窗口
+fn focus_existing_instance(app_handle: &AppHandle<tauri::Wry>) {
+    log::info!("聚焦已运行的实例");
+    if let Some(window) = app_handle.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
 pub fn setup_tauri_builder() -> tauri::Builder<tauri::Wry> {
     tauri::Builder::default()
+        // 单实例插件必须最先注册；它基于系统级互斥量/DBus，崩溃后不会残留锁文件
+        .plugin(tauri_plugin_single_instance::init(
+            |app_handle, args, _cwd| {
+                handle_second_instance(app_handle, args);
+            },
+        ))
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_autostart::init(
+            MacosLauncher::LaunchAgent,
+            None,
+        ))
+}
+
+/// 启动时以系统中的实际注册状态（注册表 Run 键等）为准同步开机自启设置
+pub fn sync_autostart_state(app_handle: &AppHandle<tauri::Wry>) {
+    let registered = match app_handle.autolaunch().is_enabled() {
+        Ok(registered) => registered,
+        Err(error) => {
+            log::warn!("读取开机自启状态失败: {}", error);
+            return;
+        }
+    };
+
+    let state = app_handle.state::<AppState>();
+    if state.autostart_enabled() == registered {
+        return;
+    }
+
+    log::info!("开机自启配置与系统状态不一致，已同步为: {}", registered);
+    state.set_autostart_enabled(registered);
+    if let Err(error) = crate::config::save_config(app_handle.clone()) {
+        log::warn!("保存开机自启状态失败: {}", error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ARM_ARG, HIDDEN_ARG, LaunchOptions, SILENT_STARTUP_CHECK_ARG, is_silent_startup_check,
+        shortcut_retry_delay,
+    };
+    use std::time::Duration;
+
+    #[test]
+    fn silent_startup_check_requires_explicit_flag() {
+        assert!(is_silent_startup_check([
+            "snaplock.exe".to_string(),
+            SILENT_STARTUP_CHECK_ARG.to_string(),
+        ]));
+        assert!(!is_silent_startup_check(["snaplock.exe".to_string()]));
+    }
+
+    #[test]
+    fn launch_options_read_arm_and_hidden_flags() {
+        assert_eq!(
+            LaunchOptions::from_args(["snaplock.exe".to_string()]),
+            LaunchOptions::default()
+        );
+        assert_eq!(
+            LaunchOptions::from_args([
+                "snaplock.exe".to_string(),
+                HIDDEN_ARG.to_string(),
+                ARM_ARG.to_string(),
+            ]),
+            LaunchOptions {
+                arm: true,
+                hidden: true,
+            }
+        );
+    }
+
+    #[test]
+    fn shortcut_retry_delay_doubles_up_to_cap() {
+        assert_eq!(shortcut_retry_delay(1_000, 1), Duration::from_secs(1));
+        assert_eq!(shortcut_retry_delay(1_000, 2), Duration::from_secs(2));
+        assert_eq!(shortcut_retry_delay(1_000, 3), Duration::from_secs(4));
+        assert_eq!(shortcut_retry_delay(1_000, 40), Duration::from_secs(30));
+        assert_eq!(shortcut_retry_delay(0, 5), Duration::ZERO);
+    }
 }
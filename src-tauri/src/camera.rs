@@ -1,19 +1,19 @@
 use base64::{Engine as _, engine::general_purpose};
 use chrono::Local;
+use image::codecs::{jpeg::JpegEncoder, png::PngEncoder, webp::WebPEncoder};
 use image::{ImageBuffer, RgbImage};
 use nokhwa::{
     Camera,
     pixel_format::RgbFormat,
     query,
-    utils::{ApiBackend, CameraIndex, CameraInfo, RequestedFormat, RequestedFormatType},
+    utils::{
+        ApiBackend, CameraIndex, CameraInfo, FrameFormat, RequestedFormat, RequestedFormatType,
+    },
 };
 use serde::Serialize;
-use std::collections::HashMap;
 use std::io::Cursor;
-use std::path::PathBuf;
-use std::process::{Child, Command, Stdio};
-use std::sync::Mutex;
-use tauri::{AppHandle, Manager, command};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager, command};
 
 /// Camera information for frontend
 #[derive(Serialize)]
@@ -22,8 +22,120 @@ pub struct CameraListItem {
     pub name: String,
 }
 
-lazy_static::lazy_static! {
-    pub static ref VIDEO_PROCESSES: Mutex<HashMap<u32, Child>> = Mutex::new(HashMap::new());
+/// A resolution, frame rate and pixel format combination supported by a camera
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CameraFormatItem {
+    pub width: u32,
+    pub height: u32,
+    pub frame_rate: u32,
+    pub fourcc: String,
+}
+
+/// Errors returned by capture operations that callers need to tell apart
+#[derive(Debug)]
+pub enum CameraError {
+    /// No camera is present on the system
+    NotFound,
+    /// The camera is held by another application
+    Busy(String),
+    /// The camera did not deliver a frame within the capture timeout
+    TimedOut(u64),
+    Other(String),
+}
+
+impl std::fmt::Display for CameraError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CameraError::NotFound => write!(f, "No cameras available on the system"),
+            CameraError::Busy(message) => write!(f, "Camera is in use: {}", message),
+            CameraError::TimedOut(timeout_ms) => {
+                write!(f, "Camera did not respond within {} ms", timeout_ms)
+            }
+            CameraError::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+/// Options applied when capturing photos
+#[derive(Debug, Clone, Copy)]
+pub struct PhotoOptions {
+    pub jpeg_quality: u8,
+    pub busy_retry_attempts: u32,
+    pub busy_retry_backoff_ms: u64,
+    /// 0 disables the timeout
+    pub capture_timeout_ms: u64,
+    pub fourcc: CameraFourcc,
+    /// Seconds to keep the stream open before the capture so focus and exposure settle
+    pub capture_delay_seconds: u32,
+    /// Frames read and discarded before the capture; the first frames are often black or blurry
+    pub warmup_frames: u32,
+    pub image_format: ImageFormat,
+    /// Photos taken per capture; the stream stays open between burst shots
+    pub burst_count: u32,
+    pub burst_interval_ms: u64,
+    /// Burn the capture time into the bottom-left corner before encoding
+    pub watermark_enabled: bool,
+}
+
+impl PhotoOptions {
+    pub fn from_state(state: &AppState) -> Self {
+        let (burst_count, burst_interval_ms) = state.capture_mode().photo_shots();
+        Self {
+            jpeg_quality: state.jpeg_quality(),
+            busy_retry_attempts: state.camera_busy_retry_attempts(),
+            busy_retry_backoff_ms: state.camera_busy_retry_backoff_ms(),
+            capture_timeout_ms: state.capture_timeout_ms(),
+            fourcc: state.camera_fourcc(),
+            capture_delay_seconds: state.capture_delay_seconds(),
+            warmup_frames: state.photo_warmup_frames(),
+            image_format: state.capture_image_format(),
+            burst_count,
+            burst_interval_ms,
+            watermark_enabled: state.watermark_enabled(),
+        }
+    }
+
+    /// 拍摄超时需要把预热、拍摄延迟与连拍间隔计算在内；0 表示不限制
+    fn effective_timeout_ms(&self) -> u64 {
+        if self.capture_timeout_ms == 0 {
+            return 0;
+        }
+        let warmup_ms =
+            u64::from(self.warmup_frames) * PHOTO_WARMUP_FRAME_INTERVAL.as_millis() as u64;
+        let burst_ms =
+            u64::from(self.burst_count.saturating_sub(1)).saturating_mul(self.burst_interval_ms);
+        self.capture_timeout_ms
+            .saturating_add(warmup_ms)
+            .saturating_add(u64::from(self.capture_delay_seconds) * 1_000)
+            .saturating_add(burst_ms)
+    }
+}
+
+/// 判断错误信息是否表示设备被其他程序占用
+fn is_device_busy_error(message: &str) -> bool {
+    let message = message.to_ascii_lowercase();
+    [
+        "busy",
+        "in use",
+        "being used",
+        "0x800700aa",
+        "0xc00d3704",
+        "0x80070020",
+    ]
+    .iter()
+    .any(|pattern| message.contains(pattern))
+}
+
+impl From<String> for CameraError {
+    fn from(message: String) -> Self {
+        CameraError::Other(message)
+    }
+}
+
+impl From<&str> for CameraError {
+    fn from(message: &str) -> Self {
+        CameraError::Other(message.to_string())
+    }
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -74,36 +186,6 @@ fn resolve_camera_info(camera_id: u32) -> Result<CameraInfo, String> {
     ))
 }
 
-fn prune_finished_video_processes(processes: &mut HashMap<u32, Child>) {
-    let mut finished_camera_ids = Vec::new();
-
-    for (camera_id, child) in processes.iter_mut() {
-        match child.try_wait() {
-            Ok(None) => {}
-            Ok(Some(status)) => {
-                log::warn!(
-                    "摄像头 {} 的 ffmpeg 录像进程已退出，状态: {:?}",
-                    camera_id,
-                    status
-                );
-                finished_camera_ids.push(*camera_id);
-            }
-            Err(error) => {
-                log::error!(
-                    "检查摄像头 {} 的 ffmpeg 录像进程状态失败: {}",
-                    camera_id,
-                    error
-                );
-                finished_camera_ids.push(*camera_id);
-            }
-        }
-    }
-
-    for camera_id in finished_camera_ids {
-        processes.remove(&camera_id);
-    }
-}
-
 /// A Tauri command that retrieves a list of available cameras with their actual indices.
 #[command]
 pub async fn get_camera_list() -> Result<Vec<CameraListItem>, String> {
@@ -130,8 +212,15 @@ pub async fn get_camera_list() -> Result<Vec<CameraListItem>, String> {
         })
 }
 
+/// Returns whether at least one camera can be enumerated on the system.
+pub fn has_cameras() -> bool {
+    enumerate_camera_info()
+        .map(|cameras| !cameras.is_empty())
+        .unwrap_or(false)
+}
+
 /// Validates if the given camera ID is available and returns the corresponding CameraInfo
-fn validate_camera_id(camera_id: u32) -> Result<CameraInfo, String> {
+pub(crate) fn validate_camera_id(camera_id: u32) -> Result<CameraInfo, String> {
     resolve_camera_info(camera_id)
 }
 
@@ -196,15 +285,72 @@ impl Drop for CameraGuard {
     }
 }
 
-/// 通用的相机初始化函数
-fn init_camera(camera_id: u32) -> Result<Camera, String> {
+/// 配置的像素格式对应的 nokhwa 帧格式，`Auto` 时返回 `None`
+fn frame_formats(fourcc: CameraFourcc) -> Option<&'static [FrameFormat]> {
+    match fourcc {
+        CameraFourcc::Auto => None,
+        CameraFourcc::Mjpg => Some(&[FrameFormat::MJPEG]),
+        CameraFourcc::Yuy2 => Some(&[FrameFormat::YUYV]),
+        CameraFourcc::Nv12 => Some(&[FrameFormat::NV12]),
+    }
+}
+
+/// 通用的相机初始化函数；摄像头不支持指定的像素格式时回退到自动选择
+fn init_camera(camera_id: u32, fourcc: CameraFourcc) -> Result<Camera, String> {
     let camera_info = validate_camera_id(camera_id)?;
+    let resolution = RequestedFormatType::AbsoluteHighestResolution;
 
-    let requested =
-        RequestedFormat::new::<RgbFormat>(RequestedFormatType::AbsoluteHighestResolution);
+    if let Some(formats) = frame_formats(fourcc) {
+        match Camera::new(
+            camera_info.index().clone(),
+            RequestedFormat::with_formats(resolution, formats),
+        ) {
+            Ok(camera) => return Ok(camera),
+            Err(e) => log::warn!(
+                "摄像头 {} 无法使用 {:?} 格式，回退到自动选择: {}",
+                camera_id,
+                fourcc,
+                e
+            ),
+        }
+    }
 
-    Camera::new(camera_info.index().clone(), requested)
-        .map_err(|e| format!("Failed to initialize camera ID {}: {}", camera_id, e))
+    Camera::new(
+        camera_info.index().clone(),
+        RequestedFormat::new::<RgbFormat>(resolution),
+    )
+    .map_err(|e| format!("Failed to initialize camera ID {}: {}", camera_id, e))
+}
+
+/// 打开摄像头并开始取流，供需要持续取帧的调用方使用
+pub(crate) fn open_camera_stream(camera_id: u32, fourcc: CameraFourcc) -> Result<Camera, String> {
+    let mut camera = init_camera(camera_id, fourcc)?;
+    camera
+        .open_stream()
+        .map_err(|e| format!("Failed to open stream for camera ID {}: {}", camera_id, e))?;
+    Ok(camera)
+}
+
+/// 从已打开的摄像头抓取一帧 RGB 图像
+pub(crate) fn capture_rgb_frame(camera: &mut Camera, camera_id: u32) -> Result<RgbImage, String> {
+    let (width, height, raw_buffer) = capture_frame_from_open_stream(camera, camera_id)?;
+    ImageBuffer::from_raw(width, height, raw_buffer)
+        .ok_or_else(|| "Failed to create image buffer from raw data".to_string())
+}
+
+/// 从已打开的摄像头抓取一帧并编码为 JPEG
+pub(crate) fn capture_jpeg_frame(
+    camera: &mut Camera,
+    camera_id: u32,
+    jpeg_quality: u8,
+) -> Result<Vec<u8>, String> {
+    let rgb_image = capture_rgb_frame(camera, camera_id)?;
+
+    let mut jpeg_buffer = Vec::new();
+    JpegEncoder::new_with_quality(&mut jpeg_buffer, jpeg_quality)
+        .encode_image(&rgb_image)
+        .map_err(|e| format!("Failed to encode frame as JPEG: {}", e))?;
+    Ok(jpeg_buffer)
 }
 
 /// 通用的图像捕获函数
@@ -230,17 +376,214 @@ fn capture_frame_from_open_stream(
     ))
 }
 
-/// 通用的图像捕获函数
-fn capture_frame(camera: &mut Camera, camera_id: u32) -> Result<(u32, u32, Vec<u8>), String> {
+/// 在延迟期间持续取帧并丢弃，让自动对焦与曝光在拍摄前稳定下来；每秒发送剩余秒数
+fn discard_frames_for(
+    app_handle: &AppHandle,
+    camera: &mut Camera,
+    camera_id: u32,
+    delay_seconds: u32,
+) {
+    if delay_seconds == 0 {
+        return;
+    }
+
+    log::info!("摄像头 {} 等待 {} 秒后拍摄", camera_id, delay_seconds);
+    let started_at = std::time::Instant::now();
+    let mut announced_seconds = None;
+    loop {
+        let elapsed_seconds = started_at.elapsed().as_secs();
+        if elapsed_seconds >= u64::from(delay_seconds) {
+            break;
+        }
+        let remaining_seconds = delay_seconds - elapsed_seconds as u32;
+        if announced_seconds != Some(remaining_seconds) {
+            emit_capture_countdown(app_handle, remaining_seconds);
+            announced_seconds = Some(remaining_seconds);
+        }
+
+        if let Err(error) = camera.frame() {
+            log::debug!("摄像头 {} 拍摄延迟期间取帧失败: {}", camera_id, error);
+            std::thread::sleep(CAPTURE_DELAY_RETRY_INTERVAL);
+        }
+    }
+    emit_capture_countdown(app_handle, 0);
+}
+
+/// 发送拍摄倒计时剩余秒数，0 表示倒计时结束
+pub(crate) fn emit_capture_countdown(app_handle: &AppHandle, remaining_seconds: u32) {
+    if let Err(error) = app_handle.emit("capture_countdown", remaining_seconds) {
+        log::error!("无法发送拍摄倒计时事件: {}", error);
+    }
+}
+
+/// 抓取到的一帧 RGB 数据及其拍摄时间
+struct CapturedFrame {
+    captured_at: chrono::DateTime<Local>,
+    width: u32,
+    height: u32,
+    raw_buffer: Vec<u8>,
+}
+
+fn grab_frame(camera: &mut Camera, camera_id: u32) -> Result<CapturedFrame, String> {
+    let (width, height, raw_buffer) = capture_frame_from_open_stream(camera, camera_id)?;
+    Ok(CapturedFrame {
+        captured_at: Local::now(),
+        width,
+        height,
+        raw_buffer,
+    })
+}
+
+/// 通用的图像捕获函数；连拍时保持摄像头打开，按间隔继续取帧
+fn capture_frames(
+    app_handle: &AppHandle,
+    camera: &mut Camera,
+    camera_id: u32,
+    options: &PhotoOptions,
+) -> Result<Vec<CapturedFrame>, String> {
     camera
         .open_stream()
         .map_err(|e| format!("Failed to open stream for camera ID {}: {}", camera_id, e))?;
 
-    capture_frame_from_open_stream(camera, camera_id)
+    // 预热帧失败不中断拍照，由最后一帧决定结果；流由调用方的 CameraGuard 关闭
+    for _ in 0..options.warmup_frames {
+        let _ = camera.frame();
+        std::thread::sleep(PHOTO_WARMUP_FRAME_INTERVAL);
+    }
+    discard_frames_for(app_handle, camera, camera_id, options.capture_delay_seconds);
+
+    if let Err(error) = app_handle.emit("capturing", [camera_id]) {
+        log::error!("无法发送拍摄事件: {}", error);
+    }
+    let mut frames = vec![grab_frame(camera, camera_id)?];
+    for shot in 2..=options.burst_count {
+        std::thread::sleep(std::time::Duration::from_millis(options.burst_interval_ms));
+        match grab_frame(camera, camera_id) {
+            Ok(frame) => frames.push(frame),
+            Err(error) => {
+                // 已拍到的照片仍然保存
+                log::warn!(
+                    "摄像头 {} 连拍第 {} 张失败，停止连拍: {}",
+                    camera_id,
+                    shot,
+                    error
+                );
+                break;
+            }
+        }
+    }
+    Ok(frames)
+}
+
+/// 摄像头被占用、等待重试时发送给前端的进度
+#[derive(Debug, Clone, Serialize)]
+struct CameraBusyRetryPayload {
+    camera_id: u32,
+    attempt: u32,
+    max_attempts: u32,
+}
+
+/// 初始化摄像头并抓取照片所需的帧；设备被占用时按退避间隔有限次重试
+fn capture_frames_with_busy_retry(
+    app_handle: &AppHandle,
+    camera_id: u32,
+    options: &PhotoOptions,
+) -> Result<Vec<CapturedFrame>, CameraError> {
+    let mut attempt = 0;
+
+    loop {
+        let result = init_camera(camera_id, options.fourcc).and_then(|camera| {
+            let mut camera_guard = CameraGuard::new(camera);
+            let cam = camera_guard
+                .get_mut()
+                .ok_or("Camera guard failed to provide camera reference")?;
+            capture_frames(app_handle, cam, camera_id, options)
+        });
+
+        let error = match result {
+            Ok(frames) => return Ok(frames),
+            Err(error) if is_device_busy_error(&error) => error,
+            Err(error) => return Err(CameraError::Other(error)),
+        };
+
+        if attempt >= options.busy_retry_attempts {
+            log::error!("摄像头 {} 持续被占用，放弃拍照: {}", camera_id, error);
+            return Err(CameraError::Busy(error));
+        }
+
+        attempt += 1;
+        let backoff_ms = options.busy_retry_backoff_ms.saturating_mul(attempt.into());
+        log::warn!(
+            "摄像头 {} 被占用，{} ms 后重试 ({}/{}): {}",
+            camera_id,
+            backoff_ms,
+            attempt,
+            options.busy_retry_attempts,
+            error
+        );
+        let payload = CameraBusyRetryPayload {
+            camera_id,
+            attempt,
+            max_attempts: options.busy_retry_attempts,
+        };
+        if let Err(emit_error) = app_handle.emit("camera_busy_retry", payload) {
+            log::error!("无法发送摄像头占用重试事件: {}", emit_error);
+        }
+        std::thread::sleep(std::time::Duration::from_millis(backoff_ms));
+    }
+}
+
+/// 按设置的格式编码图像；JPEG 使用指定质量并附加 EXIF 段，PNG 与 WebP 为无损编码
+fn encode_photo(
+    image: &RgbImage,
+    format: ImageFormat,
+    jpeg_quality: u8,
+    exif_segment: &[u8],
+) -> Result<Vec<u8>, String> {
+    let mut buffer = Vec::new();
+    match format {
+        ImageFormat::Jpeg => {
+            JpegEncoder::new_with_quality(&mut buffer, jpeg_quality)
+                .encode_image(image)
+                .map_err(|e| e.to_string())?;
+            return Ok(crate::exif::insert_segment(buffer, exif_segment));
+        }
+        ImageFormat::Png => image
+            .write_with_encoder(PngEncoder::new(&mut buffer))
+            .map_err(|e| e.to_string())?,
+        ImageFormat::WebP => image
+            .write_with_encoder(WebPEncoder::new_lossless(&mut buffer))
+            .map_err(|e| e.to_string())?,
+    }
+    Ok(buffer)
+}
+
+/// 按设置的格式编码图像后写入文件
+fn save_photo(
+    image: &RgbImage,
+    filepath: &Path,
+    options: &PhotoOptions,
+    exif_segment: &[u8],
+) -> Result<(), String> {
+    let encoded = encode_photo(
+        image,
+        options.image_format,
+        options.jpeg_quality,
+        exif_segment,
+    )
+    .map_err(|e| format!("Failed to save image to '{}': {}", filepath.display(), e))?;
+
+    std::fs::write(filepath, encoded).map_err(|e| {
+        format!(
+            "Failed to create image file '{}': {}",
+            filepath.display(),
+            e
+        )
+    })
 }
 
 /// 通用的保存路径处理函数
-fn get_save_path(save_path: Option<String>) -> Result<PathBuf, String> {
+pub(crate) fn get_save_path(save_path: Option<String>) -> Result<PathBuf, String> {
     let base_path = match save_path {
         Some(path) => PathBuf::from(path),
         None => dirs::desktop_dir().ok_or_else(|| "Desktop directory not found".to_string())?,
@@ -259,11 +602,83 @@ fn get_save_path(save_path: Option<String>) -> Result<PathBuf, String> {
     Ok(base_path)
 }
 
-/// Captures a photo using the specified camera and saves it to a configurable path.
-pub async fn take_photo(camera_id: u32, save_path: Option<String>) -> Result<String, String> {
+/// 拍照文件名：多摄像头时附加摄像头编号，连拍时附加从 1 开始的序号
+fn photo_filename(
+    timestamp: &str,
+    camera_id: Option<u32>,
+    shot: Option<usize>,
+    extension: &str,
+) -> String {
+    let mut filename = format!("snaplock_capture_{}", timestamp);
+    if let Some(camera_id) = camera_id {
+        filename.push_str(&format!("_cam{}", camera_id));
+    }
+    if let Some(shot) = shot {
+        filename.push_str(&format!("_{}", shot));
+    }
+    format!("{}.{}", filename, extension)
+}
+
+/// Captures a photo (or a burst of photos) using the specified camera and saves it to a configurable path.
+pub async fn take_photo(
+    app_handle: &AppHandle,
+    camera_id: u32,
+    save_path: Option<String>,
+    options: PhotoOptions,
+) -> Result<Vec<String>, CameraError> {
+    capture_photo(app_handle.clone(), camera_id, save_path, options, false).await
+}
+
+/// Captures photos from several cameras concurrently.
+/// A failing camera is reported in its own result and does not abort the others.
+pub async fn take_photos(
+    app_handle: &AppHandle,
+    camera_ids: &[u32],
+    save_path: Option<String>,
+    options: PhotoOptions,
+) -> Vec<(u32, Result<Vec<String>, CameraError>)> {
+    let tag_with_camera_id = camera_ids.len() > 1;
+    let handles: Vec<_> = camera_ids
+        .iter()
+        .map(|&camera_id| {
+            let save_path = save_path.clone();
+            (
+                camera_id,
+                tokio::spawn(capture_photo(
+                    app_handle.clone(),
+                    camera_id,
+                    save_path,
+                    options,
+                    tag_with_camera_id,
+                )),
+            )
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(handles.len());
+    for (camera_id, handle) in handles {
+        let result = handle
+            .await
+            .unwrap_or_else(|e| Err(CameraError::Other(format!("Task join error: {}", e))));
+        results.push((camera_id, result));
+    }
+    results
+}
+
+async fn capture_photo(
+    app_handle: AppHandle,
+    camera_id: u32,
+    save_path: Option<String>,
+    options: PhotoOptions,
+    tag_with_camera_id: bool,
+) -> Result<Vec<String>, CameraError> {
     println!("Starting async photo capture with camera ID: {}", camera_id);
 
-    tokio::task::spawn_blocking(move || {
+    let capture = tokio::task::spawn_blocking(move || {
+        if !has_cameras() {
+            return Err(CameraError::NotFound);
+        }
+
         let camera_info = validate_camera_id(camera_id)?;
         println!(
             "Using camera: {} (ID: {})",
@@ -271,44 +686,91 @@ pub async fn take_photo(camera_id: u32, save_path: Option<String>) -> Result<Str
             camera_id
         );
 
-        let camera = init_camera(camera_id)?;
-        let mut camera_guard = CameraGuard::new(camera);
+        let frames = capture_frames_with_busy_retry(&app_handle, camera_id, &options)?;
 
-        let (width, height, raw_buffer) = {
-            let cam = camera_guard
-                .get_mut()
-                .ok_or("Camera guard failed to provide camera reference")?;
-            capture_frame(cam, camera_id)?
-        };
+        let base_path = get_save_path(save_path)?;
+        // 连拍的所有照片使用第一张的时间戳命名，便于归为一组
+        let timestamp = frames[0].captured_at.format("%Y%m%d_%H%M%S").to_string();
+        let is_burst = options.burst_count > 1;
+        let mut photo_paths = Vec::with_capacity(frames.len());
+        for (index, frame) in frames.into_iter().enumerate() {
+            let mut rgb_image: RgbImage =
+                ImageBuffer::from_raw(frame.width, frame.height, frame.raw_buffer)
+                    .ok_or("Failed to create image buffer from raw data")?;
+            // 在编码前绘制，所有保存格式都带有水印
+            if options.watermark_enabled {
+                crate::watermark::draw_timestamp(
+                    &mut rgb_image,
+                    &frame.captured_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+                );
+            }
 
-        let rgb_image: RgbImage = ImageBuffer::from_raw(width, height, raw_buffer)
-            .ok_or("Failed to create image buffer from raw data")?;
+            let filename = photo_filename(
+                &timestamp,
+                tag_with_camera_id.then_some(camera_id),
+                is_burst.then_some(index + 1),
+                options.image_format.extension(),
+            );
+            let filepath = base_path.join(&filename);
 
-        let base_path = get_save_path(save_path)?;
-        let timestamp = Local::now().format("%Y%m%d_%H%M%S");
-        let filename = format!("snaplock_capture_{}.jpg", timestamp);
-        let filepath = base_path.join(&filename);
+            println!("Saving image to: {}", filepath.display());
+            let exif_segment = crate::exif::capture_segment(
+                &frame.captured_at.format("%Y:%m:%d %H:%M:%S").to_string(),
+                &camera_info.human_name(),
+            );
+            save_photo(&rgb_image, &filepath, &options, &exif_segment)?;
+            photo_paths.push(filepath.to_string_lossy().to_string());
+        }
 
-        println!("Saving image to: {}", filepath.display());
-        rgb_image
-            .save(&filepath)
-            .map_err(|e| format!("Failed to save image to '{}': {}", filepath.display(), e))?;
+        Ok(photo_paths)
+    });
 
-        Ok(filepath.to_string_lossy().to_string())
-    })
-    .await
-    .map_err(|e| format!("Task join error: {}", e))?
+    // 阻塞任务无法被取消；超时后任其在后台结束，调用方继续执行锁屏与通知
+    let timeout_ms = options.effective_timeout_ms();
+    let joined = if timeout_ms == 0 {
+        capture.await
+    } else {
+        match tokio::time::timeout(std::time::Duration::from_millis(timeout_ms), capture).await {
+            Ok(joined) => joined,
+            Err(_) => {
+                log::error!(
+                    "摄像头 {} 在 {} ms 内未返回画面，放弃本次拍照",
+                    camera_id,
+                    timeout_ms
+                );
+                return Err(CameraError::TimedOut(timeout_ms));
+            }
+        }
+    };
+
+    joined.map_err(|e| CameraError::Other(format!("Task join error: {}", e)))?
 }
 
+use crate::config::{CameraFourcc, ImageFormat};
+use crate::constants::{
+    CAPTURE_DELAY_RETRY_INTERVAL, DEFAULT_PREVIEW_WIDTH, MAX_PREVIEW_WIDTH,
+    PHOTO_WARMUP_FRAME_INTERVAL,
+};
 use crate::state::AppState;
 
-/// Sets the custom save path for photos.
+/// Sets the custom save path for photos after checking that it is usable.
 #[command]
-pub fn set_save_path(path: String, app_handle: tauri::AppHandle) -> Result<(), String> {
+pub async fn set_save_path(path: String, app_handle: tauri::AppHandle) -> Result<(), String> {
+    let credentials = app_handle.state::<AppState>().share_credentials();
+    // 连接共享与可写性检查都可能等待网络，放到阻塞线程中执行
+    let validated_path = tokio::task::spawn_blocking(move || {
+        // 需要认证的网络共享必须先连接才能通过可写性检查
+        if let Some(credentials) = credentials {
+            crate::network_share::ensure_connected(&path, &credentials)?;
+        }
+        crate::storage::validate_save_path(&path)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))??;
     let state = app_handle.state::<AppState>();
     let previous_path = state.save_path();
     let previous_effective_path = state.get_effective_save_path();
-    let next_path = path.clone();
+    let next_path = validated_path.to_string_lossy().into_owned();
 
     crate::config::apply_state_change(
         &app_handle,
@@ -333,34 +795,160 @@ pub fn set_save_path(path: String, app_handle: tauri::AppHandle) -> Result<(), S
     Ok(())
 }
 
+/// 按分辨率、帧率从高到低排序并去重，便于前端直接渲染选择列表
+fn sort_camera_formats(mut formats: Vec<CameraFormatItem>) -> Vec<CameraFormatItem> {
+    formats.sort_by(|a, b| {
+        (b.width * b.height, b.frame_rate, &a.fourcc).cmp(&(
+            a.width * a.height,
+            a.frame_rate,
+            &b.fourcc,
+        ))
+    });
+    formats.dedup();
+    formats
+}
+
+/// 查询摄像头支持的分辨率、帧率与像素格式（不开启取流，查询后立即释放摄像头）
+#[command]
+pub async fn get_camera_formats(camera_id: u32) -> Result<Vec<CameraFormatItem>, String> {
+    tokio::task::spawn_blocking(move || {
+        // 实时预览占用摄像头时无法查询
+        crate::preview::stop();
+        let mut camera = init_camera(camera_id, CameraFourcc::Auto)?;
+        let fourccs = camera.compatible_fourcc().map_err(|e| {
+            format!(
+                "Failed to query pixel formats for camera ID {}: {}",
+                camera_id, e
+            )
+        })?;
+        let formats = camera
+            .compatible_camera_formats()
+            .map_err(|e| format!("Failed to query formats for camera ID {}: {}", camera_id, e))?;
+        drop(camera);
+
+        let formats: Vec<CameraFormatItem> = formats
+            .into_iter()
+            .filter(|format| fourccs.contains(&format.format()))
+            .map(|format| CameraFormatItem {
+                width: format.resolution().width(),
+                height: format.resolution().height(),
+                frame_rate: format.frame_rate(),
+                fourcc: format.format().to_string(),
+            })
+            .collect();
+        log::debug!("摄像头 {} 支持 {} 种格式", camera_id, formats.len());
+        Ok(sort_camera_formats(formats))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// 确认摄像头支持指定的像素格式，`Auto` 总是可用
+pub async fn ensure_fourcc_supported(camera_id: u32, fourcc: CameraFourcc) -> Result<(), String> {
+    let Some(formats) = frame_formats(fourcc) else {
+        return Ok(());
+    };
+
+    tokio::task::spawn_blocking(move || {
+        crate::preview::stop();
+        let mut camera = init_camera(camera_id, CameraFourcc::Auto)?;
+        let supported = camera.compatible_fourcc().map_err(|e| {
+            format!(
+                "Failed to query pixel formats for camera ID {}: {}",
+                camera_id, e
+            )
+        })?;
+        if formats.iter().any(|format| supported.contains(format)) {
+            Ok(())
+        } else {
+            Err(format!("摄像头 {} 不支持 {:?} 像素格式", camera_id, fourcc))
+        }
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
 /// 检查相机权限
 #[command]
 pub async fn check_camera_permission(camera_id: u32) -> Result<bool, String> {
-    tokio::task::spawn_blocking(move || match init_camera(camera_id) {
-        Ok(mut camera) => match camera.open_stream() {
-            Ok(_) => {
-                let _ = camera.stop_stream();
-                Ok(true)
-            }
+    tokio::task::spawn_blocking(move || {
+        crate::preview::stop();
+        match init_camera(camera_id, CameraFourcc::Auto) {
+            Ok(mut camera) => match camera.open_stream() {
+                Ok(_) => {
+                    let _ = camera.stop_stream();
+                    Ok(true)
+                }
+                Err(e) => {
+                    log::warn!("相机权限检查失败: {}", e);
+                    Ok(false)
+                }
+            },
             Err(e) => {
-                log::warn!("相机权限检查失败: {}", e);
+                log::warn!("相机初始化失败: {}", e);
                 Ok(false)
             }
-        },
-        Err(e) => {
-            log::warn!("相机初始化失败: {}", e);
-            Ok(false)
         }
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))?
 }
 
-/// 获取相机预览帧（base64编码的JPEG）
+/// 预览尺寸：宽度不超过 `max_width` 且不放大，高度按原画面比例计算（至少 1 像素）
+fn preview_size(width: u32, height: u32, max_width: u32) -> (u32, u32) {
+    let target_width = max_width.clamp(1, MAX_PREVIEW_WIDTH).min(width).max(1);
+    let target_height =
+        (u64::from(height) * u64::from(target_width) / u64::from(width.max(1))).max(1) as u32;
+    (target_width, target_height)
+}
+
+/// 将帧缩小为预览尺寸并编码为 base64 JPEG data URI
+pub(crate) fn encode_preview(
+    image: &RgbImage,
+    max_width: u32,
+    jpeg_quality: u8,
+) -> Result<String, String> {
+    let (width, height) = preview_size(image.width(), image.height(), max_width);
+    // 调整图像大小以减少数据传输
+    let preview_image =
+        image::imageops::resize(image, width, height, image::imageops::FilterType::Lanczos3);
+
+    // 转换为JPEG格式
+    let mut jpeg_buffer = Vec::new();
+    {
+        let mut cursor = Cursor::new(&mut jpeg_buffer);
+        JpegEncoder::new_with_quality(&mut cursor, jpeg_quality)
+            .encode_image(&preview_image)
+            .map_err(|e| format!("Failed to encode preview as JPEG: {}", e))?;
+    }
+
+    // 转换为base64
+    let base64_image = general_purpose::STANDARD.encode(&jpeg_buffer);
+    Ok(format!("data:image/jpeg;base64,{}", base64_image))
+}
+
+/// 获取相机预览帧（base64编码的JPEG）；`max_width` 默认 320，保持原画面比例
 #[command]
-pub async fn get_camera_preview(camera_id: u32) -> Result<String, String> {
+pub async fn get_camera_preview(
+    app_handle: AppHandle,
+    camera_id: u32,
+    max_width: Option<u32>,
+) -> Result<String, String> {
+    let state = app_handle.state::<AppState>();
+    let jpeg_quality = state.jpeg_quality();
+    let fourcc = state.camera_fourcc();
+    let max_width = max_width.unwrap_or(DEFAULT_PREVIEW_WIDTH);
+
+    // 实时预览正在推送同一摄像头的画面时直接复用，不重新打开摄像头
+    if max_width == DEFAULT_PREVIEW_WIDTH {
+        if let Some(frame) = crate::preview::latest_frame(camera_id) {
+            return Ok(frame);
+        }
+    }
+
     tokio::task::spawn_blocking(move || {
-        let camera = init_camera(camera_id)?;
+        crate::preview::stop();
+        let camera = init_camera(camera_id, fourcc)?;
         let mut camera_guard = CameraGuard::new(camera);
 
         let (width, height, raw_buffer) = {
@@ -383,194 +971,140 @@ pub async fn get_camera_preview(camera_id: u32) -> Result<String, String> {
         let rgb_image: RgbImage = ImageBuffer::from_raw(width, height, raw_buffer)
             .ok_or("Failed to create image buffer from raw preview data")?;
 
-        // 调整图像大小以减少数据传输
-        let preview_image =
-            image::imageops::resize(&rgb_image, 320, 240, image::imageops::FilterType::Lanczos3);
-
-        // 转换为JPEG格式
-        let mut jpeg_buffer = Vec::new();
-        {
-            let mut cursor = Cursor::new(&mut jpeg_buffer);
-            preview_image
-                .write_to(&mut cursor, image::ImageFormat::Jpeg)
-                .map_err(|e| format!("Failed to encode preview as JPEG: {}", e))?;
-        }
-
-        // 转换为base64
-        let base64_image = general_purpose::STANDARD.encode(&jpeg_buffer);
-        Ok(format!("data:image/jpeg;base64,{}", base64_image))
+        encode_preview(&rgb_image, max_width, jpeg_quality)
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))?
 }
 
-/// 开始录像
-pub async fn start_video_recording(
-    app_handle: AppHandle,
-    camera_id: u32,
-    save_path: Option<String>,
-    duration_seconds: Option<u32>,
-) -> Result<String, String> {
-    tokio::task::spawn_blocking(move || {
-        validate_camera_id(camera_id)?;
-
-        {
-            let mut processes = VIDEO_PROCESSES.lock().unwrap();
-            prune_finished_video_processes(&mut processes);
-            if processes.contains_key(&camera_id) {
-                return Err(format!("Camera {} is already recording", camera_id));
-            }
-        }
-
-        let base_path = get_save_path(save_path)?;
-        let timestamp = Local::now().format("%Y%m%d_%H%M%S");
-        let filename = format!("snaplock_video_{}.mkv", timestamp);
-        let filepath = base_path.join(&filename);
+#[cfg(test)]
+mod tests {
+    use super::{
+        CameraFormatItem, PhotoOptions, encode_photo, frame_formats, is_device_busy_error,
+        photo_filename, preview_size, sort_camera_formats,
+    };
+    use crate::config::{CameraFourcc, ImageFormat};
+    use nokhwa::utils::FrameFormat;
+
+    #[test]
+    fn detects_device_busy_errors() {
+        assert!(is_device_busy_error(
+            "Failed to open stream for camera ID 0: Device or resource busy"
+        ));
+        assert!(is_device_busy_error("HRESULT 0xC00D3704"));
+        assert!(!is_device_busy_error(
+            "Camera ID 3 not found. Available camera IDs: [0]"
+        ));
+    }
 
-        let ffmpeg_path = match app_handle.path().resolve(
-            "libs/ffmpeg/bin/ffmpeg.exe",
-            tauri::path::BaseDirectory::Resource,
-        ) {
-            Ok(path) => path,
-            Err(e) => return Err(format!("无法解析ffmpeg路径: {}", e)),
+    #[test]
+    fn capture_timeout_includes_warmup_and_capture_delay() {
+        let options = PhotoOptions {
+            jpeg_quality: 90,
+            busy_retry_attempts: 0,
+            busy_retry_backoff_ms: 0,
+            capture_timeout_ms: 5_000,
+            fourcc: CameraFourcc::Auto,
+            capture_delay_seconds: 3,
+            warmup_frames: 5,
+            image_format: ImageFormat::Jpeg,
+            burst_count: 3,
+            burst_interval_ms: 500,
+            watermark_enabled: true,
         };
-
-        let result = try_simple_recording(
-            &ffmpeg_path.to_string_lossy(),
-            camera_id,
-            &filepath,
-            duration_seconds,
-        );
-
-        match result {
-            Ok(mut child) => {
-                if let Err(error) =
-                    crate::process_utils::assign_child_to_kill_on_close_job(&mut child)
-                {
-                    log::error!("无法将摄像头录像进程纳入 Job Object: {}", error);
-                    crate::process_utils::terminate_child_process(&mut child, "camera ffmpeg");
-                    return Err(error);
-                }
-
-                let mut processes = VIDEO_PROCESSES.lock().unwrap();
-                prune_finished_video_processes(&mut processes);
-                if processes.contains_key(&camera_id) {
-                    crate::process_utils::terminate_child_process(&mut child, "camera ffmpeg");
-                    return Err(format!("Camera {} is already recording", camera_id));
-                }
-                processes.insert(camera_id, child);
-
-                println!(
-                    "Started video recording for camera {} to: {}",
-                    camera_id,
-                    filepath.display()
-                );
-                Ok(filepath.to_string_lossy().to_string())
+        assert_eq!(options.effective_timeout_ms(), 9_500);
+        assert_eq!(
+            PhotoOptions {
+                capture_timeout_ms: 0,
+                ..options
             }
-            Err(e) => Err(format!("Failed to start video recording: {}", e)),
-        }
-    })
-    .await
-    .map_err(|e| format!("Task join error: {}", e))?
-}
-
-/// 尝试使用简化的录像命令
-fn try_simple_recording(
-    ffmpeg_path: &str,
-    camera_id: u32,
-    filepath: &PathBuf,
-    duration_seconds: Option<u32>,
-) -> Result<Child, String> {
-    let duration = duration_seconds.unwrap_or(5);
-    let mut command = Command::new(ffmpeg_path);
-
-    if cfg!(target_os = "windows") {
-        let camera_info = validate_camera_id(camera_id)?;
-        let device_name = camera_info.human_name();
-
-        command
-            .arg("-f")
-            .arg("dshow")
-            .arg("-i")
-            .arg(format!("video={}", device_name))
-            .arg("-c:v")
-            .arg("libx264")
-            .arg("-preset")
-            .arg("ultrafast")
-            .arg("-crf")
-            .arg("25")
-            .arg("-pix_fmt")
-            .arg("yuv420p")
-            .arg("-t")
-            .arg(&duration.to_string())
-            .arg("-y")
-            .arg(filepath);
-    } else {
-        command
-            .arg("-f")
-            .arg("v4l2")
-            .arg("-i")
-            .arg(format!("/dev/video{}", camera_id))
-            .arg("-c:v")
-            .arg("libx264")
-            .arg("-preset")
-            .arg("ultrafast")
-            .arg("-crf")
-            .arg("25")
-            .arg("-pix_fmt")
-            .arg("yuv420p")
-            .arg("-t")
-            .arg(&duration.to_string())
-            .arg("-y")
-            .arg(filepath);
-    }
-
-    println!("Trying simple recording command: {:?}", command);
-    crate::process_utils::configure_background_command(&mut command);
-    #[cfg(all(windows, not(debug_assertions)))]
-    {
-        command
-            .stdin(Stdio::null())
-            .stdout(Stdio::null())
-            .stderr(Stdio::null());
+            .effective_timeout_ms(),
+            0
+        );
     }
 
-    #[cfg(not(all(windows, not(debug_assertions))))]
-    {
-        command.stdout(Stdio::piped()).stderr(Stdio::piped());
+    #[test]
+    fn burst_photos_get_incrementing_suffixes() {
+        assert_eq!(
+            photo_filename("20240307_120000", None, None, "jpg"),
+            "snaplock_capture_20240307_120000.jpg"
+        );
+        assert_eq!(
+            photo_filename("20240307_120000", None, Some(2), "png"),
+            "snaplock_capture_20240307_120000_2.png"
+        );
+        assert_eq!(
+            photo_filename("20240307_120000", Some(1), Some(3), "jpg"),
+            "snaplock_capture_20240307_120000_cam1_3.jpg"
+        );
     }
 
-    command.spawn().map_err(|e| {
-        let cmd_str = format!("{:?}", command);
-        format!(
-            "Failed to start ffmpeg process: {}. Command: {}",
-            e, cmd_str
-        )
-    })
-}
-
-/// 停止录像
-pub async fn stop_video_recording() -> Result<(), String> {
-    tokio::task::spawn_blocking(move || {
-        let mut processes_guard = VIDEO_PROCESSES.lock().unwrap();
-        prune_finished_video_processes(&mut processes_guard);
-
-        if processes_guard.is_empty() {
-            println!("No active camera recordings to stop");
-            return Ok(());
+    #[test]
+    fn encoded_photos_decode_in_every_format() {
+        let image =
+            image::RgbImage::from_fn(16, 8, |x, y| image::Rgb([x as u8 * 16, y as u8 * 32, 128]));
+        for (format, expected) in [
+            (ImageFormat::Jpeg, image::ImageFormat::Jpeg),
+            (ImageFormat::Png, image::ImageFormat::Png),
+            (ImageFormat::WebP, image::ImageFormat::WebP),
+        ] {
+            let encoded = encode_photo(&image, format, 90, &[]).unwrap();
+            assert_eq!(image::guess_format(&encoded).unwrap(), expected);
+            let decoded = image::load_from_memory_with_format(&encoded, expected).unwrap();
+            assert_eq!((decoded.width(), decoded.height()), (16, 8));
         }
+    }
 
-        let mut processes = std::mem::take(&mut *processes_guard);
-        drop(processes_guard);
+    #[test]
+    fn camera_formats_are_sorted_and_deduplicated() {
+        let format = |width, height, frame_rate, fourcc: &str| CameraFormatItem {
+            width,
+            height,
+            frame_rate,
+            fourcc: fourcc.to_string(),
+        };
+        let sorted = sort_camera_formats(vec![
+            format(640, 480, 30, "YUYV"),
+            format(1920, 1080, 30, "MJPEG"),
+            format(1920, 1080, 60, "MJPEG"),
+            format(640, 480, 30, "YUYV"),
+            format(640, 480, 30, "MJPEG"),
+        ]);
+        assert_eq!(
+            sorted,
+            vec![
+                format(1920, 1080, 60, "MJPEG"),
+                format(1920, 1080, 30, "MJPEG"),
+                format(640, 480, 30, "MJPEG"),
+                format(640, 480, 30, "YUYV"),
+            ]
+        );
+    }
 
-        for (camera_id, mut child) in processes.drain() {
-            println!("Stopping video recording for camera {}", camera_id);
-            crate::process_utils::terminate_child_process(&mut child, "camera ffmpeg");
-        }
+    #[test]
+    fn fourcc_maps_to_requested_frame_format() {
+        assert_eq!(frame_formats(CameraFourcc::Auto), None);
+        assert_eq!(
+            frame_formats(CameraFourcc::Mjpg),
+            Some(&[FrameFormat::MJPEG][..])
+        );
+        assert_eq!(
+            frame_formats(CameraFourcc::Yuy2),
+            Some(&[FrameFormat::YUYV][..])
+        );
+        assert_eq!(
+            frame_formats(CameraFourcc::Nv12),
+            Some(&[FrameFormat::NV12][..])
+        );
+    }
 
-        println!("All video recordings stopped");
-        Ok(())
-    })
-    .await
-    .map_err(|e| format!("Task join error: {}", e))?
+    #[test]
+    fn preview_keeps_source_aspect_ratio() {
+        assert_eq!(preview_size(1920, 1080, 320), (320, 180));
+        assert_eq!(preview_size(640, 480, 320), (320, 240));
+        // 不放大小于目标宽度的画面
+        assert_eq!(preview_size(160, 120, 320), (160, 120));
+        assert_eq!(preview_size(1920, 1080, 100_000), (1920, 1080));
+        assert_eq!(preview_size(4000, 1, 320), (320, 1));
+    }
 }
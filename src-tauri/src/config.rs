@@ -3,8 +3,99 @@ use std::fs;
 use std::path::PathBuf;
 use tauri::{AppHandle, Manager};
 
+use crate::constants::{
+    DEFAULT_ARM_SETTLE_MS, DEFAULT_CAMERA_BUSY_RETRY_ATTEMPTS,
+    DEFAULT_CAMERA_BUSY_RETRY_BACKOFF_MS, DEFAULT_CAMERA_RECORDING_SECONDS,
+    DEFAULT_CAPTURE_TIMEOUT_MS, DEFAULT_EVENT_HISTORY_LIMIT, DEFAULT_EVENT_IGNORE_WINDOW_MS,
+    DEFAULT_IDLE_TRIGGER_MINUTES, DEFAULT_JPEG_QUALITY, DEFAULT_LOCAL_API_PORT,
+    DEFAULT_MOTION_SAMPLE_INTERVAL_MS, DEFAULT_MOTION_THRESHOLD_PERCENT,
+    DEFAULT_PHOTO_WARMUP_FRAMES, DEFAULT_PREPARATION_DELAY_SECONDS,
+    DEFAULT_RECORD_BEFORE_LOCK_SECONDS, DEFAULT_RECORDING_BITRATE_KBPS,
+    DEFAULT_RECORDING_FRAMERATE, DEFAULT_RECORDING_IDLE_TIMEOUT_SECS,
+    DEFAULT_RECORDING_SCALE_DIVISOR, DEFAULT_RETRIGGER_COOLDOWN_MS, DEFAULT_SHORTCUT_DEBOUNCE_MS,
+    DEFAULT_SHORTCUT_FLAG_CLEAR_DELAY_MS, DEFAULT_SHORTCUT_RETRY_ATTEMPTS,
+    DEFAULT_SHORTCUT_RETRY_INTERVAL_MS, DEFAULT_SMTP_PORT, MAX_BURST_COUNT, MAX_BURST_INTERVAL_MS,
+    MAX_CAMERA_BUSY_RETRY_ATTEMPTS, MAX_CAMERA_RECORDING_SECONDS, MAX_CAPTURE_TIMEOUT_MS,
+    MAX_EVENT_HISTORY_LIMIT, MAX_IDLE_TRIGGER_MINUTES, MAX_PHOTO_WARMUP_FRAMES,
+    MAX_PREBUFFER_SECONDS, MAX_PREPARATION_DELAY_SECONDS, MAX_RECORD_BEFORE_LOCK_SECONDS,
+    MAX_RECORDING_DURATION_SECONDS, MAX_RECORDING_IDLE_TIMEOUT_SECS, MIN_BURST_INTERVAL_MS,
+    MIN_MOTION_SAMPLE_INTERVAL_MS, RECORDING_BITRATE_KBPS_RANGE, RECORDING_FRAMERATE_RANGE,
+    RECORDING_SCALE_DIVISOR_RANGE,
+};
+use crate::i18n::Language;
+
 const MAX_CAPTURE_DELAY_SECONDS: u32 = 60;
 
+/// 当前配置文件格式版本。修改字段含义、重命名或移动字段时递增，并在
+/// `CONFIG_MIGRATIONS` 末尾追加对应的升级步骤
+pub const CONFIG_VERSION: u32 = 1;
+
+type ConfigMigration = fn(&mut serde_json::Map<String, serde_json::Value>);
+
+/// 按顺序排列的升级步骤，第 N 项把版本 N 的配置升级到版本 N + 1
+const CONFIG_MIGRATIONS: [ConfigMigration; CONFIG_VERSION as usize] = [migrate_v0_to_v1];
+
+/// 版本 0 为加入版本号之前的配置，缺失的字段均可由 serde 默认值补齐，
+/// 旧的临时目录配置文件已由 `migrate_legacy_config_if_needed` 移动
+fn migrate_v0_to_v1(_config: &mut serde_json::Map<String, serde_json::Value>) {}
+
+/// 将配置 JSON 逐步升级到当前版本，返回升级前的版本号
+fn migrate_config_value(value: &mut serde_json::Value) -> Result<u32, String> {
+    let config = value
+        .as_object_mut()
+        .ok_or_else(|| "配置文件格式无效".to_string())?;
+    let original_version = config
+        .get("config_version")
+        .and_then(serde_json::Value::as_u64)
+        .unwrap_or(0) as u32;
+
+    if original_version > CONFIG_VERSION {
+        log::warn!(
+            "配置文件版本 {} 高于当前支持的版本 {}，将按当前版本读取",
+            original_version,
+            CONFIG_VERSION
+        );
+        return Ok(original_version);
+    }
+
+    for (version, migration) in CONFIG_MIGRATIONS
+        .iter()
+        .enumerate()
+        .skip(original_version as usize)
+    {
+        migration(config);
+        log::info!("配置已从版本 {} 升级到版本 {}", version, version + 1);
+    }
+    config.insert("config_version".to_string(), CONFIG_VERSION.into());
+    Ok(original_version)
+}
+
+/// 磁盘上的配置由更新版本写入时，保留其版本号与当前版本不认识的字段，
+/// 避免降级使用期间修改设置把配置悄悄降级并丢失新版本的设置
+fn keep_newer_config_fields(value: &mut serde_json::Value, existing_content: &str) {
+    let Ok(serde_json::Value::Object(existing)) =
+        serde_json::from_str::<serde_json::Value>(existing_content)
+    else {
+        return;
+    };
+    let existing_version = existing
+        .get("config_version")
+        .and_then(serde_json::Value::as_u64)
+        .unwrap_or(0);
+    if existing_version <= u64::from(CONFIG_VERSION) {
+        return;
+    }
+    let Some(config) = value.as_object_mut() else {
+        return;
+    };
+
+    for (key, field) in existing {
+        if key == "config_version" || !config.contains_key(&key) {
+            config.insert(key, field);
+        }
+    }
+}
+
 /// 触发后动作选项
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum PostTriggerAction {
@@ -14,6 +105,33 @@ pub enum PostTriggerAction {
     CaptureOnly,
     /// 屏幕录制
     ScreenRecording,
+    /// 先录制屏幕若干秒再锁屏。锁屏后 Windows 切换到安全桌面，gdigrab
+    /// 无法采集其画面，录像在锁屏期间只会重复最后一帧或为黑屏
+    RecordAndLock,
+    /// 用摄像头录制一段视频，不锁屏
+    RecordCamera,
+}
+
+impl PostTriggerAction {
+    /// 是否不论拍摄模式都录制摄像头视频
+    pub fn records_camera(&self) -> bool {
+        matches!(self, PostTriggerAction::RecordCamera)
+    }
+
+    /// 是否使用屏幕录制代替摄像头录像
+    pub fn records_screen(&self) -> bool {
+        matches!(
+            self,
+            PostTriggerAction::ScreenRecording | PostTriggerAction::RecordAndLock
+        )
+    }
+
+    pub fn locks_screen(&self) -> bool {
+        matches!(
+            self,
+            PostTriggerAction::CaptureAndLock | PostTriggerAction::RecordAndLock
+        )
+    }
 }
 
 impl Default for PostTriggerAction {
@@ -22,16 +140,261 @@ impl Default for PostTriggerAction {
     }
 }
 
-/// 拍摄模式选项
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+/// 触发方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum TriggerMode {
+    /// 检测到输入活动时触发
+    #[default]
+    Activity,
+    /// 超过设定时间无任何输入时触发（无人值守场景）
+    Idle,
+}
+
+/// 系统解锁后的处理方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum UnlockAction {
+    /// 停止录制并解除警戒（启用触发后重新警戒时仍会重新警戒）
+    #[default]
+    ResetToIdle,
+    /// 停止录制后重新经过准备期进入警戒
+    ReArm,
+    /// 保持当前状态不做处理
+    DoNothing,
+}
+
+/// 全局输入监听的实现方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum MonitoringBackend {
+    /// 跨平台的 rdev 监听
+    #[default]
+    Rdev,
+    /// Windows 低级键盘/鼠标钩子（其他平台回退到 rdev）
+    WindowsHook,
+}
+
+/// 拍照与预录时向摄像头请求的像素格式（fourcc），取帧后统一解码为 RGB
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum CameraFourcc {
+    /// 由 nokhwa 按最高分辨率自动选择
+    #[default]
+    Auto,
+    /// Motion JPEG，部分摄像头在高分辨率下出帧明显更快
+    Mjpg,
+    /// YUY2 (YUYV 4:2:2)
+    Yuy2,
+    /// NV12 (YUV 4:2:0)
+    Nv12,
+}
+
+/// 拍摄照片的保存格式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum ImageFormat {
+    /// 有损压缩，质量由 `jpeg_quality` 控制，并写入 EXIF 拍摄信息
+    #[default]
+    Jpeg,
+    /// 无损压缩，文件较大
+    Png,
+    /// 无损 WebP
+    WebP,
+}
+
+impl ImageFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "jpg",
+            ImageFormat::Png => "png",
+            ImageFormat::WebP => "webp",
+        }
+    }
+}
+
+/// 拍摄模式选项（屏幕录制动作下不生效）
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum CaptureMode {
-    /// 录像模式
+    /// 只拍照
+    PhotoOnly,
+    /// 摄像头录像，时长为拍摄延迟设置（为 0 时退化为拍照）
     Video,
+    /// 先拍照再录像
+    PhotoThenVideo,
+    /// 连拍：摄像头保持打开，按间隔连续拍摄多张
+    Burst { count: u32, interval_ms: u64 },
 }
 
 impl Default for CaptureMode {
     fn default() -> Self {
-        CaptureMode::Video
+        CaptureMode::PhotoOnly
+    }
+}
+
+impl CaptureMode {
+    pub fn takes_photo(self) -> bool {
+        matches!(
+            self,
+            CaptureMode::PhotoOnly | CaptureMode::PhotoThenVideo | CaptureMode::Burst { .. }
+        )
+    }
+
+    /// 每次拍照的张数与间隔毫秒数，非连拍模式为单张
+    pub fn photo_shots(self) -> (u32, u64) {
+        match self {
+            CaptureMode::Burst { count, interval_ms } => (count.max(1), interval_ms),
+            _ => (1, 0),
+        }
+    }
+
+    pub fn records_video(self) -> bool {
+        matches!(self, CaptureMode::Video | CaptureMode::PhotoThenVideo)
+    }
+}
+
+/// 屏幕录制区域选项
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RecordingRegion {
+    /// 整个虚拟桌面（所有显示器）
+    FullDesktop,
+    /// 仅主显示器
+    PrimaryMonitor,
+    /// 按系统枚举顺序指定的显示器（从 0 开始）
+    Monitor { index: u32 },
+    /// 自定义区域（虚拟桌面坐标）
+    Custom {
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+    },
+}
+
+impl Default for RecordingRegion {
+    fn default() -> Self {
+        RecordingRegion::FullDesktop
+    }
+}
+
+/// 主窗口位置与大小（物理像素）
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 本地 HTTP 接口设置，只监听 127.0.0.1 且请求必须携带令牌
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LocalApiConfig {
+    pub enabled: bool,
+    pub port: u16,
+    pub token: Option<String>,
+}
+
+impl Default for LocalApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_LOCAL_API_PORT,
+            token: None,
+        }
+    }
+}
+
+/// 触发时发送告警邮件所用的 SMTP 设置；端口 465 使用隐式 TLS，其他端口使用 STARTTLS
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SmtpConfig {
+    #[serde(default)]
+    pub host: Option<String>,
+    #[serde(default = "default_smtp_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub alert_email_to: Option<String>,
+}
+
+/// 返回给前端的 SMTP 设置，不包含密码本身
+#[derive(Debug, Clone, Serialize)]
+pub struct SmtpConfigInfo {
+    pub host: Option<String>,
+    pub port: u16,
+    pub username: Option<String>,
+    pub has_password: bool,
+    pub alert_email_to: Option<String>,
+}
+
+impl From<&SmtpConfig> for SmtpConfigInfo {
+    fn from(config: &SmtpConfig) -> Self {
+        Self {
+            host: config.host.clone(),
+            port: config.port,
+            username: config.username.clone(),
+            has_password: config.password.is_some(),
+            alert_email_to: config.alert_email_to.clone(),
+        }
+    }
+}
+
+/// 为 SMTP 端口提供默认值
+fn default_smtp_port() -> u16 {
+    DEFAULT_SMTP_PORT
+}
+
+impl SmtpConfig {
+    /// 至少需要服务器与收件人才能发送
+    pub fn is_complete(&self) -> bool {
+        self.host.is_some() && self.alert_email_to.is_some()
+    }
+}
+
+impl Default for SmtpConfig {
+    fn default() -> Self {
+        Self {
+            host: None,
+            port: DEFAULT_SMTP_PORT,
+            username: None,
+            password: None,
+            alert_email_to: None,
+        }
+    }
+}
+
+/// 密码不出现在日志中
+impl std::fmt::Debug for SmtpConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SmtpConfig")
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| "***"))
+            .field("alert_email_to", &self.alert_email_to)
+            .finish()
+    }
+}
+
+/// 保存目录为网络共享（UNC 路径）时使用的 SMB 凭据
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NetworkShareCredentials {
+    pub username: String,
+    /// 写入配置文件时加密，见 `secret_store`
+    #[serde(with = "crate::secret_store::protected")]
+    pub password: String,
+}
+
+/// 返回给前端的网络共享凭据，不包含密码本身
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkShareCredentialsInfo {
+    pub username: String,
+    pub has_password: bool,
+}
+
+impl From<&NetworkShareCredentials> for NetworkShareCredentialsInfo {
+    fn from(credentials: &NetworkShareCredentials) -> Self {
+        Self {
+            username: credentials.username.clone(),
+            has_password: !credentials.password.is_empty(),
+        }
     }
 }
 
@@ -45,10 +408,291 @@ fn default_capture_delay_seconds() -> u32 {
     0
 }
 
+/// 为录制分段时长提供默认值（0 表示不分段）
+fn default_max_segment_seconds() -> u32 {
+    0
+}
+
+/// 为最小剩余磁盘空间提供默认值（MB）
+fn default_min_free_mb() -> u64 {
+    500
+}
+
+/// 为警戒准备时间提供默认值
+fn default_preparation_delay_seconds() -> u32 {
+    DEFAULT_PREPARATION_DELAY_SECONDS
+}
+
+/// 为重复触发冷却时间提供默认值
+fn default_retrigger_cooldown_ms() -> u64 {
+    DEFAULT_RETRIGGER_COOLDOWN_MS
+}
+
+/// 各输入来源默认都会触发
+fn default_trigger_source_enabled() -> bool {
+    true
+}
+
+/// 为关闭窗口时最小化到托盘提供默认值
+fn default_close_to_tray() -> bool {
+    true
+}
+
+/// 为照片 JPEG 质量提供默认值
+fn default_jpeg_quality() -> u8 {
+    DEFAULT_JPEG_QUALITY
+}
+
+/// 为摄像头占用重试次数提供默认值
+fn default_camera_busy_retry_attempts() -> u32 {
+    DEFAULT_CAMERA_BUSY_RETRY_ATTEMPTS
+}
+
+/// 为摄像头占用重试间隔提供默认值
+fn default_camera_busy_retry_backoff_ms() -> u64 {
+    DEFAULT_CAMERA_BUSY_RETRY_BACKOFF_MS
+}
+
+/// 为拍照超时时间提供默认值
+fn default_capture_timeout_ms() -> u64 {
+    DEFAULT_CAPTURE_TIMEOUT_MS
+}
+
+/// 为无活动触发阈值提供默认值
+fn default_idle_trigger_minutes() -> u32 {
+    DEFAULT_IDLE_TRIGGER_MINUTES
+}
+
+/// 为录屏并锁屏模式下锁屏前的录制时长提供默认值
+fn default_record_before_lock_seconds() -> u32 {
+    DEFAULT_RECORD_BEFORE_LOCK_SECONDS
+}
+
+/// 为快捷键防抖时间提供默认值
+fn default_shortcut_debounce_ms() -> u64 {
+    DEFAULT_SHORTCUT_DEBOUNCE_MS
+}
+
+/// 为快捷键处理标志清除延迟提供默认值
+fn default_shortcut_flag_clear_delay_ms() -> u64 {
+    DEFAULT_SHORTCUT_FLAG_CLEAR_DELAY_MS
+}
+
+/// 为快捷键后的输入忽略窗口提供默认值
+fn default_event_ignore_window_ms() -> u64 {
+    DEFAULT_EVENT_IGNORE_WINDOW_MS
+}
+
+/// 为警戒稳定期提供默认值
+fn default_arm_settle_ms() -> u64 {
+    DEFAULT_ARM_SETTLE_MS
+}
+
+/// 为运动检测阈值提供默认值
+fn default_motion_threshold_percent() -> u8 {
+    DEFAULT_MOTION_THRESHOLD_PERCENT
+}
+
+/// 为运动检测采样间隔提供默认值
+fn default_motion_sample_interval_ms() -> u64 {
+    DEFAULT_MOTION_SAMPLE_INTERVAL_MS
+}
+
+/// 画面变化阈值限制在 1% 到 100% 之间
+pub(crate) fn normalize_motion_threshold_percent(percent: u8) -> u8 {
+    percent.clamp(1, 100)
+}
+
+/// 采样间隔不低于 `MIN_MOTION_SAMPLE_INTERVAL_MS`
+pub(crate) fn normalize_motion_sample_interval_ms(interval_ms: u64) -> u64 {
+    interval_ms.max(MIN_MOTION_SAMPLE_INTERVAL_MS)
+}
+
+/// 为拍照预热帧数提供默认值
+fn default_photo_warmup_frames() -> u32 {
+    DEFAULT_PHOTO_WARMUP_FRAMES
+}
+
+/// 为屏幕录制空闲暂停时长提供默认值
+fn default_recording_idle_timeout_secs() -> u32 {
+    DEFAULT_RECORDING_IDLE_TIMEOUT_SECS
+}
+
+fn default_recording_framerate() -> u32 {
+    DEFAULT_RECORDING_FRAMERATE
+}
+
+fn default_recording_scale_divisor() -> u32 {
+    DEFAULT_RECORDING_SCALE_DIVISOR
+}
+
+fn default_recording_bitrate_kbps() -> u32 {
+    DEFAULT_RECORDING_BITRATE_KBPS
+}
+
+/// 为触发历史保留条数提供默认值
+fn default_event_history_limit() -> u32 {
+    DEFAULT_EVENT_HISTORY_LIMIT
+}
+
+/// 为触发后摄像头录像时长提供默认值
+fn default_camera_recording_seconds() -> u32 {
+    DEFAULT_CAMERA_RECORDING_SECONDS
+}
+
+/// 照片时间水印默认开启
+fn default_watermark_enabled() -> bool {
+    true
+}
+
+/// 为快捷键注册重试次数提供默认值
+fn default_shortcut_retry_attempts() -> u32 {
+    DEFAULT_SHORTCUT_RETRY_ATTEMPTS
+}
+
+/// 为快捷键注册重试间隔提供默认值
+fn default_shortcut_retry_interval_ms() -> u64 {
+    DEFAULT_SHORTCUT_RETRY_INTERVAL_MS
+}
+
+pub(crate) fn normalize_jpeg_quality(quality: u8) -> u8 {
+    quality.clamp(1, 100)
+}
+
+/// 按任意整数读取 JPEG 质量再限制到有效范围，手动写入超出 u8 的值不会让整个配置加载失败
+fn deserialize_jpeg_quality<'de, D>(deserializer: D) -> Result<u8, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let quality = i64::deserialize(deserializer)?;
+    Ok(quality.clamp(1, 100) as u8)
+}
+
+pub(crate) fn normalize_camera_busy_retry_attempts(attempts: u32) -> u32 {
+    attempts.min(MAX_CAMERA_BUSY_RETRY_ATTEMPTS)
+}
+
+/// 拍照超时时间不超过上限，0 表示不限制
+pub(crate) fn normalize_capture_timeout_ms(timeout_ms: u64) -> u64 {
+    timeout_ms.min(MAX_CAPTURE_TIMEOUT_MS)
+}
+
+/// 固定录制时长不超过上限，0 表示按活动持续录制
+pub(crate) fn normalize_recording_duration_seconds(seconds: u32) -> u32 {
+    seconds.min(MAX_RECORDING_DURATION_SECONDS)
+}
+
+/// 无活动触发时间限制在 1 分钟到上限之间，0 会在警戒后立即触发
+pub(crate) fn normalize_idle_trigger_minutes(minutes: u32) -> u32 {
+    minutes.clamp(1, MAX_IDLE_TRIGGER_MINUTES)
+}
+
+/// 锁屏前录制时长限制在 1 秒到上限之间，0 会让录屏并锁屏模式的录制没有终点
+pub(crate) fn normalize_record_before_lock_seconds(seconds: u32) -> u32 {
+    seconds.clamp(1, MAX_RECORD_BEFORE_LOCK_SECONDS)
+}
+
+pub(crate) fn normalize_photo_warmup_frames(frames: u32) -> u32 {
+    frames.min(MAX_PHOTO_WARMUP_FRAMES)
+}
+
+pub(crate) fn normalize_recording_idle_timeout(seconds: u32) -> u32 {
+    seconds.min(MAX_RECORDING_IDLE_TIMEOUT_SECS)
+}
+
+/// 屏幕录制帧率超出范围时恢复默认值
+pub(crate) fn normalize_recording_framerate(framerate: u32) -> u32 {
+    if RECORDING_FRAMERATE_RANGE.contains(&framerate) {
+        framerate
+    } else {
+        DEFAULT_RECORDING_FRAMERATE
+    }
+}
+
+pub(crate) fn normalize_recording_scale_divisor(divisor: u32) -> u32 {
+    if RECORDING_SCALE_DIVISOR_RANGE.contains(&divisor) {
+        divisor
+    } else {
+        DEFAULT_RECORDING_SCALE_DIVISOR
+    }
+}
+
+pub(crate) fn normalize_recording_bitrate_kbps(bitrate_kbps: u32) -> u32 {
+    if RECORDING_BITRATE_KBPS_RANGE.contains(&bitrate_kbps) {
+        bitrate_kbps
+    } else {
+        DEFAULT_RECORDING_BITRATE_KBPS
+    }
+}
+
+pub(crate) fn normalize_event_history_limit(limit: u32) -> u32 {
+    limit.clamp(1, MAX_EVENT_HISTORY_LIMIT)
+}
+
+/// 摄像头录像时长限制在 1 秒到上限之间
+pub(crate) fn normalize_camera_recording_seconds(seconds: u32) -> u32 {
+    seconds.clamp(1, MAX_CAMERA_RECORDING_SECONDS)
+}
+
+pub(crate) fn normalize_capture_mode(mode: CaptureMode) -> CaptureMode {
+    match mode {
+        CaptureMode::Burst { count, interval_ms } => CaptureMode::Burst {
+            count: count.clamp(1, MAX_BURST_COUNT),
+            interval_ms: interval_ms.clamp(MIN_BURST_INTERVAL_MS, MAX_BURST_INTERVAL_MS),
+        },
+        mode => mode,
+    }
+}
+
 fn normalize_capture_delay(delay: u32) -> u32 {
     delay.min(MAX_CAPTURE_DELAY_SECONDS)
 }
 
+fn normalize_preparation_delay(delay: u32) -> u32 {
+    delay.min(MAX_PREPARATION_DELAY_SECONDS)
+}
+
+/// 输入忽略窗口需短于警戒准备时间，否则准备期结束后仍会吞掉输入；与设置界面的校验一致
+fn normalize_event_ignore_window(event_ignore_window_ms: u64, preparation_delay: u32) -> u64 {
+    let preparation_delay_ms = u64::from(preparation_delay) * 1000;
+    if preparation_delay_ms > 0 && event_ignore_window_ms >= preparation_delay_ms {
+        preparation_delay_ms - 1
+    } else {
+        event_ignore_window_ms
+    }
+}
+
+pub(crate) fn normalize_prebuffer_seconds(seconds: u32) -> u32 {
+    seconds.min(MAX_PREBUFFER_SECONDS)
+}
+
+/// 去除重复的摄像头 ID，保持原有顺序
+pub(crate) fn normalize_camera_ids(camera_ids: &[u32]) -> Vec<u32> {
+    let mut normalized = Vec::new();
+    for camera_id in camera_ids {
+        if !normalized.contains(camera_id) {
+            normalized.push(*camera_id);
+        }
+    }
+    normalized
+}
+
+/// 规范化忽略按键列表，丢弃无法识别的名称并去重
+fn normalize_ignored_keys(keys: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for key in keys {
+        match crate::key_filter::parse_key_name(key) {
+            Some((canonical, _)) => {
+                if !normalized.contains(&canonical) {
+                    normalized.push(canonical);
+                }
+            }
+            None => log::warn!("忽略无法识别的按键名称: {}", key),
+        }
+    }
+    normalized
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     pub shortcut_key: String,
@@ -67,6 +711,150 @@ pub struct AppConfig {
     pub capture_delay_seconds: u32,
     #[serde(default)]
     pub capture_mode: CaptureMode,
+    #[serde(default = "default_max_segment_seconds")]
+    pub max_segment_seconds: u32,
+    #[serde(default = "default_min_free_mb")]
+    pub min_free_mb: u64,
+    #[serde(default)]
+    pub ignored_keys: Vec<String>,
+    #[serde(default = "default_preparation_delay_seconds")]
+    pub preparation_delay_seconds: u32,
+    #[serde(default)]
+    pub notification_title: Option<String>,
+    #[serde(default)]
+    pub notification_body: Option<String>,
+    #[serde(default = "default_retrigger_cooldown_ms")]
+    pub retrigger_cooldown_ms: u64,
+    #[serde(default)]
+    pub rearm_after_trigger: bool,
+    #[serde(default)]
+    pub autostart_enabled: bool,
+    #[serde(default)]
+    pub auto_arm_on_start: bool,
+    #[serde(default = "default_close_to_tray")]
+    pub close_to_tray: bool,
+    #[serde(default)]
+    pub allow_no_camera: bool,
+    #[serde(default)]
+    pub notify_on_startup: bool,
+    #[serde(default)]
+    pub recording_region: RecordingRegion,
+    #[serde(default)]
+    pub capture_camera_ids: Vec<u32>,
+    #[serde(default)]
+    pub restore_armed_state: bool,
+    #[serde(default)]
+    pub was_armed: bool,
+    #[serde(
+        default = "default_jpeg_quality",
+        deserialize_with = "deserialize_jpeg_quality"
+    )]
+    pub jpeg_quality: u8,
+    #[serde(default = "default_camera_busy_retry_attempts")]
+    pub camera_busy_retry_attempts: u32,
+    #[serde(default = "default_camera_busy_retry_backoff_ms")]
+    pub camera_busy_retry_backoff_ms: u64,
+    #[serde(default)]
+    pub prebuffer_seconds: u32,
+    #[serde(default = "default_shortcut_debounce_ms")]
+    pub shortcut_debounce_ms: u64,
+    #[serde(default = "default_shortcut_flag_clear_delay_ms")]
+    pub shortcut_flag_clear_delay_ms: u64,
+    #[serde(default = "default_event_ignore_window_ms")]
+    pub event_ignore_window_ms: u64,
+    #[serde(default)]
+    pub language: Option<Language>,
+    #[serde(default = "default_capture_timeout_ms")]
+    pub capture_timeout_ms: u64,
+    #[serde(default)]
+    pub recording_duration_seconds: u32,
+    #[serde(default)]
+    pub ffmpeg_path: Option<String>,
+    #[serde(default)]
+    pub trigger_mode: TriggerMode,
+    #[serde(default = "default_idle_trigger_minutes")]
+    pub idle_trigger_minutes: u32,
+    #[serde(default)]
+    pub organize_by_date: bool,
+    #[serde(default)]
+    pub audio_device: Option<String>,
+    #[serde(default)]
+    pub trigger_on_lid_close: bool,
+    #[serde(default)]
+    pub monitored_device_id: Option<String>,
+    #[serde(default = "default_record_before_lock_seconds")]
+    pub record_before_lock_seconds: u32,
+    #[serde(default)]
+    pub photo_with_screen_recording: bool,
+    #[serde(default)]
+    pub on_unlock_action: UnlockAction,
+    #[serde(default)]
+    pub panic_shortcut_key: Option<String>,
+    #[serde(default)]
+    pub window_geometry: Option<WindowGeometry>,
+    #[serde(default)]
+    pub stealth_mode: bool,
+    #[serde(default)]
+    pub hide_tray_icon: bool,
+    #[serde(default)]
+    pub lock_first: bool,
+    #[serde(default)]
+    pub alert_min_interval_seconds: u64,
+    #[serde(default = "default_shortcut_retry_attempts")]
+    pub shortcut_retry_attempts: u32,
+    #[serde(default = "default_shortcut_retry_interval_ms")]
+    pub shortcut_retry_interval_ms: u64,
+    #[serde(default = "default_arm_settle_ms")]
+    pub arm_settle_ms: u64,
+    #[serde(default)]
+    pub monitoring_backend: MonitoringBackend,
+    /// 配置文件格式版本，旧配置缺失时视为 0
+    #[serde(default)]
+    pub config_version: u32,
+    #[serde(default = "default_trigger_source_enabled")]
+    pub trigger_on_keyboard: bool,
+    #[serde(default = "default_trigger_source_enabled")]
+    pub trigger_on_mouse_buttons: bool,
+    #[serde(default = "default_trigger_source_enabled")]
+    pub trigger_on_mouse_move: bool,
+    #[serde(default = "default_trigger_source_enabled")]
+    pub trigger_on_scroll: bool,
+    #[serde(default)]
+    pub camera_fourcc: CameraFourcc,
+    #[serde(default)]
+    pub local_api: LocalApiConfig,
+    #[serde(default)]
+    pub share_credentials: Option<NetworkShareCredentials>,
+    #[serde(default)]
+    pub motion_detection_enabled: bool,
+    #[serde(default = "default_motion_threshold_percent")]
+    pub motion_threshold_percent: u8,
+    #[serde(default = "default_motion_sample_interval_ms")]
+    pub motion_sample_interval_ms: u64,
+    #[serde(default = "default_photo_warmup_frames")]
+    pub photo_warmup_frames: u32,
+    #[serde(default)]
+    pub capture_image_format: ImageFormat,
+    #[serde(default = "default_watermark_enabled")]
+    pub watermark_enabled: bool,
+    #[serde(default = "default_recording_idle_timeout_secs")]
+    pub recording_idle_timeout_secs: u32,
+    #[serde(default = "default_recording_framerate")]
+    pub recording_framerate: u32,
+    #[serde(default = "default_recording_scale_divisor")]
+    pub recording_scale_divisor: u32,
+    #[serde(default = "default_recording_bitrate_kbps")]
+    pub recording_bitrate_kbps: u32,
+    #[serde(default)]
+    pub capture_screenshot_on_trigger: bool,
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    #[serde(default)]
+    pub smtp: SmtpConfig,
+    #[serde(default = "default_event_history_limit")]
+    pub event_history_limit: u32,
+    #[serde(default = "default_camera_recording_seconds")]
+    pub camera_recording_seconds: u32,
 }
 
 impl Default for AppConfig {
@@ -82,7 +870,77 @@ impl Default for AppConfig {
             enable_notifications: true,
             default_camera_id: None,
             capture_delay_seconds: 0,
-            capture_mode: CaptureMode::Video,
+            capture_mode: CaptureMode::PhotoOnly,
+            max_segment_seconds: 0,
+            min_free_mb: default_min_free_mb(),
+            ignored_keys: Vec::new(),
+            preparation_delay_seconds: DEFAULT_PREPARATION_DELAY_SECONDS,
+            notification_title: None,
+            notification_body: None,
+            retrigger_cooldown_ms: DEFAULT_RETRIGGER_COOLDOWN_MS,
+            rearm_after_trigger: false,
+            autostart_enabled: false,
+            auto_arm_on_start: false,
+            close_to_tray: true,
+            allow_no_camera: false,
+            notify_on_startup: false,
+            recording_region: RecordingRegion::FullDesktop,
+            capture_camera_ids: Vec::new(),
+            restore_armed_state: false,
+            was_armed: false,
+            jpeg_quality: DEFAULT_JPEG_QUALITY,
+            camera_busy_retry_attempts: DEFAULT_CAMERA_BUSY_RETRY_ATTEMPTS,
+            camera_busy_retry_backoff_ms: DEFAULT_CAMERA_BUSY_RETRY_BACKOFF_MS,
+            prebuffer_seconds: 0,
+            shortcut_debounce_ms: DEFAULT_SHORTCUT_DEBOUNCE_MS,
+            shortcut_flag_clear_delay_ms: DEFAULT_SHORTCUT_FLAG_CLEAR_DELAY_MS,
+            event_ignore_window_ms: DEFAULT_EVENT_IGNORE_WINDOW_MS,
+            language: None,
+            capture_timeout_ms: DEFAULT_CAPTURE_TIMEOUT_MS,
+            recording_duration_seconds: 0,
+            ffmpeg_path: None,
+            trigger_mode: TriggerMode::Activity,
+            idle_trigger_minutes: DEFAULT_IDLE_TRIGGER_MINUTES,
+            organize_by_date: false,
+            audio_device: None,
+            trigger_on_lid_close: false,
+            monitored_device_id: None,
+            record_before_lock_seconds: DEFAULT_RECORD_BEFORE_LOCK_SECONDS,
+            photo_with_screen_recording: false,
+            on_unlock_action: UnlockAction::ResetToIdle,
+            panic_shortcut_key: None,
+            window_geometry: None,
+            stealth_mode: false,
+            hide_tray_icon: false,
+            lock_first: false,
+            alert_min_interval_seconds: 0,
+            shortcut_retry_attempts: DEFAULT_SHORTCUT_RETRY_ATTEMPTS,
+            shortcut_retry_interval_ms: DEFAULT_SHORTCUT_RETRY_INTERVAL_MS,
+            arm_settle_ms: DEFAULT_ARM_SETTLE_MS,
+            monitoring_backend: MonitoringBackend::Rdev,
+            config_version: CONFIG_VERSION,
+            trigger_on_keyboard: true,
+            trigger_on_mouse_buttons: true,
+            trigger_on_mouse_move: true,
+            trigger_on_scroll: true,
+            camera_fourcc: CameraFourcc::Auto,
+            local_api: LocalApiConfig::default(),
+            share_credentials: None,
+            motion_detection_enabled: false,
+            motion_threshold_percent: DEFAULT_MOTION_THRESHOLD_PERCENT,
+            motion_sample_interval_ms: DEFAULT_MOTION_SAMPLE_INTERVAL_MS,
+            photo_warmup_frames: DEFAULT_PHOTO_WARMUP_FRAMES,
+            capture_image_format: ImageFormat::default(),
+            watermark_enabled: true,
+            recording_idle_timeout_secs: DEFAULT_RECORDING_IDLE_TIMEOUT_SECS,
+            recording_framerate: DEFAULT_RECORDING_FRAMERATE,
+            recording_scale_divisor: DEFAULT_RECORDING_SCALE_DIVISOR,
+            recording_bitrate_kbps: DEFAULT_RECORDING_BITRATE_KBPS,
+            capture_screenshot_on_trigger: false,
+            webhook_url: None,
+            smtp: SmtpConfig::default(),
+            event_history_limit: DEFAULT_EVENT_HISTORY_LIMIT,
+            camera_recording_seconds: DEFAULT_CAMERA_RECORDING_SECONDS,
         }
     }
 }
@@ -132,6 +990,44 @@ impl AppConfig {
 
     fn sanitize(mut self) -> Self {
         self.capture_delay_seconds = normalize_capture_delay(self.capture_delay_seconds);
+        self.capture_mode = normalize_capture_mode(self.capture_mode);
+        self.ignored_keys = normalize_ignored_keys(&self.ignored_keys);
+        self.preparation_delay_seconds =
+            normalize_preparation_delay(self.preparation_delay_seconds);
+        self.event_ignore_window_ms = normalize_event_ignore_window(
+            self.event_ignore_window_ms,
+            self.preparation_delay_seconds,
+        );
+        self.capture_camera_ids = normalize_camera_ids(&self.capture_camera_ids);
+        self.jpeg_quality = normalize_jpeg_quality(self.jpeg_quality);
+        self.camera_busy_retry_attempts =
+            normalize_camera_busy_retry_attempts(self.camera_busy_retry_attempts);
+        self.capture_timeout_ms = normalize_capture_timeout_ms(self.capture_timeout_ms);
+        self.recording_duration_seconds =
+            normalize_recording_duration_seconds(self.recording_duration_seconds);
+        self.idle_trigger_minutes = normalize_idle_trigger_minutes(self.idle_trigger_minutes);
+        self.record_before_lock_seconds =
+            normalize_record_before_lock_seconds(self.record_before_lock_seconds);
+        self.prebuffer_seconds = normalize_prebuffer_seconds(self.prebuffer_seconds);
+        self.photo_warmup_frames = normalize_photo_warmup_frames(self.photo_warmup_frames);
+        self.recording_idle_timeout_secs =
+            normalize_recording_idle_timeout(self.recording_idle_timeout_secs);
+        self.camera_recording_seconds =
+            normalize_camera_recording_seconds(self.camera_recording_seconds);
+        self.recording_framerate = normalize_recording_framerate(self.recording_framerate);
+        self.recording_scale_divisor =
+            normalize_recording_scale_divisor(self.recording_scale_divisor);
+        self.recording_bitrate_kbps = normalize_recording_bitrate_kbps(self.recording_bitrate_kbps);
+        self.event_history_limit = normalize_event_history_limit(self.event_history_limit);
+        self.motion_threshold_percent =
+            normalize_motion_threshold_percent(self.motion_threshold_percent);
+        self.motion_sample_interval_ms =
+            normalize_motion_sample_interval_ms(self.motion_sample_interval_ms);
+        if let RecordingRegion::Custom { width, height, .. } = self.recording_region {
+            if width == 0 || height == 0 {
+                self.recording_region = RecordingRegion::FullDesktop;
+            }
+        }
         self
     }
 
@@ -151,6 +1047,15 @@ impl AppConfig {
         }
     }
 
+    /// 解析配置内容并升级到当前版本，同时返回文件中的原始版本号
+    fn parse_and_migrate(content: &str) -> Result<(Self, u32), String> {
+        let mut value: serde_json::Value =
+            serde_json::from_str(content).map_err(|e| e.to_string())?;
+        let original_version = migrate_config_value(&mut value)?;
+        let config = serde_json::from_value(value).map_err(|e| e.to_string())?;
+        Ok((config, original_version))
+    }
+
     /// 从文件加载配置
     pub fn load() -> Self {
         match Self::get_config_path() {
@@ -161,10 +1066,16 @@ impl AppConfig {
 
                 if config_path.exists() {
                     match fs::read_to_string(&config_path) {
-                        Ok(content) => match serde_json::from_str::<AppConfig>(&content) {
-                            Ok(config) => {
+                        Ok(content) => match Self::parse_and_migrate(&content) {
+                            Ok((config, original_version)) => {
                                 println!("配置文件加载成功: {:?}", config_path);
-                                return config.sanitize();
+                                let config = config.sanitize();
+                                if original_version < CONFIG_VERSION {
+                                    if let Err(error) = config.save() {
+                                        log::warn!("保存升级后的配置失败: {}", error);
+                                    }
+                                }
+                                return config;
                             }
                             Err(e) => {
                                 log::error!("配置文件解析失败: {}", e);
@@ -189,7 +1100,14 @@ impl AppConfig {
     /// 保存配置到文件
     pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
         let config_path = Self::get_config_path()?;
-        let content = serde_json::to_string_pretty(&self.clone().sanitize())
+        let mut config = self.clone().sanitize();
+        config.config_version = CONFIG_VERSION;
+        let mut value = serde_json::to_value(&config)
+            .map_err(|e| format!("Failed to serialize config: {}", e))?;
+        if let Ok(existing_content) = fs::read_to_string(&config_path) {
+            keep_newer_config_fields(&mut value, &existing_content);
+        }
+        let content = serde_json::to_string_pretty(&value)
             .map_err(|e| format!("Failed to serialize config: {}", e))?;
 
         fs::write(&config_path, content)
@@ -212,10 +1130,79 @@ impl AppConfig {
         self.default_camera_id = state.default_camera_id();
         self.capture_delay_seconds = normalize_capture_delay(state.capture_delay_seconds());
         self.capture_mode = state.capture_mode();
+        self.max_segment_seconds = state.max_segment_seconds();
+        self.min_free_mb = state.min_free_mb();
+        self.ignored_keys = state.ignored_keys();
+        self.preparation_delay_seconds =
+            normalize_preparation_delay(state.preparation_delay_seconds());
+        self.notification_title = state.notification_title();
+        self.notification_body = state.notification_body();
+        self.retrigger_cooldown_ms = state.retrigger_cooldown_ms();
+        self.rearm_after_trigger = state.rearm_after_trigger();
+        self.autostart_enabled = state.autostart_enabled();
+        self.auto_arm_on_start = state.auto_arm_on_start();
+        self.close_to_tray = state.close_to_tray();
+        self.allow_no_camera = state.allow_no_camera();
+        self.notify_on_startup = state.notify_on_startup();
+        self.recording_region = state.recording_region();
+        self.capture_camera_ids = state.capture_camera_ids();
+        self.restore_armed_state = state.restore_armed_state();
+        self.was_armed = state.was_armed();
+        self.jpeg_quality = state.jpeg_quality();
+        self.camera_busy_retry_attempts = state.camera_busy_retry_attempts();
+        self.camera_busy_retry_backoff_ms = state.camera_busy_retry_backoff_ms();
+        self.prebuffer_seconds = state.prebuffer_seconds();
+        self.shortcut_debounce_ms = state.shortcut_debounce_ms();
+        self.shortcut_flag_clear_delay_ms = state.shortcut_flag_clear_delay_ms();
+        self.event_ignore_window_ms = state.event_ignore_window_ms();
+        self.language = state.language();
+        self.capture_timeout_ms = state.capture_timeout_ms();
+        self.recording_duration_seconds = state.recording_duration_seconds();
+        self.ffmpeg_path = state.ffmpeg_path();
+        self.trigger_mode = state.trigger_mode();
+        self.idle_trigger_minutes = state.idle_trigger_minutes();
+        self.organize_by_date = state.organize_by_date();
+        self.audio_device = state.audio_device();
+        self.trigger_on_lid_close = state.trigger_on_lid_close();
+        self.monitored_device_id = state.monitored_device_id();
+        self.record_before_lock_seconds = state.record_before_lock_seconds();
+        self.photo_with_screen_recording = state.photo_with_screen_recording();
+        self.on_unlock_action = state.on_unlock_action();
+        self.panic_shortcut_key = state.panic_shortcut_key();
+        self.window_geometry = state.window_geometry();
+        self.stealth_mode = state.stealth_mode();
+        self.hide_tray_icon = state.hide_tray_icon();
+        self.lock_first = state.lock_first();
+        self.alert_min_interval_seconds = state.alert_min_interval_seconds();
+        self.shortcut_retry_attempts = state.shortcut_retry_attempts();
+        self.shortcut_retry_interval_ms = state.shortcut_retry_interval_ms();
+        self.arm_settle_ms = state.arm_settle_ms();
+        self.monitoring_backend = state.monitoring_backend();
+        self.trigger_on_keyboard = state.trigger_on_keyboard();
+        self.trigger_on_mouse_buttons = state.trigger_on_mouse_buttons();
+        self.trigger_on_mouse_move = state.trigger_on_mouse_move();
+        self.trigger_on_scroll = state.trigger_on_scroll();
+        self.camera_fourcc = state.camera_fourcc();
+        self.local_api = state.local_api();
+        self.share_credentials = state.share_credentials();
+        self.motion_detection_enabled = state.motion_detection_enabled();
+        self.motion_threshold_percent = state.motion_threshold_percent();
+        self.motion_sample_interval_ms = state.motion_sample_interval_ms();
+        self.photo_warmup_frames = state.photo_warmup_frames();
+        self.capture_image_format = state.capture_image_format();
+        self.watermark_enabled = state.watermark_enabled();
+        self.recording_idle_timeout_secs = state.recording_idle_timeout_secs();
+        self.recording_framerate = state.recording_framerate();
+        self.recording_scale_divisor = state.recording_scale_divisor();
+        self.recording_bitrate_kbps = state.recording_bitrate_kbps();
+        self.capture_screenshot_on_trigger = state.capture_screenshot_on_trigger();
+        self.webhook_url = state.webhook_url();
+        self.smtp = state.smtp();
+        self.event_history_limit = state.event_history_limit();
+        self.camera_recording_seconds = state.camera_recording_seconds();
     }
 
     #[cfg_attr(not(test), allow(dead_code))]
-    #[allow(dead_code)]
     /// 将配置应用到应用状态
     pub fn apply_to_state(&self, state: &crate::state::AppState) {
         self.apply_to_state_with_runtime_camera(state, self.default_camera_id);
@@ -241,7 +1228,86 @@ impl AppConfig {
         }
 
         state.set_capture_delay_seconds(normalize_capture_delay(self.capture_delay_seconds));
-        state.set_capture_mode(self.capture_mode.clone());
+        state.set_capture_mode(normalize_capture_mode(self.capture_mode));
+        state.set_max_segment_seconds(self.max_segment_seconds);
+        state.set_min_free_mb(self.min_free_mb);
+        state.set_ignored_keys(self.ignored_keys.clone());
+        state.set_preparation_delay_seconds(normalize_preparation_delay(
+            self.preparation_delay_seconds,
+        ));
+        state.set_notification_title(self.notification_title.clone());
+        state.set_notification_body(self.notification_body.clone());
+        state.set_retrigger_cooldown_ms(self.retrigger_cooldown_ms);
+        state.set_rearm_after_trigger(self.rearm_after_trigger);
+        state.set_autostart_enabled(self.autostart_enabled);
+        state.set_auto_arm_on_start(self.auto_arm_on_start);
+        state.set_close_to_tray(self.close_to_tray);
+        state.set_allow_no_camera(self.allow_no_camera);
+        state.set_notify_on_startup(self.notify_on_startup);
+        state.set_recording_region(self.recording_region);
+        state.set_capture_camera_ids(normalize_camera_ids(&self.capture_camera_ids));
+        state.set_restore_armed_state(self.restore_armed_state);
+        state.set_was_armed(self.was_armed);
+        state.set_jpeg_quality(normalize_jpeg_quality(self.jpeg_quality));
+        state.set_camera_busy_retry_attempts(self.camera_busy_retry_attempts);
+        state.set_camera_busy_retry_backoff_ms(self.camera_busy_retry_backoff_ms);
+        state.set_prebuffer_seconds(normalize_prebuffer_seconds(self.prebuffer_seconds));
+        state.set_shortcut_debounce_ms(self.shortcut_debounce_ms);
+        state.set_shortcut_flag_clear_delay_ms(self.shortcut_flag_clear_delay_ms);
+        state.set_event_ignore_window_ms(self.event_ignore_window_ms);
+        state.set_language(self.language);
+        state.set_capture_timeout_ms(self.capture_timeout_ms);
+        state.set_recording_duration_seconds(self.recording_duration_seconds);
+        state.set_ffmpeg_path(self.ffmpeg_path.clone());
+        state.set_trigger_mode(self.trigger_mode);
+        state.set_idle_trigger_minutes(self.idle_trigger_minutes);
+        state.set_organize_by_date(self.organize_by_date);
+        state.set_audio_device(self.audio_device.clone());
+        state.set_trigger_on_lid_close(self.trigger_on_lid_close);
+        state.set_monitored_device_id(self.monitored_device_id.clone());
+        state.set_record_before_lock_seconds(self.record_before_lock_seconds);
+        state.set_photo_with_screen_recording(self.photo_with_screen_recording);
+        state.set_on_unlock_action(self.on_unlock_action);
+        state.set_panic_shortcut_key(self.panic_shortcut_key.clone());
+        state.set_window_geometry(self.window_geometry);
+        state.set_stealth_mode(self.stealth_mode);
+        state.set_hide_tray_icon(self.hide_tray_icon);
+        state.set_lock_first(self.lock_first);
+        state.set_alert_min_interval_seconds(self.alert_min_interval_seconds);
+        state.set_shortcut_retry_attempts(self.shortcut_retry_attempts);
+        state.set_shortcut_retry_interval_ms(self.shortcut_retry_interval_ms);
+        state.set_arm_settle_ms(self.arm_settle_ms);
+        state.set_monitoring_backend(self.monitoring_backend);
+        state.set_trigger_on_keyboard(self.trigger_on_keyboard);
+        state.set_trigger_on_mouse_buttons(self.trigger_on_mouse_buttons);
+        state.set_trigger_on_mouse_move(self.trigger_on_mouse_move);
+        state.set_trigger_on_scroll(self.trigger_on_scroll);
+        state.set_camera_fourcc(self.camera_fourcc);
+        state.set_local_api(self.local_api.clone());
+        state.set_share_credentials(self.share_credentials.clone());
+        state.set_motion_detection_enabled(self.motion_detection_enabled);
+        state.set_motion_threshold_percent(self.motion_threshold_percent);
+        state.set_motion_sample_interval_ms(self.motion_sample_interval_ms);
+        state.set_photo_warmup_frames(normalize_photo_warmup_frames(self.photo_warmup_frames));
+        state.set_capture_image_format(self.capture_image_format);
+        state.set_watermark_enabled(self.watermark_enabled);
+        state.set_recording_idle_timeout_secs(normalize_recording_idle_timeout(
+            self.recording_idle_timeout_secs,
+        ));
+        state.set_recording_framerate(normalize_recording_framerate(self.recording_framerate));
+        state.set_recording_scale_divisor(normalize_recording_scale_divisor(
+            self.recording_scale_divisor,
+        ));
+        state.set_recording_bitrate_kbps(normalize_recording_bitrate_kbps(
+            self.recording_bitrate_kbps,
+        ));
+        state.set_capture_screenshot_on_trigger(self.capture_screenshot_on_trigger);
+        state.set_webhook_url(self.webhook_url.clone());
+        state.set_smtp(self.smtp.clone());
+        state.set_event_history_limit(normalize_event_history_limit(self.event_history_limit));
+        state.set_camera_recording_seconds(normalize_camera_recording_seconds(
+            self.camera_recording_seconds,
+        ));
 
         if self.save_logs_to_file {
             if let Some(logger) = crate::logger::get_logger() {
@@ -307,11 +1373,7 @@ pub fn save_dark_mode_setting(app_handle: AppHandle, enabled: bool) -> Result<()
     Ok(())
 }
 
-pub fn apply_state_change<A, R>(
-    app_handle: &AppHandle,
-    apply: A,
-    rollback: R,
-) -> Result<(), String>
+pub fn apply_state_change<A, R>(app_handle: &AppHandle, apply: A, rollback: R) -> Result<(), String>
 where
     A: FnOnce(&crate::state::AppState),
     R: FnOnce(&crate::state::AppState),
@@ -329,7 +1391,10 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::{AppConfig, CaptureMode, PostTriggerAction};
+    use super::{
+        AppConfig, CONFIG_VERSION, CaptureMode, PostTriggerAction, SmtpConfig,
+        keep_newer_config_fields, normalize_capture_mode,
+    };
     use crate::state::AppState;
 
     #[test]
@@ -364,6 +1429,61 @@ mod tests {
         assert_eq!(restored_state.camera_id(), 42);
     }
 
+    #[test]
+    fn record_and_lock_records_screen_and_locks() {
+        assert!(PostTriggerAction::RecordAndLock.records_screen());
+        assert!(PostTriggerAction::RecordAndLock.locks_screen());
+        assert!(PostTriggerAction::ScreenRecording.records_screen());
+        assert!(!PostTriggerAction::ScreenRecording.locks_screen());
+        assert!(!PostTriggerAction::CaptureOnly.locks_screen());
+        assert!(PostTriggerAction::RecordCamera.records_camera());
+        assert!(!PostTriggerAction::RecordCamera.records_screen());
+        assert!(!PostTriggerAction::RecordCamera.locks_screen());
+    }
+
+    #[test]
+    fn capture_mode_selects_photo_and_video_steps() {
+        assert!(CaptureMode::PhotoOnly.takes_photo());
+        assert!(!CaptureMode::PhotoOnly.records_video());
+        assert!(!CaptureMode::Video.takes_photo());
+        assert!(CaptureMode::Video.records_video());
+        assert!(CaptureMode::PhotoThenVideo.takes_photo());
+        assert!(CaptureMode::PhotoThenVideo.records_video());
+
+        let burst = CaptureMode::Burst {
+            count: 3,
+            interval_ms: 500,
+        };
+        assert!(burst.takes_photo());
+        assert!(!burst.records_video());
+        assert_eq!(burst.photo_shots(), (3, 500));
+        assert_eq!(CaptureMode::PhotoOnly.photo_shots(), (1, 0));
+        assert_eq!(
+            normalize_capture_mode(CaptureMode::Burst {
+                count: 0,
+                interval_ms: 100
+            })
+            .photo_shots(),
+            (1, 100)
+        );
+        assert_eq!(
+            normalize_capture_mode(CaptureMode::Burst {
+                count: 3,
+                interval_ms: u64::MAX
+            })
+            .photo_shots(),
+            (3, 10_000)
+        );
+        assert_eq!(
+            normalize_capture_mode(CaptureMode::Burst {
+                count: 3,
+                interval_ms: 0
+            })
+            .photo_shots(),
+            (3, 50)
+        );
+    }
+
     #[test]
     fn config_sanitizes_capture_delay() {
         let config = AppConfig {
@@ -374,4 +1494,242 @@ mod tests {
 
         assert_eq!(config.capture_delay_seconds, 60);
     }
+
+    #[test]
+    fn camera_recording_seconds_defaults_to_ten_and_is_clamped() {
+        // 旧配置文件没有该字段时使用默认值
+        let mut value = serde_json::to_value(AppConfig::default()).unwrap();
+        value
+            .as_object_mut()
+            .unwrap()
+            .remove("camera_recording_seconds");
+        let config: AppConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(config.camera_recording_seconds, 10);
+
+        let config = AppConfig {
+            camera_recording_seconds: 0,
+            ..AppConfig::default()
+        }
+        .sanitize();
+        assert_eq!(config.camera_recording_seconds, 1);
+
+        let config = AppConfig {
+            camera_recording_seconds: 100_000,
+            ..AppConfig::default()
+        }
+        .sanitize();
+        assert_eq!(config.camera_recording_seconds, 600);
+    }
+
+    #[test]
+    fn partial_smtp_settings_use_field_defaults() {
+        let config: AppConfig = serde_json::from_str(
+            r#"{"smtp":{"host":"smtp.example.com","alert_email_to":"me@example.com"}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.smtp.host.as_deref(), Some("smtp.example.com"));
+        assert_eq!(config.smtp.port, SmtpConfig::default().port);
+        assert_eq!(config.smtp.password, None);
+    }
+
+    #[test]
+    fn config_clamps_motion_detection_settings() {
+        let config = AppConfig {
+            motion_threshold_percent: 0,
+            motion_sample_interval_ms: 0,
+            ..AppConfig::default()
+        }
+        .sanitize();
+        assert_eq!(config.motion_threshold_percent, 1);
+        assert_eq!(config.motion_sample_interval_ms, 200);
+
+        let config = AppConfig {
+            motion_threshold_percent: 250,
+            ..AppConfig::default()
+        }
+        .sanitize();
+        assert_eq!(config.motion_threshold_percent, 100);
+    }
+
+    #[test]
+    fn config_caps_camera_busy_retry_attempts() {
+        let config = AppConfig {
+            camera_busy_retry_attempts: 1_000,
+            ..AppConfig::default()
+        }
+        .sanitize();
+        assert_eq!(config.camera_busy_retry_attempts, 10);
+    }
+
+    #[test]
+    fn config_caps_capture_timeout() {
+        let config = AppConfig {
+            capture_timeout_ms: u64::MAX,
+            ..AppConfig::default()
+        }
+        .sanitize();
+        assert_eq!(config.capture_timeout_ms, 60_000);
+    }
+
+    #[test]
+    fn config_caps_recording_duration() {
+        let config = AppConfig {
+            recording_duration_seconds: 100_000,
+            ..AppConfig::default()
+        }
+        .sanitize();
+        assert_eq!(config.recording_duration_seconds, 3_600);
+    }
+
+    #[test]
+    fn config_clamps_idle_trigger_minutes() {
+        let config = AppConfig {
+            idle_trigger_minutes: 0,
+            ..AppConfig::default()
+        }
+        .sanitize();
+        assert_eq!(config.idle_trigger_minutes, 1);
+
+        let config = AppConfig {
+            idle_trigger_minutes: u32::MAX,
+            ..AppConfig::default()
+        }
+        .sanitize();
+        assert_eq!(config.idle_trigger_minutes, 24 * 60);
+    }
+
+    #[test]
+    fn config_clamps_record_before_lock_seconds() {
+        let config = AppConfig {
+            record_before_lock_seconds: 0,
+            ..AppConfig::default()
+        }
+        .sanitize();
+        assert_eq!(config.record_before_lock_seconds, 1);
+
+        let config = AppConfig {
+            record_before_lock_seconds: 600,
+            ..AppConfig::default()
+        }
+        .sanitize();
+        assert_eq!(config.record_before_lock_seconds, 60);
+    }
+
+    #[test]
+    fn config_keeps_event_ignore_window_below_preparation_delay() {
+        let config = AppConfig {
+            preparation_delay_seconds: 2,
+            event_ignore_window_ms: 5_000,
+            ..AppConfig::default()
+        }
+        .sanitize();
+        assert_eq!(config.event_ignore_window_ms, 1_999);
+
+        let config = AppConfig {
+            preparation_delay_seconds: 0,
+            event_ignore_window_ms: 5_000,
+            ..AppConfig::default()
+        }
+        .sanitize();
+        assert_eq!(config.event_ignore_window_ms, 5_000);
+    }
+
+    #[test]
+    fn config_clamps_jpeg_quality() {
+        let config = AppConfig {
+            jpeg_quality: 0,
+            ..AppConfig::default()
+        }
+        .sanitize();
+        assert_eq!(config.jpeg_quality, 1);
+
+        let config = AppConfig {
+            jpeg_quality: 255,
+            ..AppConfig::default()
+        }
+        .sanitize();
+        assert_eq!(config.jpeg_quality, 100);
+
+        let config: AppConfig = serde_json::from_str(r#"{"jpeg_quality":1000}"#).unwrap();
+        assert_eq!(config.jpeg_quality, 100);
+        let config: AppConfig = serde_json::from_str(r#"{"jpeg_quality":-5}"#).unwrap();
+        assert_eq!(config.jpeg_quality, 1);
+    }
+
+    #[test]
+    fn invalid_recording_quality_falls_back_to_defaults() {
+        let config = AppConfig {
+            recording_framerate: 0,
+            recording_scale_divisor: 100,
+            recording_bitrate_kbps: 10,
+            ..AppConfig::default()
+        }
+        .sanitize();
+        assert_eq!(config.recording_framerate, 30);
+        assert_eq!(config.recording_scale_divisor, 2);
+        assert_eq!(config.recording_bitrate_kbps, 2000);
+
+        let config = AppConfig {
+            recording_framerate: 60,
+            recording_scale_divisor: 1,
+            recording_bitrate_kbps: 8000,
+            ..AppConfig::default()
+        }
+        .sanitize();
+        assert_eq!(config.recording_framerate, 60);
+        assert_eq!(config.recording_scale_divisor, 1);
+        assert_eq!(config.recording_bitrate_kbps, 8000);
+    }
+
+    #[test]
+    fn unversioned_config_is_migrated_to_current_version() {
+        let (config, original_version) =
+            AppConfig::parse_and_migrate(r#"{"shortcut_key":"Ctrl+Alt+K","dark_mode":true}"#)
+                .unwrap();
+
+        assert_eq!(original_version, 0);
+        assert_eq!(config.config_version, CONFIG_VERSION);
+        assert_eq!(config.shortcut_key, "Ctrl+Alt+K");
+        assert!(config.dark_mode);
+        assert_eq!(config.jpeg_quality, AppConfig::default().jpeg_quality);
+    }
+
+    #[test]
+    fn newer_config_version_is_left_untouched() {
+        let content = format!(
+            r#"{{"shortcut_key":"Alt+L","config_version":{}}}"#,
+            CONFIG_VERSION + 1
+        );
+        let (config, original_version) = AppConfig::parse_and_migrate(&content).unwrap();
+
+        assert_eq!(original_version, CONFIG_VERSION + 1);
+        assert_eq!(config.config_version, CONFIG_VERSION + 1);
+        assert!(AppConfig::parse_and_migrate("[]").is_err());
+    }
+
+    #[test]
+    fn saving_over_newer_config_keeps_its_version_and_unknown_fields() {
+        let newer = format!(
+            r#"{{"shortcut_key":"Alt+L","config_version":{},"future_option":true}}"#,
+            CONFIG_VERSION + 1
+        );
+        let config = AppConfig {
+            shortcut_key: "Ctrl+Alt+K".to_string(),
+            ..AppConfig::default()
+        };
+
+        let mut value = serde_json::to_value(&config).unwrap();
+        keep_newer_config_fields(&mut value, &newer);
+        assert_eq!(value["config_version"], CONFIG_VERSION + 1);
+        assert_eq!(value["future_option"], true);
+        assert_eq!(value["shortcut_key"], "Ctrl+Alt+K");
+
+        // 当前或更旧版本的配置照常按当前版本覆盖
+        let older = r#"{"config_version":0,"obsolete_option":1}"#;
+        let mut value = serde_json::to_value(&config).unwrap();
+        keep_newer_config_fields(&mut value, older);
+        assert_eq!(value["config_version"], CONFIG_VERSION);
+        assert!(value.get("obsolete_option").is_none());
+    }
 }
@@ -1,8 +1,107 @@
 // snaplock/src-tauri/src/constants.rs
 
+use std::ops::RangeInclusive;
 use std::time::Duration;
 
-pub const PREPARATION_DELAY: Duration = Duration::from_secs(2);
-pub const SHORTCUT_DEBOUNCE_TIME: Duration = Duration::from_millis(500);
-pub const SHORTCUT_FLAG_CLEAR_DELAY: Duration = Duration::from_millis(1000);
-pub const EVENT_IGNORE_WINDOW_MS: u64 = 500; // 减少事件忽略窗口从1000ms到500ms
+pub const DEFAULT_PREPARATION_DELAY_SECONDS: u32 = 2;
+pub const MAX_PREPARATION_DELAY_SECONDS: u32 = 300;
+pub const DEFAULT_SHORTCUT_DEBOUNCE_MS: u64 = 500;
+pub const DEFAULT_SHORTCUT_FLAG_CLEAR_DELAY_MS: u64 = 1000;
+pub const DEFAULT_EVENT_IGNORE_WINDOW_MS: u64 = 500; // 减少事件忽略窗口从1000ms到500ms
+/// 快捷键按键一直未松开时，最长忽略输入的时间
+pub const SHORTCUT_KEY_RELEASE_TIMEOUT_MS: u64 = 3_000;
+/// 进入警戒后的稳定期，期间输入只更新活动时间而不触发
+pub const DEFAULT_ARM_SETTLE_MS: u64 = 500;
+pub const DEFAULT_SHORTCUT_RETRY_ATTEMPTS: u32 = 3;
+pub const DEFAULT_SHORTCUT_RETRY_INTERVAL_MS: u64 = 1_000;
+/// 快捷键注册重试的指数退避上限
+pub const MAX_SHORTCUT_RETRY_DELAY_MS: u64 = 30_000;
+pub const DEFAULT_RETRIGGER_COOLDOWN_MS: u64 = 3_000;
+pub const DEFAULT_JPEG_QUALITY: u8 = 85;
+pub const DEFAULT_CAMERA_BUSY_RETRY_ATTEMPTS: u32 = 3;
+pub const MAX_CAMERA_BUSY_RETRY_ATTEMPTS: u32 = 10;
+pub const DEFAULT_CAMERA_BUSY_RETRY_BACKOFF_MS: u64 = 500;
+pub const DEFAULT_PHOTO_WARMUP_FRAMES: u32 = 5;
+pub const MAX_PHOTO_WARMUP_FRAMES: u32 = 30;
+pub const MAX_BURST_COUNT: u32 = 20;
+/// 连拍间隔范围，连拍在锁屏前进行，间隔过长会推迟锁屏
+pub const MIN_BURST_INTERVAL_MS: u64 = 50;
+pub const MAX_BURST_INTERVAL_MS: u64 = 10_000;
+/// 预热帧之间的间隔，与相机预览一致
+pub const PHOTO_WARMUP_FRAME_INTERVAL: Duration = Duration::from_millis(100);
+pub const DEFAULT_CAPTURE_TIMEOUT_MS: u64 = 10_000;
+pub const MAX_CAPTURE_TIMEOUT_MS: u64 = 60_000;
+pub const FFMPEG_GRACEFUL_STOP_TIMEOUT: Duration = Duration::from_secs(5);
+/// 触发时桌面截图的最长等待时间，超时后放弃截图继续锁屏
+pub const SCREENSHOT_TIMEOUT: Duration = Duration::from_secs(5);
+pub const LISTENER_WATCHDOG_INTERVAL: Duration = Duration::from_secs(5);
+pub const FFMPEG_WATCHDOG_INTERVAL: Duration = Duration::from_secs(5);
+/// 屏幕录制 ffmpeg 连续意外退出后的重启退避：从 2 秒起每次翻倍，最长 1 分钟
+pub const FFMPEG_RESTART_BASE_DELAY: Duration = Duration::from_secs(2);
+pub const FFMPEG_RESTART_MAX_DELAY: Duration = Duration::from_secs(60);
+pub const FFMPEG_RESTART_MAX_ATTEMPTS: u32 = 5;
+/// 重启后稳定运行超过该时长再退出，重新从第一次重启开始计数
+pub const FFMPEG_RESTART_STABLE_PERIOD: Duration = Duration::from_secs(60);
+pub const LISTENER_RECOVERY_MAX_ATTEMPTS: u32 = 3;
+pub const MAX_PREBUFFER_SECONDS: u32 = 30;
+pub const MAX_RECORDING_DURATION_SECONDS: u32 = 3_600;
+pub const DEFAULT_RECORD_BEFORE_LOCK_SECONDS: u32 = 5;
+pub const MAX_RECORD_BEFORE_LOCK_SECONDS: u32 = 60;
+/// 模拟触发时录制时长配置为 0（不限时）的替代时长，避免模拟结束后录制仍在后台运行
+pub const SIMULATED_RECORDING_SECONDS: u32 = 10;
+pub const DEFAULT_IDLE_TRIGGER_MINUTES: u32 = 30;
+pub const MAX_IDLE_TRIGGER_MINUTES: u32 = 24 * 60;
+/// 屏幕录制模式下无操作超过该秒数后暂停录制，0 表示不自动暂停
+pub const DEFAULT_RECORDING_IDLE_TIMEOUT_SECS: u32 = 20;
+pub const MAX_RECORDING_IDLE_TIMEOUT_SECS: u32 = 3_600;
+/// 触发后摄像头录像的默认与最长时长（秒）
+pub const DEFAULT_CAMERA_RECORDING_SECONDS: u32 = 10;
+pub const MAX_CAMERA_RECORDING_SECONDS: u32 = 600;
+/// 录制不自动暂停时，计时器重新读取设置并确认录制仍在运行的间隔
+pub const RECORDING_ALWAYS_ON_RECHECK_INTERVAL: Duration = Duration::from_secs(5);
+/// 屏幕录制画质默认值与原先的固定参数一致：30 帧、宽高减半、2000 kbps
+pub const DEFAULT_RECORDING_FRAMERATE: u32 = 30;
+pub const RECORDING_FRAMERATE_RANGE: RangeInclusive<u32> = 1..=60;
+pub const DEFAULT_RECORDING_SCALE_DIVISOR: u32 = 2;
+pub const RECORDING_SCALE_DIVISOR_RANGE: RangeInclusive<u32> = 1..=8;
+pub const DEFAULT_RECORDING_BITRATE_KBPS: u32 = 2_000;
+pub const RECORDING_BITRATE_KBPS_RANGE: RangeInclusive<u32> = 100..=50_000;
+pub const IDLE_TRIGGER_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+/// 合盖/睡眠触发时推迟挂起等待拍摄的最长时间，Windows 仅为睡眠通知预留约 2 秒
+pub const LID_CLOSE_SUSPEND_GRACE: Duration = Duration::from_secs(2);
+pub const PREBUFFER_FPS: u32 = 5;
+pub const PREBUFFER_POST_TRIGGER: Duration = Duration::from_secs(2);
+/// 拍摄延迟期间取帧失败时的重试间隔
+pub const CAPTURE_DELAY_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+/// 触发历史文件中默认最多保留的记录数，超出后丢弃最早的记录
+pub const DEFAULT_EVENT_HISTORY_LIMIT: u32 = 500;
+pub const MAX_EVENT_HISTORY_LIMIT: u32 = 10_000;
+/// 摄像头预览默认宽度，高度按画面比例计算
+pub const DEFAULT_PREVIEW_WIDTH: u32 = 320;
+pub const MAX_PREVIEW_WIDTH: u32 = 1_920;
+/// 连续预览默认与最大帧率
+pub const DEFAULT_PREVIEW_FPS: u32 = 10;
+pub const MAX_PREVIEW_FPS: u32 = 30;
+pub const DEFAULT_LOCAL_API_PORT: u16 = 47_615;
+/// 本地 HTTP 接口读取单个请求的超时时间
+pub const LOCAL_API_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// 连接需要认证的网络共享时的最多尝试次数与重试间隔
+pub const NETWORK_SHARE_CONNECT_ATTEMPTS: u32 = 3;
+pub const NETWORK_SHARE_RETRY_DELAY: Duration = Duration::from_millis(500);
+/// 触发时 Webhook 请求的超时时间，避免无响应的服务器占用后台任务
+pub const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+pub const DEFAULT_SMTP_PORT: u16 = 587;
+/// 发送告警邮件（连接、认证与上传附件）的超时时间
+pub const EMAIL_TIMEOUT: Duration = Duration::from_secs(30);
+pub const DEFAULT_MOTION_THRESHOLD_PERCENT: u8 = 5;
+pub const DEFAULT_MOTION_SAMPLE_INTERVAL_MS: u64 = 1_000;
+/// 运动检测的最短采样间隔，避免持续取帧占用过多 CPU
+pub const MIN_MOTION_SAMPLE_INTERVAL_MS: u64 = 200;
+/// 运动检测打开摄像头后先丢弃的帧数，等待自动曝光稳定后再取基准画面
+pub const MOTION_WARMUP_FRAMES: u32 = 10;
+/// 运动检测等待下一次采样时检查停止标志的间隔
+pub const MOTION_STOP_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+/// 灰度差超过该值的像素才计为变化，过滤摄像头噪点
+pub const MOTION_PIXEL_DELTA: u8 = 25;
+/// 运动检测前将画面缩小到该尺寸再比较
+pub const MOTION_FRAME_SIZE: (u32, u32) = (160, 120);
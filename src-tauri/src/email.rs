@@ -0,0 +1,236 @@
+// snaplock/src-tauri/src/email.rs
+//! 触发锁定时通过 SMTP 发送告警邮件，并附上入侵者照片。
+//!
+//! 邮件在后台任务中发送，不阻塞锁定流程；设置不完整时直接跳过。
+//! 与系统警报共用最小间隔，间隔内的多次触发合并为一封邮件。
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use tauri::AppHandle;
+
+use crate::config::SmtpConfig;
+use crate::constants::EMAIL_TIMEOUT;
+use crate::notifications::RemoteAlertThrottle;
+
+static EMAIL_THROTTLE: Mutex<RemoteAlertThrottle<(SmtpConfig, EmailAlert)>> =
+    Mutex::new(RemoteAlertThrottle::new());
+
+/// 隐式 TLS 使用的端口，其他端口使用 STARTTLS
+const SMTPS_PORT: u16 = 465;
+
+/// 一封告警邮件的内容
+#[derive(Debug, Clone)]
+pub struct EmailAlert {
+    pub timestamp: String,
+    pub photo_path: Option<String>,
+    /// 合并发送时为间隔内的触发次数，否则为 1
+    pub trigger_count: u32,
+    /// 由模拟触发发送的测试邮件
+    pub test: bool,
+}
+
+fn alert_subject(hostname: &str, test: bool) -> String {
+    if test {
+        format!("SnapLock test alert on {}", hostname)
+    } else {
+        format!("SnapLock alert on {}", hostname)
+    }
+}
+
+fn alert_body(alert: &EmailAlert, hostname: &str) -> String {
+    let mut body = format!(
+        "SnapLock 于 {} 在 {} 上触发了锁定。",
+        alert.timestamp, hostname
+    );
+    if alert.trigger_count > 1 {
+        body.push_str(&format!(
+            "\n告警间隔内共触发 {} 次，附件为最近一次的照片。",
+            alert.trigger_count
+        ));
+    }
+    if alert.test {
+        body.insert_str(0, "这是模拟触发发送的测试邮件。\n");
+    }
+    body
+}
+
+/// 按扩展名推断附件类型
+fn attachment_content_type(path: &Path) -> &'static str {
+    match path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase)
+        .as_deref()
+    {
+        Some("png") => "image/png",
+        Some("webp") => "image/webp",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        _ => "application/octet-stream",
+    }
+}
+
+/// 按警报最小间隔发送告警邮件，间隔内的触发合并为一封
+pub fn send_alert_throttled(app_handle: &AppHandle, config: SmtpConfig, alert: EmailAlert) {
+    if !config.is_complete() {
+        log::debug!("SMTP 设置不完整，跳过告警邮件");
+        return;
+    }
+
+    crate::notifications::throttle_remote_alert(
+        app_handle,
+        &EMAIL_THROTTLE,
+        (config, alert),
+        |(config, alert), trigger_count| {
+            send_alert(
+                config,
+                EmailAlert {
+                    trigger_count,
+                    ..alert
+                },
+            )
+        },
+    );
+}
+
+/// 在后台发送告警邮件，不等待结果
+pub fn send_alert(config: SmtpConfig, alert: EmailAlert) {
+    if !config.is_complete() {
+        log::debug!("SMTP 设置不完整，跳过告警邮件");
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        match send(&config, &alert).await {
+            Ok(()) => log::info!("告警邮件已发送"),
+            Err(error) => log::error!("告警邮件发送失败: {}", error),
+        }
+    });
+}
+
+async fn send(config: &SmtpConfig, alert: &EmailAlert) -> Result<(), String> {
+    let (Some(host), Some(to)) = (config.host.as_deref(), config.alert_email_to.as_deref()) else {
+        return Err("SMTP 设置不完整".to_string());
+    };
+    let to: Mailbox = to.parse().map_err(|e| format!("收件人地址无效: {}", e))?;
+    // 用户名通常就是发件邮箱，不是邮箱地址时以收件人作为发件人
+    let from = config
+        .username
+        .as_deref()
+        .and_then(|username| username.parse::<Mailbox>().ok())
+        .unwrap_or_else(|| to.clone());
+
+    let hostname = crate::notifications::system_hostname();
+    let body = alert_body(alert, &hostname);
+    let mut multipart = MultiPart::mixed().singlepart(SinglePart::plain(body));
+    if let Some(photo_path) = alert.photo_path.as_deref() {
+        let path = Path::new(photo_path);
+        match tokio::fs::read(path).await {
+            Ok(bytes) => {
+                let filename = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "snaplock.jpg".to_string());
+                let content_type = ContentType::parse(attachment_content_type(path))
+                    .map_err(|e| format!("附件类型无效: {}", e))?;
+                multipart =
+                    multipart.singlepart(Attachment::new(filename).body(bytes, content_type));
+            }
+            Err(error) => log::warn!("读取照片失败，邮件不附带照片: {}", error),
+        }
+    }
+
+    let message = Message::builder()
+        .from(from)
+        .to(to)
+        .subject(alert_subject(&hostname, alert.test))
+        .multipart(multipart)
+        .map_err(|e| format!("无法生成邮件: {}", e))?;
+
+    let builder = if config.port == SMTPS_PORT {
+        AsyncSmtpTransport::<Tokio1Executor>::relay(host)
+    } else {
+        AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+    }
+    .map_err(|e| format!("无法连接 SMTP 服务器 {}: {}", host, e))?
+    .port(config.port)
+    .timeout(Some(EMAIL_TIMEOUT));
+    let builder = match &config.username {
+        Some(username) => builder.credentials(Credentials::new(
+            username.clone(),
+            config.password.clone().unwrap_or_default(),
+        )),
+        None => builder,
+    };
+
+    builder
+        .build()
+        .send(message)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EmailAlert, alert_body, alert_subject, attachment_content_type};
+    use crate::config::SmtpConfig;
+    use std::path::Path;
+
+    #[test]
+    fn attachment_type_follows_photo_format() {
+        assert_eq!(attachment_content_type(Path::new("a.JPG")), "image/jpeg");
+        assert_eq!(attachment_content_type(Path::new("a.png")), "image/png");
+        assert_eq!(attachment_content_type(Path::new("a.webp")), "image/webp");
+        assert_eq!(
+            attachment_content_type(Path::new("a")),
+            "application/octet-stream"
+        );
+        assert_eq!(alert_subject("DESK-01", false), "SnapLock alert on DESK-01");
+        assert_eq!(
+            alert_subject("DESK-01", true),
+            "SnapLock test alert on DESK-01"
+        );
+    }
+
+    #[test]
+    fn body_mentions_coalesced_triggers_and_test_mail() {
+        let alert = EmailAlert {
+            timestamp: "2024-03-07 12:00:00".to_string(),
+            photo_path: None,
+            trigger_count: 1,
+            test: false,
+        };
+        assert_eq!(
+            alert_body(&alert, "DESK-01"),
+            "SnapLock 于 2024-03-07 12:00:00 在 DESK-01 上触发了锁定。"
+        );
+
+        let coalesced = EmailAlert {
+            trigger_count: 3,
+            test: true,
+            ..alert
+        };
+        let body = alert_body(&coalesced, "DESK-01");
+        assert!(body.starts_with("这是模拟触发发送的测试邮件。"));
+        assert!(body.contains("共触发 3 次"));
+    }
+
+    #[test]
+    fn smtp_password_is_not_printed() {
+        let config = SmtpConfig {
+            host: Some("smtp.example.com".to_string()),
+            username: Some("me@example.com".to_string()),
+            password: Some("hunter2".to_string()),
+            alert_email_to: Some("me@example.com".to_string()),
+            ..SmtpConfig::default()
+        };
+        assert!(config.is_complete());
+        assert!(!format!("{:?}", config).contains("hunter2"));
+        assert!(!SmtpConfig::default().is_complete());
+    }
+}
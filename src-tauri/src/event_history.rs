@@ -0,0 +1,253 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::monitoring::LockdownTriggeredPayload;
+
+/// 串行化对事件文件的读写
+static EVENT_HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+/// 获取事件历史文件路径（与配置文件位于同一目录）
+fn get_event_history_path() -> Result<PathBuf, String> {
+    let base_dir = dirs::config_dir().unwrap_or_else(std::env::temp_dir);
+    let config_dir = base_dir.join("SnapLock");
+    fs::create_dir_all(&config_dir).map_err(|e| format!("无法创建配置目录: {}", e))?;
+    Ok(config_dir.join("events.json"))
+}
+
+/// 损坏的历史文件备份路径，如 `events.json.20240101_120000.bak`；
+/// 同一秒内已有备份时追加序号（`_1`、`_2`…），Windows 上 `fs::rename` 会直接覆盖目标文件
+fn backup_path(path: &Path) -> PathBuf {
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S").to_string();
+    let file_name = path.file_name().unwrap_or_default();
+
+    let mut counter = 0u32;
+    loop {
+        let mut name = file_name.to_os_string();
+        if counter == 0 {
+            name.push(format!(".{}.bak", timestamp));
+        } else {
+            name.push(format!(".{}_{}.bak", timestamp, counter));
+        }
+        let candidate = path.with_file_name(name);
+        if !candidate.exists() {
+            return candidate;
+        }
+        counter += 1;
+    }
+}
+
+/// 读取历史记录；文件无法解析时先改名备份再视为空历史，避免之后的写入覆盖原有记录
+fn read_events(path: &Path) -> Result<Vec<LockdownTriggeredPayload>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(path).map_err(|e| format!("读取事件历史文件失败: {}", e))?;
+    match serde_json::from_str::<Vec<LockdownTriggeredPayload>>(&content) {
+        Ok(events) => Ok(events),
+        Err(error) => {
+            let backup = backup_path(path);
+            fs::rename(path, &backup)
+                .map_err(|e| format!("事件历史文件解析失败且无法备份: {}, {}", error, e))?;
+            log::error!(
+                "事件历史文件解析失败: {}，已备份到 {}",
+                error,
+                backup.display()
+            );
+            Ok(Vec::new())
+        }
+    }
+}
+
+fn write_events(path: &Path, events: &[LockdownTriggeredPayload]) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(events)
+        .map_err(|e| format!("Failed to serialize event history: {}", e))?;
+    fs::write(path, content).map_err(|e| format!("Failed to write event history: {}", e))
+}
+
+/// 追加记录，超过 `max_events` 条时丢弃最早的记录
+fn append_event_to(
+    path: &Path,
+    event: LockdownTriggeredPayload,
+    max_events: usize,
+) -> Result<(), String> {
+    let mut events = read_events(path)?;
+    events.push(event);
+    if events.len() > max_events {
+        let overflow = events.len() - max_events;
+        events.drain(..overflow);
+    }
+    write_events(path, &events)
+}
+
+fn latest_events(
+    events: Vec<LockdownTriggeredPayload>,
+    limit: Option<usize>,
+) -> Vec<LockdownTriggeredPayload> {
+    let limit = limit.unwrap_or(events.len());
+    events.into_iter().rev().take(limit).collect()
+}
+
+/// 追加一条触发记录到持久化历史
+pub fn append_event(event: LockdownTriggeredPayload, max_events: u32) {
+    let _guard = EVENT_HISTORY_LOCK.lock().unwrap();
+    let result = get_event_history_path()
+        .and_then(|path| append_event_to(&path, event, max_events as usize));
+    if let Err(error) = result {
+        log::error!("保存触发事件历史失败: {}", error);
+    }
+}
+
+/// 获取触发事件历史（按时间倒序）
+#[tauri::command]
+pub fn get_event_history(limit: Option<usize>) -> Result<Vec<LockdownTriggeredPayload>, String> {
+    let _guard = EVENT_HISTORY_LOCK.lock().unwrap();
+    let path = get_event_history_path()?;
+    Ok(latest_events(read_events(&path)?, limit))
+}
+
+/// 清空触发事件历史
+#[tauri::command]
+pub fn clear_event_history() -> Result<(), String> {
+    let _guard = EVENT_HISTORY_LOCK.lock().unwrap();
+    let path = get_event_history_path()?;
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| format!("无法删除事件历史文件: {}", e))?;
+    }
+    log::info!("触发事件历史已清空");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{append_event_to, latest_events, read_events};
+    use crate::config::PostTriggerAction;
+    use crate::monitoring::{LockdownTriggeredPayload, TriggerSource};
+
+    fn sample_event(timestamp: &str) -> LockdownTriggeredPayload {
+        LockdownTriggeredPayload {
+            trigger_source: TriggerSource::Keyboard,
+            timestamp: timestamp.to_string(),
+            photo_path: Some("D:/captures/a.jpg".to_string()),
+            photo_paths: vec!["D:/captures/a.jpg".to_string()],
+            video_path: None,
+            prebuffer_video_path: None,
+            screenshot_path: None,
+            post_trigger_action: PostTriggerAction::CaptureAndLock,
+        }
+    }
+
+    #[test]
+    fn appended_events_roundtrip_through_file() {
+        let path =
+            std::env::temp_dir().join(format!("snaplock_events_test_{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        append_event_to(&path, sample_event("2024-01-01 10:00:00"), 500).unwrap();
+        append_event_to(&path, sample_event("2024-01-01 11:00:00"), 500).unwrap();
+
+        let events = read_events(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].timestamp, "2024-01-01 11:00:00");
+    }
+
+    #[test]
+    fn oldest_events_are_rotated_out_past_the_limit() {
+        let path = std::env::temp_dir().join(format!(
+            "snaplock_events_rotate_test_{}.json",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        for hour in 10..13 {
+            append_event_to(
+                &path,
+                sample_event(&format!("2024-01-01 {}:00:00", hour)),
+                2,
+            )
+            .unwrap();
+        }
+
+        let events = read_events(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].timestamp, "2024-01-01 11:00:00");
+        assert_eq!(events[1].timestamp, "2024-01-01 12:00:00");
+    }
+
+    #[test]
+    fn corrupt_history_is_backed_up_before_overwrite() {
+        let dir = std::env::temp_dir().join(format!(
+            "snaplock_events_corrupt_test_{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("events.json");
+        std::fs::write(&path, "[{\"timestamp\": ").unwrap();
+
+        append_event_to(&path, sample_event("2024-01-01 10:00:00"), 500).unwrap();
+
+        let events = read_events(&path).unwrap();
+        let backups: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().ends_with(".bak"))
+            .collect();
+        let backup_content = backups
+            .first()
+            .map(|entry| std::fs::read_to_string(entry.path()).unwrap());
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(backups.len(), 1);
+        assert_eq!(backup_content.as_deref(), Some("[{\"timestamp\": "));
+    }
+
+    #[test]
+    fn repeated_corruption_keeps_every_backup() {
+        let dir = std::env::temp_dir().join(format!(
+            "snaplock_events_repeat_corrupt_test_{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("events.json");
+
+        // 同一秒内连续两次损坏，两份备份都要保留
+        std::fs::write(&path, "first").unwrap();
+        read_events(&path).unwrap();
+        std::fs::write(&path, "second").unwrap();
+        read_events(&path).unwrap();
+
+        let mut backup_contents: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().ends_with(".bak"))
+            .map(|entry| std::fs::read_to_string(entry.path()).unwrap())
+            .collect();
+        backup_contents.sort();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(backup_contents, vec!["first", "second"]);
+    }
+
+    #[test]
+    fn latest_events_returns_newest_first_with_limit() {
+        let events = vec![
+            sample_event("2024-01-01 10:00:00"),
+            sample_event("2024-01-01 11:00:00"),
+            sample_event("2024-01-01 12:00:00"),
+        ];
+
+        let latest = latest_events(events, Some(2));
+
+        assert_eq!(latest.len(), 2);
+        assert_eq!(latest[0].timestamp, "2024-01-01 12:00:00");
+        assert_eq!(latest[1].timestamp, "2024-01-01 11:00:00");
+    }
+}
@@ -0,0 +1,178 @@
+//! 为拍摄的 JPEG 写入 EXIF 元数据（拍摄时间、摄像头名称、软件名），便于照片被复制后仍保留拍摄信息。
+//! 只需要写入少量 ASCII 标签，直接按 TIFF 结构生成 APP1 段，不引入额外依赖。
+
+const TAG_MAKE: u16 = 0x010F;
+const TAG_MODEL: u16 = 0x0110;
+const TAG_SOFTWARE: u16 = 0x0131;
+const TAG_DATE_TIME: u16 = 0x0132;
+const TAG_EXIF_IFD_POINTER: u16 = 0x8769;
+const TAG_DATE_TIME_ORIGINAL: u16 = 0x9003;
+
+const TYPE_ASCII: u16 = 2;
+const TYPE_LONG: u16 = 4;
+
+/// TIFF 头（小端序 + 魔数 42 + 第一个 IFD 的偏移 8）
+const TIFF_HEADER: [u8; 8] = [b'I', b'I', 0x2A, 0x00, 0x08, 0x00, 0x00, 0x00];
+
+struct Entry {
+    tag: u16,
+    field_type: u16,
+    count: u32,
+    data: Vec<u8>,
+}
+
+impl Entry {
+    /// EXIF 的 ASCII 类型只允许 7 位字符，其余字符替换为 `?`
+    fn ascii(tag: u16, value: &str) -> Self {
+        let mut data: Vec<u8> = value
+            .chars()
+            .map(|c| if c.is_ascii() { c as u8 } else { b'?' })
+            .collect();
+        data.push(0);
+        Self {
+            tag,
+            field_type: TYPE_ASCII,
+            count: data.len() as u32,
+            data,
+        }
+    }
+
+    fn long(tag: u16, value: u32) -> Self {
+        Self {
+            tag,
+            field_type: TYPE_LONG,
+            count: 1,
+            data: value.to_le_bytes().to_vec(),
+        }
+    }
+}
+
+/// 生成位于 TIFF 偏移 `start` 处的 IFD；超过 4 字节的值紧跟在 IFD 之后存放
+fn encode_ifd(start: u32, entries: &[Entry]) -> Vec<u8> {
+    let ifd_len = 2 + 12 * entries.len() as u32 + 4;
+    let mut ifd = Vec::new();
+    let mut values = Vec::new();
+
+    ifd.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    for entry in entries {
+        ifd.extend_from_slice(&entry.tag.to_le_bytes());
+        ifd.extend_from_slice(&entry.field_type.to_le_bytes());
+        ifd.extend_from_slice(&entry.count.to_le_bytes());
+        if entry.data.len() <= 4 {
+            let mut inline = [0u8; 4];
+            inline[..entry.data.len()].copy_from_slice(&entry.data);
+            ifd.extend_from_slice(&inline);
+        } else {
+            let offset = start + ifd_len + values.len() as u32;
+            ifd.extend_from_slice(&offset.to_le_bytes());
+            values.extend_from_slice(&entry.data);
+            // 值必须从偶数偏移开始
+            if values.len() % 2 == 1 {
+                values.push(0);
+            }
+        }
+    }
+    ifd.extend_from_slice(&0u32.to_le_bytes());
+    ifd.extend_from_slice(&values);
+    ifd
+}
+
+/// 生成完整的 APP1 (Exif) 段。`date_time` 格式为 `YYYY:MM:DD HH:MM:SS`
+pub fn capture_segment(date_time: &str, camera_name: &str) -> Vec<u8> {
+    let ifd0 = |exif_ifd_offset: u32| {
+        encode_ifd(
+            TIFF_HEADER.len() as u32,
+            &[
+                Entry::ascii(TAG_MAKE, camera_name),
+                Entry::ascii(TAG_MODEL, camera_name),
+                Entry::ascii(TAG_SOFTWARE, "SnapLock"),
+                Entry::ascii(TAG_DATE_TIME, date_time),
+                Entry::long(TAG_EXIF_IFD_POINTER, exif_ifd_offset),
+            ],
+        )
+    };
+    // 偏移值不影响 IFD0 的长度，先计算长度再写入真实偏移
+    let exif_ifd_offset = (TIFF_HEADER.len() + ifd0(0).len()) as u32;
+    let exif_ifd = encode_ifd(
+        exif_ifd_offset,
+        &[Entry::ascii(TAG_DATE_TIME_ORIGINAL, date_time)],
+    );
+
+    let mut payload = b"Exif\0\0".to_vec();
+    payload.extend_from_slice(&TIFF_HEADER);
+    payload.extend_from_slice(&ifd0(exif_ifd_offset));
+    payload.extend_from_slice(&exif_ifd);
+
+    let mut segment = vec![0xFF, 0xE1];
+    segment.extend_from_slice(&((payload.len() + 2) as u16).to_be_bytes());
+    segment.extend_from_slice(&payload);
+    segment
+}
+
+/// 将 APP1 段插入到 JPEG 的 SOI 标记之后；数据不是 JPEG 时原样返回
+pub fn insert_segment(jpeg: Vec<u8>, segment: &[u8]) -> Vec<u8> {
+    if !jpeg.starts_with(&[0xFF, 0xD8]) {
+        log::warn!("图像不是 JPEG 格式，跳过写入 EXIF");
+        return jpeg;
+    }
+
+    let mut output = Vec::with_capacity(jpeg.len() + segment.len());
+    output.extend_from_slice(&jpeg[..2]);
+    output.extend_from_slice(segment);
+    output.extend_from_slice(&jpeg[2..]);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TAG_DATE_TIME_ORIGINAL, TAG_EXIF_IFD_POINTER, capture_segment, insert_segment};
+
+    fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+        u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+    }
+
+    fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    /// 在 IFD 中查找标签，返回其值或偏移字段
+    fn find_tag(tiff: &[u8], ifd_offset: usize, tag: u16) -> Option<u32> {
+        let count = read_u16(tiff, ifd_offset) as usize;
+        (0..count)
+            .map(|index| ifd_offset + 2 + index * 12)
+            .find(|&entry| read_u16(tiff, entry) == tag)
+            .map(|entry| read_u32(tiff, entry + 8))
+    }
+
+    #[test]
+    fn segment_contains_readable_date_time_original() {
+        let segment = capture_segment("2024:03:07 08:15:30", "Integrated Camera");
+        assert_eq!(&segment[..2], &[0xFF, 0xE1]);
+        assert_eq!(
+            u16::from_be_bytes([segment[2], segment[3]]) as usize,
+            segment.len() - 2
+        );
+        assert_eq!(&segment[4..10], b"Exif\0\0");
+
+        let tiff = &segment[10..];
+        assert_eq!(&tiff[..4], b"II*\0");
+        let ifd0 = read_u32(tiff, 4) as usize;
+        let exif_ifd = find_tag(tiff, ifd0, TAG_EXIF_IFD_POINTER).unwrap() as usize;
+        let value_offset = find_tag(tiff, exif_ifd, TAG_DATE_TIME_ORIGINAL).unwrap() as usize;
+        assert_eq!(
+            &tiff[value_offset..value_offset + 20],
+            b"2024:03:07 08:15:30\0"
+        );
+        assert!(segment.windows(9).any(|window| window == b"SnapLock\0"));
+    }
+
+    #[test]
+    fn segment_is_inserted_after_soi_only_for_jpeg() {
+        let segment = [0xFF, 0xE1, 0x00, 0x02];
+        assert_eq!(
+            insert_segment(vec![0xFF, 0xD8, 0xFF, 0xD9], &segment),
+            vec![0xFF, 0xD8, 0xFF, 0xE1, 0x00, 0x02, 0xFF, 0xD9]
+        );
+        assert_eq!(insert_segment(vec![0x89, b'P'], &segment), vec![0x89, b'P']);
+    }
+}
@@ -0,0 +1,192 @@
+// snaplock/src-tauri/src/ffmpeg.rs
+//! 定位 ffmpeg 可执行文件：用户配置路径 -> 随应用打包的资源 -> 系统 PATH
+
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::state::AppState;
+
+const BUNDLED_FFMPEG: &str = "libs/ffmpeg/bin/ffmpeg.exe";
+
+#[cfg(target_os = "windows")]
+const FFMPEG_BINARY: &str = "ffmpeg.exe";
+#[cfg(not(target_os = "windows"))]
+const FFMPEG_BINARY: &str = "ffmpeg";
+
+/// 在 PATH 指定的目录中查找可执行文件
+fn find_in_path(binary: &str, path_var: Option<std::ffi::OsString>) -> Option<PathBuf> {
+    let path_var = path_var?;
+    std::env::split_paths(&path_var)
+        .map(|dir| dir.join(binary))
+        .find(|candidate| candidate.is_file())
+}
+
+/// 按优先级解析 ffmpeg 路径；配置了路径但文件不存在时直接报错，不再回退
+fn locate(configured: Option<&str>, bundled: Option<PathBuf>) -> Result<PathBuf, String> {
+    if let Some(configured) = configured.map(str::trim).filter(|path| !path.is_empty()) {
+        let path = Path::new(configured);
+        return if path.is_file() {
+            Ok(path.to_path_buf())
+        } else {
+            Err(format!("配置的 ffmpeg 路径不存在: {}", configured))
+        };
+    }
+
+    if let Some(bundled) = bundled.filter(|path| path.is_file()) {
+        return Ok(bundled);
+    }
+
+    find_in_path(FFMPEG_BINARY, std::env::var_os("PATH")).ok_or_else(|| {
+        "未找到 ffmpeg，请安装 ffmpeg 并加入 PATH，或在设置中指定 ffmpeg 路径".to_string()
+    })
+}
+
+/// 解析 ffmpeg 路径，失败时向前端发送 `ffmpeg_unavailable` 事件
+pub fn resolve(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let configured = app_handle.state::<AppState>().ffmpeg_path();
+    let bundled = app_handle
+        .path()
+        .resolve(BUNDLED_FFMPEG, tauri::path::BaseDirectory::Resource)
+        .ok();
+
+    let result = locate(configured.as_deref(), bundled);
+    match &result {
+        Ok(path) => log::debug!("使用 ffmpeg: {}", path.display()),
+        Err(error) => {
+            log::error!("{}", error);
+            if let Err(emit_error) = app_handle.emit("ffmpeg_unavailable", error) {
+                log::error!("无法发送 ffmpeg 不可用事件: {}", emit_error);
+            }
+        }
+    }
+    result
+}
+
+/// 从 `ffmpeg -list_devices true -f dshow -i dummy` 的输出中提取音频输入设备名称。
+/// 同时兼容新版（每行带 `(audio)` 标记）与旧版（按 "DirectShow audio devices" 分节）格式。
+fn parse_dshow_audio_devices(output: &str) -> Vec<String> {
+    let mut devices = Vec::new();
+    let mut in_audio_section = false;
+
+    for line in output.lines() {
+        if line.contains("DirectShow audio devices") {
+            in_audio_section = true;
+            continue;
+        }
+        if line.contains("DirectShow video devices") {
+            in_audio_section = false;
+            continue;
+        }
+        if line.contains("Alternative name") {
+            continue;
+        }
+
+        let Some(start) = line.find('"') else {
+            continue;
+        };
+        let Some(length) = line[start + 1..].find('"') else {
+            continue;
+        };
+        let name = &line[start + 1..start + 1 + length];
+        let marker = line[start + 2 + length..].trim();
+
+        let is_audio = match marker {
+            // 同时提供画面与声音的设备（如带麦克风的采集卡）也可作为音频输入
+            "(audio)" | "(audio, video)" => true,
+            "(video)" => false,
+            _ => in_audio_section,
+        };
+        if is_audio && !name.is_empty() && !devices.iter().any(|device| device == name) {
+            devices.push(name.to_string());
+        }
+    }
+
+    devices
+}
+
+/// 枚举可用于录制的麦克风；目前仅支持 Windows (dshow)，其他平台返回空列表
+pub fn list_audio_input_devices(app_handle: &AppHandle) -> Result<Vec<String>, String> {
+    if !cfg!(target_os = "windows") {
+        log::info!("当前平台暂不支持枚举音频输入设备");
+        return Ok(Vec::new());
+    }
+
+    let ffmpeg_path = resolve(app_handle)?;
+    let mut command = std::process::Command::new(ffmpeg_path);
+    command.args([
+        "-hide_banner",
+        "-list_devices",
+        "true",
+        "-f",
+        "dshow",
+        "-i",
+        "dummy",
+    ]);
+    crate::process_utils::configure_background_command(&mut command);
+
+    // ffmpeg 列出设备后以非零状态退出，设备列表输出在 stderr
+    let output = command
+        .output()
+        .map_err(|e| format!("枚举音频设备失败: {}", e))?;
+    let devices = parse_dshow_audio_devices(&String::from_utf8_lossy(&output.stderr));
+    log::info!("检测到 {} 个音频输入设备", devices.len());
+    Ok(devices)
+}
+
+/// 校验用户指定的 ffmpeg 路径
+pub fn validate_configured_path(path: &str) -> Result<(), String> {
+    locate(Some(path), None).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_in_path, locate, parse_dshow_audio_devices};
+
+    #[test]
+    fn parses_audio_devices_from_both_dshow_formats() {
+        let modern = r#"[dshow @ 0000] "Integrated Camera" (video)
+[dshow @ 0000]   Alternative name "@device_pnp_camera"
+[dshow @ 0000] "Microphone (Realtek Audio)" (audio)
+[dshow @ 0000]   Alternative name "@device_cm_mic"
+[dshow @ 0000] "Headset Mic" (audio)
+[dshow @ 0000] "USB Capture HDMI" (audio, video)"#;
+        assert_eq!(
+            parse_dshow_audio_devices(modern),
+            vec![
+                "Microphone (Realtek Audio)",
+                "Headset Mic",
+                "USB Capture HDMI"
+            ]
+        );
+
+        let legacy = r#"[dshow @ 0000] DirectShow video devices
+[dshow @ 0000]  "Integrated Camera"
+[dshow @ 0000] DirectShow audio devices
+[dshow @ 0000]  "Microphone (USB)"
+[dshow @ 0000]     Alternative name "@device_cm_usb""#;
+        assert_eq!(parse_dshow_audio_devices(legacy), vec!["Microphone (USB)"]);
+        assert!(parse_dshow_audio_devices("").is_empty());
+    }
+
+    #[test]
+    fn finds_binary_in_path_directories() {
+        let dir = std::env::temp_dir().join(format!("snaplock_ffmpeg_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let binary = dir.join("fake-ffmpeg");
+        std::fs::write(&binary, b"").unwrap();
+
+        let path_var =
+            std::env::join_paths([std::env::temp_dir().join("missing"), dir.clone()]).unwrap();
+        assert_eq!(find_in_path("fake-ffmpeg", Some(path_var)), Some(binary));
+        assert_eq!(find_in_path("fake-ffmpeg", None), None);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn missing_configured_path_is_an_error() {
+        let missing = std::env::temp_dir().join("snaplock_no_such_ffmpeg");
+        assert!(locate(Some(&missing.to_string_lossy()), None).is_err());
+    }
+}
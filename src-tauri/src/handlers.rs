@@ -1,13 +1,19 @@
 use crate::{
     camera,
-    constants::{PREPARATION_DELAY, SHORTCUT_DEBOUNCE_TIME, SHORTCUT_FLAG_CLEAR_DELAY},
+    constants::{
+        MAX_CAMERA_RECORDING_SECONDS, MAX_CAPTURE_TIMEOUT_MS, MAX_IDLE_TRIGGER_MINUTES,
+        MAX_PREPARATION_DELAY_SECONDS, MAX_RECORD_BEFORE_LOCK_SECONDS,
+        MAX_RECORDING_DURATION_SECONDS, MAX_RECORDING_IDLE_TIMEOUT_SECS,
+    },
+    i18n::{self, Language, Message},
     monitoring,
     state::{AppState, MonitoringFlags, MonitoringLifecycleLock, MonitoringState},
 };
 use std::sync::Arc;
-use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tauri::{AppHandle, Emitter, Manager};
-use tauri_plugin_notification::NotificationExt;
+use tauri_plugin_opener::OpenerExt;
 use tokio::sync::OwnedMutexGuard;
 
 fn emit_monitoring_status(app_handle: &AppHandle, status: &str) {
@@ -16,27 +22,19 @@ fn emit_monitoring_status(app_handle: &AppHandle, status: &str) {
     }
 }
 
-fn show_notification(app_handle: &AppHandle, body: &str) {
-    let state = app_handle.state::<AppState>();
-    if !state.enable_notifications() {
-        return;
-    }
-
-    if let Err(error) = app_handle
-        .notification()
-        .builder()
-        .title("SnapLock")
-        .body(body)
-        .show()
-    {
-        log::error!("无法显示通知: {}", error);
-    }
+fn show_notification(app_handle: &AppHandle, message: Message) {
+    crate::notifications::show(app_handle, &i18n::tr(app_handle, message));
 }
 
 fn reset_to_idle_state(state: &AppState, app_handle: &AppHandle, reason: &str) {
     log::info!("重置为空闲状态: {}", reason);
     let monitoring_flags = app_handle.state::<Arc<MonitoringFlags>>().inner().clone();
     monitoring_flags.stop_monitoring_thread();
+    // 停止取帧线程需要等待其退出，不在异步运行时的工作线程上阻塞
+    tauri::async_runtime::spawn_blocking(|| {
+        crate::prebuffer::stop();
+        crate::motion::stop();
+    });
 
     if let Err(error) = state.set_status(MonitoringState::Idle) {
         log::error!("无法重置状态为空闲: {} ({})", reason, error);
@@ -45,9 +43,9 @@ fn reset_to_idle_state(state: &AppState, app_handle: &AppHandle, reason: &str) {
     emit_monitoring_status(app_handle, "空闲");
 }
 
-fn schedule_shortcut_flag_clear(monitoring_flags: Arc<MonitoringFlags>) {
+fn schedule_shortcut_flag_clear(monitoring_flags: Arc<MonitoringFlags>, delay: Duration) {
     tokio::spawn(async move {
-        tokio::time::sleep(SHORTCUT_FLAG_CLEAR_DELAY).await;
+        tokio::time::sleep(delay).await;
         monitoring_flags.set_shortcut_in_progress(false);
         log::debug!("清除快捷键处理标志");
     });
@@ -72,7 +70,8 @@ fn begin_shortcut_toggle(
         .lock()
         .map_err(|_| "无法获取快捷键防抖锁".to_string())?;
 
-    if last_toggle_time.elapsed() < SHORTCUT_DEBOUNCE_TIME {
+    let state = app_handle.state::<AppState>();
+    if last_toggle_time.elapsed() < Duration::from_millis(state.shortcut_debounce_ms()) {
         log::debug!("快捷键防抖，忽略请求");
         return Err("debounced".to_string());
     }
@@ -85,14 +84,26 @@ fn begin_shortcut_toggle(
 
     monitoring_flags.set_last_shortcut_time(current_time);
     monitoring_flags.set_shortcut_in_progress(true);
+    monitoring_flags.begin_shortcut_key_tracking();
     log::debug!("设置快捷键处理标志，时间戳: {}", current_time);
-    schedule_shortcut_flag_clear(monitoring_flags.clone());
+    schedule_shortcut_flag_clear(
+        monitoring_flags.clone(),
+        Duration::from_millis(state.shortcut_flag_clear_delay_ms()),
+    );
     Ok(())
 }
 
 async fn cleanup_capture_processes() {
-    crate::recorder::stop_screen_recording();
-    if let Err(error) = camera::stop_video_recording().await {
+    if let Err(error) = tokio::task::spawn_blocking(crate::prebuffer::stop).await {
+        log::error!("停止预录缓冲失败: {}", error);
+    }
+    if let Err(error) = tokio::task::spawn_blocking(crate::motion::stop).await {
+        log::error!("停止画面变化检测失败: {}", error);
+    }
+    if let Err(error) = crate::recorder::stop_screen_recording().await {
+        log::error!("停止屏幕录制失败: {}", error);
+    }
+    if let Err(error) = crate::recorder::stop_webcam_recordings().await {
         log::error!("停止摄像头录像失败: {}", error);
     }
 }
@@ -117,7 +128,32 @@ where
     Ok(())
 }
 
-async fn start_monitoring_locked(app_handle: &AppHandle, camera_id: u32) -> Result<(), String> {
+/// 记录用户的警戒意图，供应用重启后恢复
+pub fn persist_armed_state(app_handle: &AppHandle, armed: bool) {
+    let state = app_handle.state::<AppState>();
+    if state.was_armed() == armed {
+        return;
+    }
+
+    state.set_was_armed(armed);
+    if let Err(error) = crate::config::save_config(app_handle.clone()) {
+        log::warn!("保存警戒状态失败: {}", error);
+    }
+}
+
+/// 无摄像头模式下仅在首次警戒时提示一次拍摄已禁用
+fn notify_no_camera_once(app_handle: &AppHandle) {
+    static NOTIFIED: AtomicBool = AtomicBool::new(false);
+    if !NOTIFIED.swap(true, Ordering::SeqCst) {
+        show_notification(app_handle, Message::NoCameraDetected);
+    }
+}
+
+async fn start_monitoring_locked(
+    app_handle: &AppHandle,
+    camera_id: u32,
+    skip_preparation: bool,
+) -> Result<(), String> {
     let monitoring_flags = app_handle.state::<Arc<MonitoringFlags>>().inner().clone();
     let state = app_handle.state::<AppState>();
 
@@ -126,9 +162,14 @@ async fn start_monitoring_locked(app_handle: &AppHandle, camera_id: u32) -> Resu
         return Ok(());
     }
 
-    camera::ensure_camera_available(camera_id)?;
-    if !camera::check_camera_permission(camera_id).await? {
-        return Err("无法访问选中的摄像头，请检查权限或设备占用".to_string());
+    if state.allow_no_camera() && !camera::has_cameras() {
+        log::warn!("未检测到摄像头，以无摄像头模式进入警戒");
+        notify_no_camera_once(app_handle);
+    } else {
+        camera::ensure_camera_available(camera_id)?;
+        if !camera::check_camera_permission(camera_id).await? {
+            return Err("无法访问选中的摄像头，请检查权限或设备占用".to_string());
+        }
     }
 
     monitoring::ensure_listener_started(app_handle.clone(), monitoring_flags.clone())?;
@@ -151,60 +192,131 @@ async fn start_monitoring_locked(app_handle: &AppHandle, camera_id: u32) -> Resu
         .map_err(|error| format!("无法进入准备状态: {}", error))?;
     emit_monitoring_status(app_handle, "准备中");
 
+    if skip_preparation {
+        log::info!("跳过准备期，立即进入警戒");
+        activate_monitoring_locked(app_handle);
+        return Ok(());
+    }
+
+    let preparation_delay = Duration::from_secs(state.preparation_delay_seconds().into());
+    log::info!("进入准备状态，{} 秒后开始监控", preparation_delay.as_secs());
+    if !preparation_delay.is_zero() {
+        crate::notifications::show_preparation(app_handle, state.preparation_delay_seconds());
+    }
+
     let app_handle_clone = app_handle.clone();
     tokio::spawn(async move {
-        tokio::time::sleep(PREPARATION_DELAY).await;
+        tokio::time::sleep(preparation_delay).await;
         let _lifecycle_guard = lock_monitoring_lifecycle(&app_handle_clone).await;
+        activate_monitoring_locked(&app_handle_clone);
+    });
 
-        let state = app_handle_clone.state::<AppState>();
-        let monitoring_flags = app_handle_clone
-            .state::<Arc<MonitoringFlags>>()
-            .inner()
-            .clone();
+    Ok(())
+}
 
-        if state.status() != MonitoringState::Preparing {
-            log::info!("监控准备已取消，当前状态: {:?}", state.status());
-            return;
-        }
+/// 准备期结束后进入警戒；调用方需持有生命周期锁
+fn activate_monitoring_locked(app_handle: &AppHandle) {
+    let state = app_handle.state::<AppState>();
+    let monitoring_flags = app_handle.state::<Arc<MonitoringFlags>>().inner().clone();
 
-        if !monitoring_flags.listener_ready() {
-            log::error!("输入监听器不可用，无法启动监控");
-            reset_to_idle_state(&state, &app_handle_clone, "输入监听器不可用");
-            return;
-        }
+    crate::notifications::dismiss_preparation();
+    if state.status() != MonitoringState::Preparing {
+        log::info!("监控准备已取消，当前状态: {:?}", state.status());
+        return;
+    }
 
-        if let Err(error) = state.set_status(MonitoringState::Active) {
-            log::error!("无法转换到激活状态: {}", error);
-            reset_to_idle_state(&state, &app_handle_clone, "无法进入警戒状态");
-            return;
-        }
+    if !monitoring_flags.listener_ready() {
+        log::error!("输入监听器不可用，无法启动监控");
+        reset_to_idle_state(&state, app_handle, "输入监听器不可用");
+        return;
+    }
 
-        if !monitoring_flags.start_monitoring_atomic() {
-            reset_to_idle_state(&state, &app_handle_clone, "监控已在运行中");
-            return;
-        }
+    if let Err(error) = state.set_status(MonitoringState::Active) {
+        log::error!("无法转换到激活状态: {}", error);
+        reset_to_idle_state(&state, app_handle, "无法进入警戒状态");
+        return;
+    }
 
-        if state.post_trigger_action() == crate::config::PostTriggerAction::ScreenRecording {
-            monitoring_flags.set_last_activity_time(0);
-            monitoring_flags.replace_idle_check_handle(monitoring::start_idle_check_loop(
-                app_handle_clone.clone(),
-                monitoring_flags.clone(),
-            ));
-        }
+    if !monitoring_flags.start_monitoring_atomic() {
+        reset_to_idle_state(&state, app_handle, "监控已在运行中");
+        return;
+    }
 
-        emit_monitoring_status(&app_handle_clone, "警戒中");
-        show_notification(&app_handle_clone, "已进入警戒状态，正在监控活动");
+    // 设置界面的实时预览会占用摄像头，进入警戒前先关闭
+    crate::preview::stop();
 
-        if let Some(window) = app_handle_clone.get_webview_window("main") {
-            if let Err(error) = window.hide() {
-                log::error!("隐藏主窗口失败: {}", error);
-            }
+    // 手动录制会让触发时的屏幕录制无法启动，进入警戒后交由监控流程管理
+    if crate::recorder::is_manual_recording_running() {
+        log::info!("进入警戒，停止手动开始的屏幕录制");
+        tokio::task::spawn_blocking(crate::recorder::stop_manual_recording);
+    }
+
+    // 提前在后台连接网络共享，触发时不必等待
+    if let (Some(save_path), Some(credentials)) = (state.save_path(), state.share_credentials()) {
+        crate::network_share::connect_in_background(&save_path, &credentials);
+    }
+
+    let prebuffer_seconds = state.prebuffer_seconds();
+    if prebuffer_seconds > 0 && camera::has_cameras() {
+        crate::prebuffer::start(
+            state.camera_id(),
+            prebuffer_seconds,
+            state.jpeg_quality(),
+            state.camera_fourcc(),
+        );
+    }
+
+    if state.motion_detection_enabled() && camera::has_cameras() {
+        if crate::prebuffer::is_running() {
+            // 同一摄像头无法同时被两个线程打开
+            log::warn!("预录缓冲正在占用摄像头，本次警戒不启用画面变化检测");
+        } else {
+            crate::motion::start(
+                app_handle.clone(),
+                crate::motion::MotionSettings {
+                    camera_id: state.camera_id(),
+                    fourcc: state.camera_fourcc(),
+                    threshold_percent: state.motion_threshold_percent(),
+                    sample_interval_ms: state.motion_sample_interval_ms(),
+                },
+            );
         }
+    }
 
-        log::info!("✓ 监控启动成功");
-    });
+    if state.trigger_mode() == crate::config::TriggerMode::Idle {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        monitoring_flags.set_last_activity_time(now);
+        monitoring_flags.replace_idle_check_handle(monitoring::start_idle_trigger_loop(
+            app_handle.clone(),
+            monitoring_flags.clone(),
+        ));
+    } else if state.idle_managed_recording() {
+        monitoring_flags.set_last_activity_time(0);
+        monitoring_flags.replace_idle_check_handle(monitoring::start_idle_check_loop(
+            app_handle.clone(),
+            monitoring_flags.clone(),
+        ));
+        // 持续屏幕录制的 ffmpeg 意外退出时自动重启，解除警戒后随之停止
+        monitoring_flags.replace_ffmpeg_watchdog_handle(monitoring::start_ffmpeg_watchdog(
+            app_handle.clone(),
+            monitoring_flags.clone(),
+        ));
+    }
 
-    Ok(())
+    emit_monitoring_status(app_handle, "警戒中");
+    show_notification(app_handle, Message::MonitoringArmed);
+    persist_armed_state(app_handle, true);
+
+    if let Some(window) = app_handle.get_webview_window("main") {
+        if let Err(error) = window.hide() {
+            log::error!("隐藏主窗口失败: {}", error);
+        }
+    }
+
+    log::info!("✓ 监控启动成功");
 }
 
 async fn stop_monitoring_locked(app_handle: &AppHandle) -> Result<(), String> {
@@ -217,7 +329,9 @@ async fn stop_monitoring_locked(app_handle: &AppHandle) -> Result<(), String> {
     );
 
     monitoring_flags.stop_monitoring_thread();
+    crate::notifications::dismiss_preparation();
     cleanup_capture_processes().await;
+    crate::network_share::disconnect();
 
     if current_status == MonitoringState::Idle {
         emit_monitoring_status(app_handle, "空闲");
@@ -231,13 +345,102 @@ async fn stop_monitoring_locked(app_handle: &AppHandle) -> Result<(), String> {
     emit_monitoring_status(app_handle, "空闲");
 
     if was_active {
-        show_notification(app_handle, "已退出警戒状态");
+        show_notification(app_handle, Message::MonitoringDisarmed);
     }
 
     log::info!("监控已成功停止");
     Ok(())
 }
 
+/// 准备通知上的“取消”按钮：仍处于准备期时放弃本次警戒并回到空闲
+pub async fn cancel_preparation(app_handle: &AppHandle) {
+    let _lifecycle_guard = lock_monitoring_lifecycle(app_handle).await;
+    let state = app_handle.state::<AppState>();
+    if state.status() != MonitoringState::Preparing {
+        log::info!("当前不在准备期，忽略取消请求: {:?}", state.status());
+        return;
+    }
+
+    match stop_monitoring_locked(app_handle).await {
+        Ok(()) => {
+            persist_armed_state(app_handle, false);
+            log::info!("已取消进入警戒");
+        }
+        Err(error) => {
+            log::error!("取消准备失败: {}", error);
+            reset_to_idle_state(&state, app_handle, "取消准备失败");
+        }
+    }
+}
+
+/// 将状态重置为空闲后重新走一遍准备 -> 警戒流程
+pub async fn rearm_monitoring(app_handle: &AppHandle) -> Result<(), String> {
+    let _lifecycle_guard = lock_monitoring_lifecycle(app_handle).await;
+    let monitoring_flags = app_handle.state::<Arc<MonitoringFlags>>().inner().clone();
+    let state = app_handle.state::<AppState>();
+
+    monitoring_flags.stop_monitoring_thread();
+    // 预录缓冲与画面变化检测占用摄像头，需先释放才能通过启动前的摄像头检查
+    if let Err(error) = tokio::task::spawn_blocking(crate::prebuffer::stop).await {
+        log::error!("停止预录缓冲失败: {}", error);
+    }
+    if let Err(error) = tokio::task::spawn_blocking(crate::motion::stop).await {
+        log::error!("停止画面变化检测失败: {}", error);
+    }
+    if state.status() != MonitoringState::Idle {
+        state
+            .set_status(MonitoringState::Idle)
+            .map_err(|error| format!("无法重置为空闲状态: {}", error))?;
+    }
+
+    let camera_id = state.camera_id();
+    let result = start_monitoring_locked(app_handle, camera_id, false).await;
+    if let Err(error) = &result {
+        reset_to_idle_state(&state, app_handle, error);
+    }
+    result
+}
+
+/// 应用退出前停止监控，但保留警戒意图以便下次启动时恢复
+pub async fn shutdown_monitoring(app_handle: &AppHandle) -> Result<(), String> {
+    let _lifecycle_guard = lock_monitoring_lifecycle(app_handle).await;
+    stop_monitoring_locked(app_handle).await
+}
+
+/// 启动时根据重启前的警戒状态决定是否自动恢复警戒
+pub async fn restore_armed_state(app_handle: &AppHandle) {
+    let state = app_handle.state::<AppState>();
+    if !state.was_armed() {
+        return;
+    }
+
+    if state.restore_armed_state() {
+        log::info!("应用重启前处于警戒状态，正在自动恢复");
+        show_notification(app_handle, Message::RestoreArmedResuming);
+        auto_arm_on_start(app_handle).await;
+    } else {
+        log::info!("应用重启前处于警戒状态，未启用自动恢复");
+        show_notification(app_handle, Message::RestoreArmedSkipped);
+        persist_armed_state(app_handle, false);
+    }
+}
+
+/// 应用启动时自动进入警戒状态
+pub async fn auto_arm_on_start(app_handle: &AppHandle) {
+    let _lifecycle_guard = lock_monitoring_lifecycle(app_handle).await;
+    let state = app_handle.state::<AppState>();
+    let camera_id = state.camera_id();
+
+    if let Err(error) = start_monitoring_locked(app_handle, camera_id, false).await {
+        log::error!("启动时自动警戒失败: {}", error);
+        reset_to_idle_state(&state, app_handle, "启动时自动警戒失败");
+        crate::notifications::show(
+            app_handle,
+            &i18n::tr_with(app_handle, Message::AutoArmFailed, &[("error", &error)]),
+        );
+    }
+}
+
 pub async fn toggle_monitoring(app_handle: &AppHandle) {
     let monitoring_flags = app_handle.state::<Arc<MonitoringFlags>>().inner().clone();
 
@@ -260,9 +463,13 @@ pub async fn toggle_monitoring(app_handle: &AppHandle) {
     log::info!("切换监控状态请求，当前状态: {:?}", current_status);
 
     let result = match current_status {
-        MonitoringState::Idle => start_monitoring_locked(app_handle, current_camera_id).await,
+        MonitoringState::Idle => {
+            start_monitoring_locked(app_handle, current_camera_id, false).await
+        }
         MonitoringState::Preparing | MonitoringState::Active | MonitoringState::Triggered => {
-            stop_monitoring_locked(app_handle).await
+            stop_monitoring_locked(app_handle)
+                .await
+                .map(|()| persist_armed_state(app_handle, false))
         }
     };
 
@@ -272,6 +479,39 @@ pub async fn toggle_monitoring(app_handle: &AppHandle) {
     }
 }
 
+/// 紧急快捷键：立即拍照、锁屏，并跳过准备期直接进入警戒
+pub async fn panic_lock(app_handle: &AppHandle) {
+    let monitoring_flags = app_handle.state::<Arc<MonitoringFlags>>().inner().clone();
+
+    // 与警戒快捷键共用防抖与事件忽略窗口，避免按键本身触发警戒
+    if let Err(error) = begin_shortcut_toggle(app_handle, &monitoring_flags) {
+        if error != "debounced" {
+            log::error!("处理紧急快捷键失败: {}", error);
+        }
+        return;
+    }
+
+    let _lifecycle_guard = lock_monitoring_lifecycle(app_handle).await;
+    let state = app_handle.state::<AppState>();
+    log::info!("紧急快捷键触发，当前状态: {:?}", state.status());
+
+    monitoring::panic_capture_and_lock(app_handle).await;
+
+    let result = match state.status() {
+        MonitoringState::Idle => start_monitoring_locked(app_handle, state.camera_id(), true).await,
+        MonitoringState::Preparing => {
+            activate_monitoring_locked(app_handle);
+            Ok(())
+        }
+        MonitoringState::Active | MonitoringState::Triggered => Ok(()),
+    };
+
+    if let Err(error) = result {
+        log::error!("紧急快捷键进入警戒失败: {}", error);
+        reset_to_idle_state(&state, app_handle, "紧急快捷键进入警戒失败");
+    }
+}
+
 #[tauri::command]
 pub fn set_camera_id(app_handle: tauri::AppHandle, camera_id: u32) -> Result<(), String> {
     camera::ensure_camera_available(camera_id)?;
@@ -284,13 +524,110 @@ pub fn set_camera_id(app_handle: tauri::AppHandle, camera_id: u32) -> Result<(),
 #[tauri::command]
 pub async fn start_monitoring_command(app_handle: AppHandle, camera_id: u32) -> Result<(), String> {
     let _lifecycle_guard = lock_monitoring_lifecycle(&app_handle).await;
-    start_monitoring_locked(&app_handle, camera_id).await
+    start_monitoring_locked(&app_handle, camera_id, false).await
+}
+
+/// 在空闲状态下模拟一次触发，用于检查摄像头、保存路径与通知是否正常；不会锁屏或退出
+#[tauri::command]
+pub async fn simulate_trigger(app_handle: AppHandle) -> Result<(), String> {
+    let action_generation = {
+        let _lifecycle_guard = lock_monitoring_lifecycle(&app_handle).await;
+        if app_handle.state::<AppState>().status() != MonitoringState::Idle {
+            return Err("请先退出警戒状态再模拟触发".to_string());
+        }
+        app_handle
+            .state::<Arc<MonitoringFlags>>()
+            .begin_simulation()
+    };
+
+    log::info!("开始模拟触发");
+    crate::preview::stop_async().await;
+    crate::monitoring::run_simulated_trigger(app_handle, action_generation).await;
+    Ok(())
+}
+
+/// 立即用当前摄像头拍一张照片，用于检查画面与保存路径；不经过也不改变监控状态
+#[tauri::command]
+pub async fn capture_now(app_handle: AppHandle) -> Result<String, String> {
+    let (camera_id, photo_options) = {
+        let state = app_handle.state::<AppState>();
+        let photo_options = camera::PhotoOptions {
+            capture_delay_seconds: 0,
+            burst_count: 1,
+            ..camera::PhotoOptions::from_state(&state)
+        };
+        (state.camera_id(), photo_options)
+    };
+    let save_path = crate::storage::resolve_capture_save_path(&app_handle);
+
+    log::info!("手动拍照，摄像头 {}", camera_id);
+    crate::preview::stop_async().await;
+    let photo_paths = camera::take_photo(&app_handle, camera_id, save_path, photo_options)
+        .await
+        .map_err(|error| format!("拍照失败: {}", error))?;
+    photo_paths
+        .into_iter()
+        .next()
+        .ok_or_else(|| "拍照失败: 未生成照片".to_string())
+}
+
+/// 在空闲或准备状态下手动开始屏幕录制，用于确认录屏是否正常；返回输出文件路径。
+/// 进入警戒时手动录制会自动停止，由监控流程接管屏幕录制。
+#[tauri::command]
+pub async fn start_recording_now(app_handle: AppHandle) -> Result<String, String> {
+    if !matches!(
+        app_handle.state::<AppState>().status(),
+        MonitoringState::Idle | MonitoringState::Preparing
+    ) {
+        return Err("请先退出警戒状态再手动录制".to_string());
+    }
+
+    log::info!("手动开始屏幕录制");
+    crate::recorder::start_manual_recording(app_handle).await
+}
+
+/// 停止手动开始的屏幕录制
+#[tauri::command]
+pub async fn stop_recording_now() -> Result<(), String> {
+    if !crate::recorder::is_manual_recording_running() {
+        return Err("当前没有手动开始的屏幕录制".to_string());
+    }
+
+    log::info!("手动停止屏幕录制");
+    tokio::task::spawn_blocking(crate::recorder::stop_manual_recording)
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
 }
 
 #[tauri::command]
 pub async fn stop_monitoring_command(app_handle: AppHandle) -> Result<(), String> {
     let _lifecycle_guard = lock_monitoring_lifecycle(&app_handle).await;
-    stop_monitoring_locked(&app_handle).await
+    stop_monitoring_locked(&app_handle).await?;
+    persist_armed_state(&app_handle, false);
+    Ok(())
+}
+
+/// 当前监控状态快照，供前端在窗口重新显示时同步
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MonitoringStatusInfo {
+    pub status: MonitoringState,
+    pub label: &'static str,
+    pub listener_alive: bool,
+    pub valid_next_states: Vec<MonitoringState>,
+}
+
+#[tauri::command]
+pub fn get_monitoring_status(app_handle: AppHandle) -> Result<MonitoringStatusInfo, String> {
+    let status = app_handle.state::<AppState>().status();
+    let monitoring_flags = app_handle.state::<Arc<MonitoringFlags>>().inner().clone();
+
+    Ok(MonitoringStatusInfo {
+        status,
+        label: status.label(),
+        listener_alive: monitoring_flags.listener_ready()
+            && monitoring_flags.is_listener_thread_alive(),
+        valid_next_states: status.valid_next_states(),
+    })
 }
 
 #[tauri::command]
@@ -299,16 +636,23 @@ pub fn get_shortcut_key(app_handle: tauri::AppHandle) -> Result<String, String>
     Ok(state.shortcut_key())
 }
 
+/// 获取实际注册成功的快捷键；注册失败或仍在重试时返回 None
+#[tauri::command]
+pub fn get_active_shortcut(app_handle: tauri::AppHandle) -> Option<String> {
+    app_handle.state::<AppState>().active_shortcut()
+}
+
 #[tauri::command]
 pub async fn set_shortcut_key(
     app_handle: tauri::AppHandle,
     shortcut: String,
 ) -> Result<(), String> {
-    if !is_valid_shortcut(&shortcut) {
-        return Err("无效的快捷键格式".to_string());
-    }
+    let shortcut = normalize_shortcut(&shortcut)?;
 
     let state = app_handle.state::<AppState>();
+    if state.panic_shortcut_key().as_deref() == Some(shortcut.as_str()) {
+        return Err("快捷键不能与紧急快捷键相同".to_string());
+    }
     let old_shortcut = state.shortcut_key();
 
     crate::app_setup::update_global_shortcut(&app_handle, &old_shortcut, &shortcut)
@@ -340,22 +684,120 @@ pub async fn set_shortcut_key(
     Ok(())
 }
 
-fn is_valid_shortcut(shortcut: &str) -> bool {
-    let parts: Vec<&str> = shortcut.split('+').collect();
-    if parts.len() < 2 {
-        return false;
+#[tauri::command]
+pub fn get_panic_shortcut_key(app_handle: tauri::AppHandle) -> Result<Option<String>, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.panic_shortcut_key())
+}
+
+/// 设置紧急快捷键，传入空值表示不使用
+#[tauri::command]
+pub fn set_panic_shortcut_key(
+    app_handle: tauri::AppHandle,
+    shortcut: Option<String>,
+) -> Result<(), String> {
+    let shortcut = shortcut
+        .filter(|shortcut| !shortcut.trim().is_empty())
+        .map(|shortcut| normalize_shortcut(&shortcut))
+        .transpose()?;
+
+    let state = app_handle.state::<AppState>();
+    if shortcut.as_deref() == Some(state.shortcut_key().as_str()) {
+        return Err("紧急快捷键不能与警戒快捷键相同".to_string());
+    }
+    let old_shortcut = state.panic_shortcut_key();
+
+    crate::app_setup::update_panic_shortcut(
+        &app_handle,
+        old_shortcut.as_deref(),
+        shortcut.as_deref(),
+    )
+    .map_err(|error| format!("紧急快捷键注册失败: {}", error))?;
+
+    if let Err(error) = persist_state_change(
+        &app_handle,
+        |state| state.set_panic_shortcut_key(shortcut.clone()),
+        |state| state.set_panic_shortcut_key(old_shortcut.clone()),
+    ) {
+        if let Err(rollback_error) = crate::app_setup::update_panic_shortcut(
+            &app_handle,
+            shortcut.as_deref(),
+            old_shortcut.as_deref(),
+        ) {
+            log::error!("回滚紧急快捷键失败: {}", rollback_error);
+        }
+        return Err(error);
+    }
+
+    log::info!("紧急快捷键已更新为: {:?}", shortcut);
+    Ok(())
+}
+
+/// 修饰键的规范名称，顺序即规范化后的输出顺序
+const SHORTCUT_MODIFIERS: [&str; 4] = ["Ctrl", "Alt", "Shift", "Super"];
+
+fn canonical_modifier(name: &str) -> Option<&'static str> {
+    match name.to_ascii_lowercase().as_str() {
+        "ctrl" | "control" => Some("Ctrl"),
+        "alt" | "option" => Some("Alt"),
+        "shift" => Some("Shift"),
+        "super" | "meta" | "cmd" | "command" => Some("Super"),
+        _ => None,
+    }
+}
+
+fn canonical_key_name(key: &str) -> String {
+    if key.chars().count() <= 3 {
+        return key.to_ascii_uppercase();
+    }
+
+    let mut chars = key.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+        None => String::new(),
     }
+}
+
+/// 校验并规范化快捷键字符串（如 "alt+l" -> "Alt+L"）
+fn normalize_shortcut(shortcut: &str) -> Result<String, String> {
+    let mut modifiers: Vec<&'static str> = Vec::new();
+    let mut key: Option<String> = None;
 
-    let modifiers = &parts[..parts.len() - 1];
-    let key = parts.last().unwrap_or(&"");
+    for part in shortcut.split('+').map(str::trim) {
+        if part.is_empty() {
+            return Err("快捷键中包含空的按键".to_string());
+        }
 
-    for modifier in modifiers {
-        if !matches!(*modifier, "Ctrl" | "Alt" | "Shift" | "Meta" | "Cmd") {
-            return false;
+        if let Some(modifier) = canonical_modifier(part) {
+            if modifiers.contains(&modifier) {
+                return Err(format!("重复的修饰键: {}", modifier));
+            }
+            modifiers.push(modifier);
+        } else if key.is_some() {
+            return Err("快捷键只能包含一个非修饰键".to_string());
+        } else {
+            key = Some(canonical_key_name(part));
         }
     }
 
-    !key.is_empty() && !matches!(*key, "Ctrl" | "Alt" | "Shift" | "Meta" | "Cmd")
+    let key = key.ok_or_else(|| "快捷键缺少非修饰键".to_string())?;
+    if modifiers.is_empty() {
+        return Err("快捷键至少需要一个修饰键 (Ctrl/Alt/Shift/Super)".to_string());
+    }
+
+    let mut parts: Vec<String> = SHORTCUT_MODIFIERS
+        .iter()
+        .filter(|modifier| modifiers.contains(modifier))
+        .map(|modifier| modifier.to_string())
+        .collect();
+    parts.push(key.clone());
+    let normalized = parts.join("+");
+
+    normalized
+        .parse::<tauri_plugin_global_shortcut::Shortcut>()
+        .map_err(|_| format!("无法识别的按键: {}", key))?;
+
+    Ok(normalized)
 }
 
 #[tauri::command]
@@ -525,90 +967,2196 @@ pub fn set_post_trigger_action(
 }
 
 #[tauri::command]
-pub fn get_default_camera_id(app_handle: tauri::AppHandle) -> Result<Option<u32>, String> {
+pub fn get_trigger_mode(
+    app_handle: tauri::AppHandle,
+) -> Result<crate::config::TriggerMode, String> {
     let state = app_handle.state::<AppState>();
-    Ok(state.default_camera_id())
+    Ok(state.trigger_mode())
 }
 
+/// 设置触发方式，修改将在下次进入警戒时生效
 #[tauri::command]
-pub fn set_default_camera_id(
+pub fn set_trigger_mode(
     app_handle: tauri::AppHandle,
-    camera_id: Option<u32>,
+    mode: crate::config::TriggerMode,
 ) -> Result<(), String> {
-    if let Some(id) = camera_id {
-        camera::ensure_camera_available(id)?;
+    let state = app_handle.state::<AppState>();
+    let old_mode = state.trigger_mode();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_trigger_mode(mode),
+        |state| state.set_trigger_mode(old_mode),
+    )?;
+
+    log::info!("触发方式已更新为: {:?}", mode);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_idle_trigger_minutes(app_handle: tauri::AppHandle) -> Result<u32, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.idle_trigger_minutes())
+}
+
+#[tauri::command]
+pub fn set_idle_trigger_minutes(app_handle: tauri::AppHandle, minutes: u32) -> Result<(), String> {
+    if minutes == 0 || minutes > MAX_IDLE_TRIGGER_MINUTES {
+        return Err(format!(
+            "无活动触发时间必须在 1 到 {} 分钟之间",
+            MAX_IDLE_TRIGGER_MINUTES
+        ));
     }
 
     let state = app_handle.state::<AppState>();
-    let old_default_camera_id = state.default_camera_id();
-    let old_camera_id = state.camera_id();
+    let old_minutes = state.idle_trigger_minutes();
 
     persist_state_change(
         &app_handle,
-        |state| {
-            state.set_default_camera_id(camera_id);
-            if let Some(id) = camera_id {
-                state.set_camera_id(id);
-            }
-        },
-        |state| {
-            state.set_default_camera_id(old_default_camera_id);
-            state.set_camera_id(old_camera_id);
-        },
+        |state| state.set_idle_trigger_minutes(minutes),
+        |state| state.set_idle_trigger_minutes(old_minutes),
     )?;
 
-    log::info!("默认摄像头ID设置已更新为: {:?}", camera_id);
+    log::info!("无活动触发时间已更新为: {}分钟", minutes);
     Ok(())
 }
 
 #[tauri::command]
-pub fn get_capture_delay_seconds(app_handle: tauri::AppHandle) -> Result<u32, String> {
+pub fn get_camera_recording_seconds(app_handle: tauri::AppHandle) -> Result<u32, String> {
     let state = app_handle.state::<AppState>();
-    Ok(state.capture_delay_seconds())
+    Ok(state.camera_recording_seconds())
 }
 
+/// 设置触发后摄像头录像的时长（秒）
 #[tauri::command]
-pub fn set_capture_delay_seconds(app_handle: tauri::AppHandle, delay: u32) -> Result<(), String> {
-    if delay > 60 {
-        return Err("拍摄延迟必须在 0 到 60 秒之间".to_string());
+pub fn set_camera_recording_seconds(
+    app_handle: tauri::AppHandle,
+    seconds: u32,
+) -> Result<(), String> {
+    if !(1..=MAX_CAMERA_RECORDING_SECONDS).contains(&seconds) {
+        return Err(format!(
+            "摄像头录像时长必须在 1 到 {} 秒之间",
+            MAX_CAMERA_RECORDING_SECONDS
+        ));
     }
 
     let state = app_handle.state::<AppState>();
-    let old_delay = state.capture_delay_seconds();
+    let old_seconds = state.camera_recording_seconds();
 
     persist_state_change(
         &app_handle,
-        |state| state.set_capture_delay_seconds(delay),
-        |state| state.set_capture_delay_seconds(old_delay),
+        |state| state.set_camera_recording_seconds(seconds),
+        |state| state.set_camera_recording_seconds(old_seconds),
     )?;
 
-    log::info!("拍摄延迟时间设置已更新为: {}秒", delay);
+    log::info!("摄像头录像时长已更新为: {}秒", seconds);
     Ok(())
 }
 
 #[tauri::command]
-pub fn get_capture_mode(
-    app_handle: tauri::AppHandle,
-) -> Result<crate::config::CaptureMode, String> {
+pub fn get_recording_idle_timeout(app_handle: tauri::AppHandle) -> Result<u32, String> {
     let state = app_handle.state::<AppState>();
-    Ok(state.capture_mode())
+    Ok(state.recording_idle_timeout_secs())
 }
 
+/// 设置屏幕录制无操作多少秒后暂停，0 表示始终录制
 #[tauri::command]
-pub fn set_capture_mode(
+pub fn set_recording_idle_timeout(
     app_handle: tauri::AppHandle,
-    mode: crate::config::CaptureMode,
+    seconds: u32,
 ) -> Result<(), String> {
+    if seconds > MAX_RECORDING_IDLE_TIMEOUT_SECS {
+        return Err(format!(
+            "录制空闲暂停时间不能超过 {} 秒",
+            MAX_RECORDING_IDLE_TIMEOUT_SECS
+        ));
+    }
+
     let state = app_handle.state::<AppState>();
-    let old_mode = state.capture_mode();
-    let new_mode = mode.clone();
+    let old_seconds = state.recording_idle_timeout_secs();
 
     persist_state_change(
         &app_handle,
-        |state| state.set_capture_mode(new_mode.clone()),
-        |state| state.set_capture_mode(old_mode.clone()),
+        |state| state.set_recording_idle_timeout_secs(seconds),
+        |state| state.set_recording_idle_timeout_secs(old_seconds),
     )?;
 
-    log::info!("拍摄模式设置已更新为: {:?}", mode);
+    if seconds == 0 {
+        log::info!("屏幕录制已设置为不因无操作而暂停");
+    } else {
+        log::info!("录制空闲暂停时间已更新为: {}秒", seconds);
+    }
     Ok(())
 }
+
+#[tauri::command]
+pub fn get_default_camera_id(app_handle: tauri::AppHandle) -> Result<Option<u32>, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.default_camera_id())
+}
+
+#[tauri::command]
+pub fn set_default_camera_id(
+    app_handle: tauri::AppHandle,
+    camera_id: Option<u32>,
+) -> Result<(), String> {
+    if let Some(id) = camera_id {
+        camera::ensure_camera_available(id)?;
+    }
+
+    let state = app_handle.state::<AppState>();
+    let old_default_camera_id = state.default_camera_id();
+    let old_camera_id = state.camera_id();
+
+    persist_state_change(
+        &app_handle,
+        |state| {
+            state.set_default_camera_id(camera_id);
+            if let Some(id) = camera_id {
+                state.set_camera_id(id);
+            }
+        },
+        |state| {
+            state.set_default_camera_id(old_default_camera_id);
+            state.set_camera_id(old_camera_id);
+        },
+    )?;
+
+    log::info!("默认摄像头ID设置已更新为: {:?}", camera_id);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_capture_delay_seconds(app_handle: tauri::AppHandle) -> Result<u32, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.capture_delay_seconds())
+}
+
+#[tauri::command]
+pub fn set_capture_delay_seconds(app_handle: tauri::AppHandle, delay: u32) -> Result<(), String> {
+    if delay > 60 {
+        return Err("拍摄延迟必须在 0 到 60 秒之间".to_string());
+    }
+
+    let state = app_handle.state::<AppState>();
+    let old_delay = state.capture_delay_seconds();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_capture_delay_seconds(delay),
+        |state| state.set_capture_delay_seconds(old_delay),
+    )?;
+
+    log::info!("拍摄延迟时间设置已更新为: {}秒", delay);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_capture_mode(
+    app_handle: tauri::AppHandle,
+) -> Result<crate::config::CaptureMode, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.capture_mode())
+}
+
+#[tauri::command]
+pub fn set_capture_mode(
+    app_handle: tauri::AppHandle,
+    mode: crate::config::CaptureMode,
+) -> Result<(), String> {
+    let mode = crate::config::normalize_capture_mode(mode);
+    let state = app_handle.state::<AppState>();
+    let old_mode = state.capture_mode();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_capture_mode(mode),
+        |state| state.set_capture_mode(old_mode),
+    )?;
+
+    log::info!("拍摄模式设置已更新为: {:?}", mode);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_capture_image_format(
+    app_handle: tauri::AppHandle,
+) -> Result<crate::config::ImageFormat, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.capture_image_format())
+}
+
+#[tauri::command]
+pub fn set_capture_image_format(
+    app_handle: tauri::AppHandle,
+    format: crate::config::ImageFormat,
+) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let old_format = state.capture_image_format();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_capture_image_format(format),
+        |state| state.set_capture_image_format(old_format),
+    )?;
+
+    log::info!("照片保存格式已更新为: {:?}", format);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_watermark_enabled(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.watermark_enabled())
+}
+
+#[tauri::command]
+pub fn set_watermark_enabled(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let old_enabled = state.watermark_enabled();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_watermark_enabled(enabled),
+        |state| state.set_watermark_enabled(old_enabled),
+    )?;
+
+    log::info!("照片时间水印已{}", if enabled { "开启" } else { "关闭" });
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_max_segment_seconds(app_handle: tauri::AppHandle) -> Result<u32, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.max_segment_seconds())
+}
+
+#[tauri::command]
+pub fn set_max_segment_seconds(app_handle: tauri::AppHandle, seconds: u32) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let old_seconds = state.max_segment_seconds();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_max_segment_seconds(seconds),
+        |state| state.set_max_segment_seconds(old_seconds),
+    )?;
+
+    log::info!("录制分段时长设置已更新为: {}秒", seconds);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_recording_duration_seconds(app_handle: tauri::AppHandle) -> Result<u32, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.recording_duration_seconds())
+}
+
+/// 设置屏幕录制模式下触发后的固定录制时长，0 表示按活动持续录制
+#[tauri::command]
+pub fn set_recording_duration_seconds(
+    app_handle: tauri::AppHandle,
+    seconds: u32,
+) -> Result<(), String> {
+    if seconds > MAX_RECORDING_DURATION_SECONDS {
+        return Err(format!(
+            "录制时长必须在 0 到 {} 秒之间",
+            MAX_RECORDING_DURATION_SECONDS
+        ));
+    }
+
+    let state = app_handle.state::<AppState>();
+    let old_seconds = state.recording_duration_seconds();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_recording_duration_seconds(seconds),
+        |state| state.set_recording_duration_seconds(old_seconds),
+    )?;
+
+    log::info!("固定录制时长设置已更新为: {}秒", seconds);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_ffmpeg_path(app_handle: tauri::AppHandle) -> Result<Option<String>, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.ffmpeg_path())
+}
+
+/// 设置 ffmpeg 可执行文件路径，传入空值时恢复为内置或 PATH 中的 ffmpeg
+#[tauri::command]
+pub fn set_ffmpeg_path(app_handle: tauri::AppHandle, path: Option<String>) -> Result<(), String> {
+    let path = path
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty());
+    if let Some(path) = &path {
+        crate::ffmpeg::validate_configured_path(path)?;
+    }
+
+    let state = app_handle.state::<AppState>();
+    let old_path = state.ffmpeg_path();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_ffmpeg_path(path.clone()),
+        |state| state.set_ffmpeg_path(old_path.clone()),
+    )?;
+
+    log::info!("ffmpeg 路径设置已更新为: {:?}", path);
+    Ok(())
+}
+
+/// 返回当前实际使用的 ffmpeg 路径，找不到时返回错误说明
+#[tauri::command]
+pub fn check_ffmpeg(app_handle: tauri::AppHandle) -> Result<String, String> {
+    crate::ffmpeg::resolve(&app_handle).map(|path| path.to_string_lossy().to_string())
+}
+
+/// 自检单项的结果；`Skipped` 表示按当前设置无需检查
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub enum SelfCheckStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SelfCheckItem {
+    /// 检查项标识：camera / save_path / ffmpeg / notifications / shortcut
+    pub name: &'static str,
+    pub status: SelfCheckStatus,
+    pub detail: String,
+}
+
+impl SelfCheckItem {
+    fn new(name: &'static str, status: SelfCheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SelfCheckReport {
+    /// 所有检查项均未失败
+    pub passed: bool,
+    pub items: Vec<SelfCheckItem>,
+}
+
+async fn self_check_camera(app_handle: &AppHandle) -> SelfCheckItem {
+    let state = app_handle.state::<AppState>();
+    let camera_id = state.camera_id();
+
+    if !camera::has_cameras() {
+        return if state.allow_no_camera() {
+            SelfCheckItem::new(
+                "camera",
+                SelfCheckStatus::Skipped,
+                "未检测到摄像头，已允许无摄像头警戒",
+            )
+        } else {
+            SelfCheckItem::new("camera", SelfCheckStatus::Failed, "未检测到摄像头")
+        };
+    }
+    if state.status() != MonitoringState::Idle {
+        // 警戒期间预录缓冲或画面变化检测可能正占用摄像头
+        return SelfCheckItem::new(
+            "camera",
+            SelfCheckStatus::Skipped,
+            "警戒期间摄像头可能被占用，请解除警戒后再检查",
+        );
+    }
+
+    match camera::check_camera_permission(camera_id).await {
+        Ok(true) => SelfCheckItem::new(
+            "camera",
+            SelfCheckStatus::Passed,
+            format!("摄像头 {} 可以正常打开", camera_id),
+        ),
+        Ok(false) => SelfCheckItem::new(
+            "camera",
+            SelfCheckStatus::Failed,
+            format!(
+                "无法打开摄像头 {}，请检查权限或是否被其他程序占用",
+                camera_id
+            ),
+        ),
+        Err(error) => SelfCheckItem::new("camera", SelfCheckStatus::Failed, error),
+    }
+}
+
+async fn self_check_save_path(app_handle: &AppHandle) -> SelfCheckItem {
+    let app_handle = app_handle.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let state = app_handle.state::<AppState>();
+        crate::storage::connect_network_share(&state)?;
+        crate::storage::validate_save_path(&state.get_effective_save_path())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+    .and_then(|result| result);
+
+    match result {
+        Ok(path) => SelfCheckItem::new(
+            "save_path",
+            SelfCheckStatus::Passed,
+            format!("可以写入 {}", path.display()),
+        ),
+        Err(error) => SelfCheckItem::new("save_path", SelfCheckStatus::Failed, error),
+    }
+}
+
+fn self_check_ffmpeg(app_handle: &AppHandle) -> SelfCheckItem {
+    let state = app_handle.state::<AppState>();
+    let needs_ffmpeg = state.post_trigger_action().records_screen()
+        || state.capture_mode().records_video()
+        || state.prebuffer_seconds() > 0;
+    if !needs_ffmpeg {
+        return SelfCheckItem::new(
+            "ffmpeg",
+            SelfCheckStatus::Skipped,
+            "当前设置不需要录制，无需 ffmpeg",
+        );
+    }
+
+    match crate::ffmpeg::resolve(app_handle) {
+        Ok(path) => SelfCheckItem::new(
+            "ffmpeg",
+            SelfCheckStatus::Passed,
+            path.to_string_lossy().to_string(),
+        ),
+        Err(error) => SelfCheckItem::new("ffmpeg", SelfCheckStatus::Failed, error),
+    }
+}
+
+fn self_check_notifications(app_handle: &AppHandle) -> SelfCheckItem {
+    let state = app_handle.state::<AppState>();
+    if !state.enable_notifications() {
+        SelfCheckItem::new("notifications", SelfCheckStatus::Skipped, "通知已关闭")
+    } else if state.notification_permission_denied() {
+        SelfCheckItem::new(
+            "notifications",
+            SelfCheckStatus::Failed,
+            "系统拒绝了通知权限，请在系统设置中开启通知后重启应用",
+        )
+    } else {
+        SelfCheckItem::new("notifications", SelfCheckStatus::Passed, "通知已启用")
+    }
+}
+
+fn self_check_shortcut(app_handle: &AppHandle) -> SelfCheckItem {
+    let state = app_handle.state::<AppState>();
+    let configured = state.shortcut_key();
+    match state.active_shortcut() {
+        Some(active) if active == configured => SelfCheckItem::new(
+            "shortcut",
+            SelfCheckStatus::Passed,
+            format!("已注册 {}", active),
+        ),
+        Some(active) => SelfCheckItem::new(
+            "shortcut",
+            SelfCheckStatus::Passed,
+            format!("{} 注册失败，已使用备用快捷键 {}", configured, active),
+        ),
+        None => SelfCheckItem::new(
+            "shortcut",
+            SelfCheckStatus::Failed,
+            format!("快捷键 {} 未注册，可能被其他程序占用", configured),
+        ),
+    }
+}
+
+/// 无人值守前的一次性自检：摄像头、保存路径、ffmpeg、通知权限与快捷键
+#[tauri::command]
+pub async fn run_self_check(app_handle: tauri::AppHandle) -> Result<SelfCheckReport, String> {
+    let items = vec![
+        self_check_camera(&app_handle).await,
+        self_check_save_path(&app_handle).await,
+        self_check_ffmpeg(&app_handle),
+        self_check_notifications(&app_handle),
+        self_check_shortcut(&app_handle),
+    ];
+    let passed = items
+        .iter()
+        .all(|item| item.status != SelfCheckStatus::Failed);
+
+    for item in items
+        .iter()
+        .filter(|item| item.status == SelfCheckStatus::Failed)
+    {
+        log::warn!("自检未通过 [{}]: {}", item.name, item.detail);
+    }
+    log::info!("自检完成，结果: {}", if passed { "通过" } else { "未通过" });
+
+    Ok(SelfCheckReport { passed, items })
+}
+
+#[tauri::command]
+pub fn get_min_free_mb(app_handle: tauri::AppHandle) -> Result<u64, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.min_free_mb())
+}
+
+#[tauri::command]
+pub fn set_min_free_mb(app_handle: tauri::AppHandle, min_free_mb: u64) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let old_min_free_mb = state.min_free_mb();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_min_free_mb(min_free_mb),
+        |state| state.set_min_free_mb(old_min_free_mb),
+    )?;
+
+    log::info!("最小剩余磁盘空间设置已更新为: {} MB", min_free_mb);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_ignored_keys(app_handle: tauri::AppHandle) -> Result<Vec<String>, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.ignored_keys())
+}
+
+#[tauri::command]
+pub fn add_ignored_key(app_handle: tauri::AppHandle, key: String) -> Result<(), String> {
+    let (canonical, _) = crate::key_filter::parse_key_name(&key)
+        .ok_or_else(|| format!("无法识别的按键名称: {}", key))?;
+
+    let state = app_handle.state::<AppState>();
+    let old_keys = state.ignored_keys();
+    if old_keys.contains(&canonical) {
+        return Ok(());
+    }
+
+    let mut new_keys = old_keys.clone();
+    new_keys.push(canonical.clone());
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_ignored_keys(new_keys),
+        |state| state.set_ignored_keys(old_keys),
+    )?;
+
+    log::info!("已添加忽略按键: {}", canonical);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn remove_ignored_key(app_handle: tauri::AppHandle, key: String) -> Result<(), String> {
+    let (canonical, _) = crate::key_filter::parse_key_name(&key)
+        .ok_or_else(|| format!("无法识别的按键名称: {}", key))?;
+
+    let state = app_handle.state::<AppState>();
+    let old_keys = state.ignored_keys();
+    let new_keys: Vec<String> = old_keys
+        .iter()
+        .filter(|existing| **existing != canonical)
+        .cloned()
+        .collect();
+
+    if new_keys.len() == old_keys.len() {
+        return Ok(());
+    }
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_ignored_keys(new_keys),
+        |state| state.set_ignored_keys(old_keys),
+    )?;
+
+    log::info!("已移除忽略按键: {}", canonical);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_event_history_limit(app_handle: tauri::AppHandle) -> Result<u32, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.event_history_limit())
+}
+
+/// 设置触发历史最多保留的记录数，下次记录触发时丢弃超出的旧记录
+#[tauri::command]
+pub fn set_event_history_limit(app_handle: tauri::AppHandle, limit: u32) -> Result<(), String> {
+    let limit = crate::config::normalize_event_history_limit(limit);
+    let state = app_handle.state::<AppState>();
+    let old_limit = state.event_history_limit();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_event_history_limit(limit),
+        |state| state.set_event_history_limit(old_limit),
+    )?;
+
+    log::info!("触发历史保留条数已更新为: {}", limit);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_preparation_delay(app_handle: tauri::AppHandle) -> Result<u32, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.preparation_delay_seconds())
+}
+
+#[tauri::command]
+pub fn set_preparation_delay(app_handle: tauri::AppHandle, delay: u32) -> Result<(), String> {
+    if delay > MAX_PREPARATION_DELAY_SECONDS {
+        return Err(format!(
+            "准备时间必须在 0 到 {} 秒之间",
+            MAX_PREPARATION_DELAY_SECONDS
+        ));
+    }
+
+    let state = app_handle.state::<AppState>();
+    validate_event_ignore_window(state.event_ignore_window_ms(), delay)?;
+    let old_delay = state.preparation_delay_seconds();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_preparation_delay_seconds(delay),
+        |state| state.set_preparation_delay_seconds(old_delay),
+    )?;
+
+    log::info!("警戒准备时间设置已更新为: {}秒", delay);
+    Ok(())
+}
+
+/// 通知模板设置
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct NotificationTemplates {
+    pub title: Option<String>,
+    pub body: Option<String>,
+}
+
+fn normalize_template(template: Option<String>) -> Option<String> {
+    template.filter(|value| !value.trim().is_empty())
+}
+
+#[tauri::command]
+pub fn get_notification_templates(
+    app_handle: tauri::AppHandle,
+) -> Result<NotificationTemplates, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(NotificationTemplates {
+        title: state.notification_title(),
+        body: state.notification_body(),
+    })
+}
+
+#[tauri::command]
+pub fn set_notification_templates(
+    app_handle: tauri::AppHandle,
+    title: Option<String>,
+    body: Option<String>,
+) -> Result<(), String> {
+    let title = normalize_template(title);
+    let body = normalize_template(body);
+
+    let state = app_handle.state::<AppState>();
+    let old_title = state.notification_title();
+    let old_body = state.notification_body();
+    let new_title = title.clone();
+    let new_body = body.clone();
+
+    persist_state_change(
+        &app_handle,
+        |state| {
+            state.set_notification_title(new_title);
+            state.set_notification_body(new_body);
+        },
+        |state| {
+            state.set_notification_title(old_title);
+            state.set_notification_body(old_body);
+        },
+    )?;
+
+    log::info!("通知模板已更新: 标题={:?}, 内容={:?}", title, body);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_retrigger_cooldown_ms(app_handle: tauri::AppHandle) -> Result<u64, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.retrigger_cooldown_ms())
+}
+
+#[tauri::command]
+pub fn set_retrigger_cooldown_ms(
+    app_handle: tauri::AppHandle,
+    cooldown_ms: u64,
+) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let old_cooldown_ms = state.retrigger_cooldown_ms();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_retrigger_cooldown_ms(cooldown_ms),
+        |state| state.set_retrigger_cooldown_ms(old_cooldown_ms),
+    )?;
+
+    log::info!("重复触发冷却时间设置已更新为: {} ms", cooldown_ms);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_rearm_after_trigger(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.rearm_after_trigger())
+}
+
+#[tauri::command]
+pub fn set_rearm_after_trigger(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let old_enabled = state.rearm_after_trigger();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_rearm_after_trigger(enabled),
+        |state| state.set_rearm_after_trigger(old_enabled),
+    )?;
+
+    log::info!("触发后重新警戒设置已更新为: {}", enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_autostart(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.autostart_enabled())
+}
+
+fn apply_autostart_registration(app_handle: &AppHandle, enabled: bool) -> Result<(), String> {
+    use tauri_plugin_autostart::ManagerExt;
+
+    let autolaunch = app_handle.autolaunch();
+    let result = if enabled {
+        autolaunch.enable()
+    } else {
+        autolaunch.disable()
+    };
+    result.map_err(|error| format!("更新开机自启设置失败: {}", error))
+}
+
+#[tauri::command]
+pub fn set_autostart(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    // 开发版本的可执行文件位于构建目录，注册后会在下次登录时启动调试程序
+    if cfg!(debug_assertions) && enabled {
+        return Err("开发版本不支持启用开机自启，请使用安装后的正式版本".to_string());
+    }
+
+    let state = app_handle.state::<AppState>();
+    let old_enabled = state.autostart_enabled();
+
+    apply_autostart_registration(&app_handle, enabled)?;
+
+    let rollback_handle = app_handle.clone();
+    persist_state_change(
+        &app_handle,
+        |state| state.set_autostart_enabled(enabled),
+        |state| {
+            state.set_autostart_enabled(old_enabled);
+            if let Err(error) = apply_autostart_registration(&rollback_handle, old_enabled) {
+                log::error!("回滚开机自启设置失败: {}", error);
+            }
+        },
+    )?;
+
+    log::info!("开机自启设置已更新为: {}", enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_auto_arm_on_start(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.auto_arm_on_start())
+}
+
+#[tauri::command]
+pub fn set_auto_arm_on_start(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let old_enabled = state.auto_arm_on_start();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_auto_arm_on_start(enabled),
+        |state| state.set_auto_arm_on_start(old_enabled),
+    )?;
+
+    log::info!("启动时自动警戒设置已更新为: {}", enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_close_behavior(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.close_to_tray())
+}
+
+#[tauri::command]
+pub fn set_close_behavior(app_handle: tauri::AppHandle, close_to_tray: bool) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let old_close_to_tray = state.close_to_tray();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_close_to_tray(close_to_tray),
+        |state| state.set_close_to_tray(old_close_to_tray),
+    )?;
+
+    log::info!("关闭窗口时最小化到托盘设置已更新为: {}", close_to_tray);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_allow_no_camera(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.allow_no_camera())
+}
+
+#[tauri::command]
+pub fn set_allow_no_camera(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let old_enabled = state.allow_no_camera();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_allow_no_camera(enabled),
+        |state| state.set_allow_no_camera(old_enabled),
+    )?;
+
+    log::info!("无摄像头模式设置已更新为: {}", enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_notify_on_startup(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.notify_on_startup())
+}
+
+#[tauri::command]
+pub fn set_notify_on_startup(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let old_enabled = state.notify_on_startup();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_notify_on_startup(enabled),
+        |state| state.set_notify_on_startup(old_enabled),
+    )?;
+
+    log::info!("启动/退出通知设置已更新为: {}", enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_organize_by_date(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.organize_by_date())
+}
+
+#[tauri::command]
+pub fn set_organize_by_date(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let old_enabled = state.organize_by_date();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_organize_by_date(enabled),
+        |state| state.set_organize_by_date(old_enabled),
+    )?;
+
+    log::info!("按日期归档设置已更新为: {}", enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_trigger_on_lid_close(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.trigger_on_lid_close())
+}
+
+#[tauri::command]
+pub fn set_trigger_on_lid_close(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let old_enabled = state.trigger_on_lid_close();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_trigger_on_lid_close(enabled),
+        |state| state.set_trigger_on_lid_close(old_enabled),
+    )?;
+
+    log::info!("合盖/睡眠触发设置已更新为: {}", enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_input_devices() -> Result<Vec<crate::input_devices::InputDevice>, String> {
+    crate::input_devices::list_input_devices()
+}
+
+#[tauri::command]
+pub fn get_monitored_device_id(app_handle: tauri::AppHandle) -> Result<Option<String>, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.monitored_device_id())
+}
+
+/// 限定只监控某个键盘/鼠标，传入空值恢复监控全部设备
+#[tauri::command]
+pub fn set_monitored_device_id(
+    app_handle: tauri::AppHandle,
+    device_id: Option<String>,
+) -> Result<(), String> {
+    let device_id = device_id
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty());
+
+    if let Some(id) = device_id.as_deref() {
+        if !cfg!(target_os = "windows") {
+            return Err("仅 Windows 支持按设备过滤输入".to_string());
+        }
+        if !crate::input_devices::list_input_devices()?
+            .iter()
+            .any(|device| device.id == id)
+        {
+            return Err(format!("未找到输入设备: {}", id));
+        }
+    }
+
+    let state = app_handle.state::<AppState>();
+    let old_device_id = state.monitored_device_id();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_monitored_device_id(device_id.clone()),
+        |state| state.set_monitored_device_id(old_device_id.clone()),
+    )?;
+
+    log::info!("监控输入设备已更新为: {:?}", device_id);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_record_before_lock_seconds(app_handle: tauri::AppHandle) -> Result<u32, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.record_before_lock_seconds())
+}
+
+/// 设置录屏并锁屏模式下锁屏前至少录制的秒数
+#[tauri::command]
+pub fn set_record_before_lock_seconds(
+    app_handle: tauri::AppHandle,
+    seconds: u32,
+) -> Result<(), String> {
+    if !(1..=MAX_RECORD_BEFORE_LOCK_SECONDS).contains(&seconds) {
+        return Err(format!(
+            "锁屏前录制时长必须在 1 到 {} 秒之间",
+            MAX_RECORD_BEFORE_LOCK_SECONDS
+        ));
+    }
+
+    let state = app_handle.state::<AppState>();
+    let old_seconds = state.record_before_lock_seconds();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_record_before_lock_seconds(seconds),
+        |state| state.set_record_before_lock_seconds(old_seconds),
+    )?;
+
+    log::info!("锁屏前录制时长已更新为: {}秒", seconds);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_photo_with_screen_recording(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.photo_with_screen_recording())
+}
+
+/// 屏幕录制类动作触发时是否同时使用摄像头拍照
+#[tauri::command]
+pub fn set_photo_with_screen_recording(
+    app_handle: tauri::AppHandle,
+    enabled: bool,
+) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let old_enabled = state.photo_with_screen_recording();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_photo_with_screen_recording(enabled),
+        |state| state.set_photo_with_screen_recording(old_enabled),
+    )?;
+
+    log::info!("录屏时同时拍照设置已更新为: {}", enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_capture_screenshot_on_trigger(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.capture_screenshot_on_trigger())
+}
+
+/// 触发时是否额外保存一张桌面截图（与摄像头照片并存）
+#[tauri::command]
+pub fn set_capture_screenshot_on_trigger(
+    app_handle: tauri::AppHandle,
+    enabled: bool,
+) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let old_enabled = state.capture_screenshot_on_trigger();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_capture_screenshot_on_trigger(enabled),
+        |state| state.set_capture_screenshot_on_trigger(old_enabled),
+    )?;
+
+    log::info!("触发时桌面截图设置已更新为: {}", enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_on_unlock_action(
+    app_handle: tauri::AppHandle,
+) -> Result<crate::config::UnlockAction, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.on_unlock_action())
+}
+
+/// 设置系统解锁后的处理方式
+#[tauri::command]
+pub fn set_on_unlock_action(
+    app_handle: tauri::AppHandle,
+    action: crate::config::UnlockAction,
+) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let old_action = state.on_unlock_action();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_on_unlock_action(action),
+        |state| state.set_on_unlock_action(old_action),
+    )?;
+
+    log::info!("解锁后动作已更新为: {:?}", action);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_stealth_mode(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.stealth_mode())
+}
+
+/// 设置隐身模式，立即生效
+#[tauri::command]
+pub fn set_stealth_mode(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let old_enabled = state.stealth_mode();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_stealth_mode(enabled),
+        |state| state.set_stealth_mode(old_enabled),
+    )?;
+    crate::app_setup::apply_stealth_mode(&app_handle);
+
+    log::info!("隐身模式设置已更新为: {}", enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_hide_tray_icon(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.hide_tray_icon())
+}
+
+/// 设置是否隐藏托盘图标，立即生效
+#[tauri::command]
+pub fn set_hide_tray_icon(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let old_enabled = state.hide_tray_icon();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_hide_tray_icon(enabled),
+        |state| state.set_hide_tray_icon(old_enabled),
+    )?;
+    crate::app_setup::apply_stealth_mode(&app_handle);
+
+    log::info!("隐藏托盘图标设置已更新为: {}", enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_lock_first(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.lock_first())
+}
+
+/// 设置拍摄并锁屏时是否先锁屏再拍摄
+#[tauri::command]
+pub fn set_lock_first(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let old_enabled = state.lock_first();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_lock_first(enabled),
+        |state| state.set_lock_first(old_enabled),
+    )?;
+
+    log::info!("先锁屏后拍摄设置已更新为: {}", enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_alert_min_interval_seconds(app_handle: tauri::AppHandle) -> Result<u64, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.alert_min_interval_seconds())
+}
+
+#[tauri::command]
+pub fn set_alert_min_interval_seconds(
+    app_handle: tauri::AppHandle,
+    seconds: u64,
+) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let old_seconds = state.alert_min_interval_seconds();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_alert_min_interval_seconds(seconds),
+        |state| state.set_alert_min_interval_seconds(old_seconds),
+    )?;
+
+    log::info!("安全警报最小间隔设置已更新为: {} 秒", seconds);
+    Ok(())
+}
+
+/// 快捷键注册重试设置
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ShortcutRetrySettings {
+    pub attempts: u32,
+    pub interval_ms: u64,
+}
+
+#[tauri::command]
+pub fn get_shortcut_retry(app_handle: tauri::AppHandle) -> Result<ShortcutRetrySettings, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(ShortcutRetrySettings {
+        attempts: state.shortcut_retry_attempts(),
+        interval_ms: state.shortcut_retry_interval_ms(),
+    })
+}
+
+#[tauri::command]
+pub fn set_shortcut_retry(
+    app_handle: tauri::AppHandle,
+    attempts: u32,
+    interval_ms: u64,
+) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let old_attempts = state.shortcut_retry_attempts();
+    let old_interval_ms = state.shortcut_retry_interval_ms();
+
+    persist_state_change(
+        &app_handle,
+        |state| {
+            state.set_shortcut_retry_attempts(attempts);
+            state.set_shortcut_retry_interval_ms(interval_ms);
+        },
+        |state| {
+            state.set_shortcut_retry_attempts(old_attempts);
+            state.set_shortcut_retry_interval_ms(old_interval_ms);
+        },
+    )?;
+
+    log::info!(
+        "快捷键注册重试设置已更新: 次数 {}, 间隔 {} ms",
+        attempts,
+        interval_ms
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_arm_settle_ms(app_handle: tauri::AppHandle) -> Result<u64, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.arm_settle_ms())
+}
+
+#[tauri::command]
+pub fn set_arm_settle_ms(app_handle: tauri::AppHandle, settle_ms: u64) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let old_settle_ms = state.arm_settle_ms();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_arm_settle_ms(settle_ms),
+        |state| state.set_arm_settle_ms(old_settle_ms),
+    )?;
+
+    log::info!("警戒稳定期设置已更新为: {} ms", settle_ms);
+    Ok(())
+}
+
+/// 将所有设置恢复为默认值：写入默认配置、重新注册快捷键并通知前端刷新
+#[tauri::command]
+pub async fn reset_config(app_handle: tauri::AppHandle) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let old_shortcut = state.shortcut_key();
+    let old_panic_shortcut = state.panic_shortcut_key();
+    let old_autostart = state.autostart_enabled();
+
+    let defaults = crate::config::AppConfig::default();
+    defaults
+        .save()
+        .map_err(|error| format!("保存默认配置失败: {}", error))?;
+    defaults.apply_to_state(&state);
+    crate::logger::refresh_sensitive_values(&state);
+
+    // 日志写入文件随默认设置关闭，同时清除之前的日志文件路径
+    if let Some(logger) = crate::logger::get_logger() {
+        logger.set_log_to_file(defaults.save_logs_to_file);
+        logger.set_log_file_path(None);
+    }
+
+    if let Err(error) =
+        crate::app_setup::update_global_shortcut(&app_handle, &old_shortcut, &defaults.shortcut_key)
+            .await
+    {
+        log::error!("重新注册默认快捷键失败: {}", error);
+    }
+    if let Err(error) = crate::app_setup::update_panic_shortcut(
+        &app_handle,
+        old_panic_shortcut.as_deref(),
+        defaults.panic_shortcut_key.as_deref(),
+    ) {
+        log::error!("恢复默认紧急快捷键失败: {}", error);
+    }
+    if old_autostart != defaults.autostart_enabled {
+        if let Err(error) = apply_autostart_registration(&app_handle, defaults.autostart_enabled) {
+            log::error!("恢复默认开机自启设置失败: {}", error);
+        }
+    }
+    crate::app_setup::apply_stealth_mode(&app_handle);
+    crate::local_api::restart(&app_handle);
+
+    if let Err(error) = app_handle.emit("config_reset", &defaults) {
+        log::error!("无法发送配置重置事件: {}", error);
+    }
+
+    log::info!("所有设置已恢复为默认值");
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_monitoring_backend(
+    app_handle: tauri::AppHandle,
+) -> Result<crate::config::MonitoringBackend, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.monitoring_backend())
+}
+
+/// 设置全局输入监听的实现方式；监听器常驻运行，重启应用后生效
+#[tauri::command]
+pub fn set_monitoring_backend(
+    app_handle: tauri::AppHandle,
+    backend: crate::config::MonitoringBackend,
+) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let old_backend = state.monitoring_backend();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_monitoring_backend(backend),
+        |state| state.set_monitoring_backend(old_backend),
+    )?;
+
+    log::info!("输入监听实现已更新为: {:?}，重启应用后生效", backend);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_trigger_sources(
+    app_handle: tauri::AppHandle,
+) -> Result<crate::monitoring::TriggerSourceToggles, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(crate::monitoring::TriggerSourceToggles::from_state(&state))
+}
+
+#[tauri::command]
+pub fn set_trigger_sources(
+    app_handle: tauri::AppHandle,
+    keyboard: bool,
+    mouse_buttons: bool,
+    mouse_move: bool,
+    scroll: bool,
+) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let old_toggles = crate::monitoring::TriggerSourceToggles::from_state(&state);
+    let new_toggles = crate::monitoring::TriggerSourceToggles {
+        keyboard,
+        mouse_buttons,
+        mouse_move,
+        scroll,
+    };
+
+    persist_state_change(
+        &app_handle,
+        |state| new_toggles.apply_to_state(state),
+        |state| old_toggles.apply_to_state(state),
+    )?;
+
+    log::info!(
+        "触发输入类别已更新: 键盘 {}, 鼠标按键 {}, 鼠标移动 {}, 滚轮 {}",
+        keyboard,
+        mouse_buttons,
+        mouse_move,
+        scroll
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_camera_fourcc(
+    app_handle: tauri::AppHandle,
+) -> Result<crate::config::CameraFourcc, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.camera_fourcc())
+}
+
+/// 设置拍照与预录时请求的像素格式，保存前确认当前摄像头支持该格式
+#[tauri::command]
+pub async fn set_camera_fourcc(
+    app_handle: tauri::AppHandle,
+    fourcc: crate::config::CameraFourcc,
+) -> Result<(), String> {
+    let camera_id = app_handle.state::<AppState>().camera_id();
+    camera::ensure_fourcc_supported(camera_id, fourcc).await?;
+
+    let old_fourcc = app_handle.state::<AppState>().camera_fourcc();
+    persist_state_change(
+        &app_handle,
+        |state| state.set_camera_fourcc(fourcc),
+        |state| state.set_camera_fourcc(old_fourcc),
+    )?;
+
+    log::info!("摄像头像素格式已更新为: {:?}", fourcc);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_local_api(
+    app_handle: tauri::AppHandle,
+) -> Result<crate::config::LocalApiConfig, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.local_api())
+}
+
+/// 更新本地 HTTP 接口设置并按新设置重启接口
+#[tauri::command]
+pub fn set_local_api(
+    app_handle: tauri::AppHandle,
+    enabled: bool,
+    port: u16,
+    token: Option<String>,
+) -> Result<(), String> {
+    let token = token
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty());
+    if port == 0 {
+        return Err("端口必须在 1 到 65535 之间".to_string());
+    }
+    if enabled && token.is_none() {
+        return Err("启用本地 HTTP 接口前必须设置访问令牌".to_string());
+    }
+
+    let state = app_handle.state::<AppState>();
+    let old_config = state.local_api();
+    let new_config = crate::config::LocalApiConfig {
+        enabled,
+        port,
+        token,
+    };
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_local_api(new_config),
+        |state| state.set_local_api(old_config),
+    )?;
+    crate::logger::refresh_sensitive_values(&state);
+
+    crate::local_api::restart(&app_handle);
+    log::info!(
+        "本地 HTTP 接口设置已更新: {}，端口 {}",
+        if enabled { "启用" } else { "禁用" },
+        port
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_share_credentials(
+    app_handle: tauri::AppHandle,
+) -> Result<Option<crate::config::NetworkShareCredentialsInfo>, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.share_credentials().as_ref().map(Into::into))
+}
+
+/// 设置网络共享凭据；保存目录为 UNC 路径时先用新凭据验证能否连接。
+/// `password` 为 `None` 时保留已保存的密码
+#[tauri::command]
+pub async fn set_share_credentials(
+    app_handle: tauri::AppHandle,
+    username: Option<String>,
+    password: Option<String>,
+) -> Result<(), String> {
+    let old_credentials = app_handle.state::<AppState>().share_credentials();
+    let credentials = username
+        .map(|username| username.trim().to_string())
+        .filter(|username| !username.is_empty())
+        .map(|username| crate::config::NetworkShareCredentials {
+            username,
+            password: password.unwrap_or_else(|| {
+                old_credentials
+                    .as_ref()
+                    .map(|credentials| credentials.password.clone())
+                    .unwrap_or_default()
+            }),
+        });
+
+    crate::network_share::disconnect();
+    if let (Some(save_path), Some(credentials)) = (
+        app_handle.state::<AppState>().save_path(),
+        credentials.clone(),
+    ) {
+        tokio::task::spawn_blocking(move || {
+            crate::network_share::ensure_connected(&save_path, &credentials)
+        })
+        .await
+        .map_err(|e| format!("Task join error: {}", e))??;
+    }
+
+    let has_credentials = credentials.is_some();
+    persist_state_change(
+        &app_handle,
+        |state| state.set_share_credentials(credentials),
+        |state| state.set_share_credentials(old_credentials),
+    )?;
+    crate::logger::refresh_sensitive_values(&app_handle.state::<AppState>());
+
+    log::info!(
+        "网络共享凭据已{}",
+        if has_credentials { "更新" } else { "清除" }
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_webhook_url(app_handle: tauri::AppHandle) -> Result<Option<String>, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.webhook_url())
+}
+
+/// 设置触发时接收通知的 Webhook 地址，传入空值时不发送
+#[tauri::command]
+pub fn set_webhook_url(app_handle: tauri::AppHandle, url: Option<String>) -> Result<(), String> {
+    let url = url
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty());
+    if let Some(url) = &url {
+        crate::webhook::validate_url(url)?;
+    }
+
+    let state = app_handle.state::<AppState>();
+    let old_url = state.webhook_url();
+    let has_url = url.is_some();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_webhook_url(url.clone()),
+        |state| state.set_webhook_url(old_url.clone()),
+    )?;
+    crate::logger::refresh_sensitive_values(&state);
+
+    // 地址中可能带有令牌，不写入日志
+    log::info!("Webhook 地址已{}", if has_url { "更新" } else { "清除" });
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_smtp_config(
+    app_handle: tauri::AppHandle,
+) -> Result<crate::config::SmtpConfigInfo, String> {
+    let state = app_handle.state::<AppState>();
+    Ok((&state.smtp()).into())
+}
+
+/// 设置告警邮件的 SMTP 服务器与收件人；服务器或收件人为空时不发送邮件。
+/// `password` 为 `None` 时保留已保存的密码，为空字符串时清除
+#[tauri::command]
+pub fn set_smtp_config(
+    app_handle: tauri::AppHandle,
+    host: Option<String>,
+    port: u16,
+    username: Option<String>,
+    password: Option<String>,
+    alert_email_to: Option<String>,
+) -> Result<(), String> {
+    let trimmed = |value: Option<String>| {
+        value
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    if port == 0 {
+        return Err("端口必须在 1 到 65535 之间".to_string());
+    }
+    let alert_email_to = trimmed(alert_email_to);
+    if let Some(address) = &alert_email_to {
+        if !address.contains('@') {
+            return Err("收件人邮箱地址无效".to_string());
+        }
+    }
+
+    let state = app_handle.state::<AppState>();
+    let old_config = state.smtp();
+    let new_config = crate::config::SmtpConfig {
+        host: trimmed(host),
+        port,
+        username: trimmed(username),
+        password: match password {
+            Some(password) => Some(password).filter(|password| !password.is_empty()),
+            None => old_config.password.clone(),
+        },
+        alert_email_to,
+    };
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_smtp(new_config.clone()),
+        |state| state.set_smtp(old_config.clone()),
+    )?;
+    crate::logger::refresh_sensitive_values(&state);
+
+    log::info!("告警邮件设置已更新: {:?}", new_config);
+    Ok(())
+}
+
+/// 画面变化检测设置
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MotionDetectionSettings {
+    pub enabled: bool,
+    pub threshold_percent: u8,
+    pub sample_interval_ms: u64,
+}
+
+#[tauri::command]
+pub fn get_motion_detection(
+    app_handle: tauri::AppHandle,
+) -> Result<MotionDetectionSettings, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(MotionDetectionSettings {
+        enabled: state.motion_detection_enabled(),
+        threshold_percent: state.motion_threshold_percent(),
+        sample_interval_ms: state.motion_sample_interval_ms(),
+    })
+}
+
+/// 更新画面变化检测设置，下次进入警戒时生效
+#[tauri::command]
+pub fn set_motion_detection(
+    app_handle: tauri::AppHandle,
+    enabled: bool,
+    threshold_percent: u8,
+    sample_interval_ms: u64,
+) -> Result<(), String> {
+    if !(1..=100).contains(&threshold_percent) {
+        return Err("画面变化阈值必须在 1 到 100 之间".to_string());
+    }
+    if sample_interval_ms < crate::constants::MIN_MOTION_SAMPLE_INTERVAL_MS {
+        return Err(format!(
+            "采样间隔不能小于 {} 毫秒",
+            crate::constants::MIN_MOTION_SAMPLE_INTERVAL_MS
+        ));
+    }
+
+    let state = app_handle.state::<AppState>();
+    let old_settings = get_motion_detection(app_handle.clone())?;
+
+    persist_state_change(
+        &app_handle,
+        |state| {
+            state.set_motion_detection_enabled(enabled);
+            state.set_motion_threshold_percent(threshold_percent);
+            state.set_motion_sample_interval_ms(sample_interval_ms);
+        },
+        |state| {
+            state.set_motion_detection_enabled(old_settings.enabled);
+            state.set_motion_threshold_percent(old_settings.threshold_percent);
+            state.set_motion_sample_interval_ms(old_settings.sample_interval_ms);
+        },
+    )?;
+
+    if enabled && state.prebuffer_seconds() > 0 {
+        log::warn!("预录缓冲与画面变化检测共用摄像头，同时启用时警戒期间只运行预录缓冲");
+    }
+    log::info!(
+        "画面变化检测设置已更新: 启用 {}, 阈值 {}%, 采样间隔 {} ms",
+        enabled,
+        threshold_percent,
+        sample_interval_ms
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_recording_region(
+    app_handle: tauri::AppHandle,
+) -> Result<crate::config::RecordingRegion, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.recording_region())
+}
+
+#[tauri::command]
+pub fn set_recording_region(
+    app_handle: tauri::AppHandle,
+    region: crate::config::RecordingRegion,
+) -> Result<(), String> {
+    if let crate::config::RecordingRegion::Custom { width, height, .. } = region {
+        if width == 0 || height == 0 {
+            return Err("录制区域的宽度和高度必须大于 0".to_string());
+        }
+    }
+
+    let state = app_handle.state::<AppState>();
+    let old_region = state.recording_region();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_recording_region(region),
+        |state| state.set_recording_region(old_region),
+    )?;
+
+    log::info!("屏幕录制区域已更新为: {:?}", region);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_capture_camera_ids(app_handle: tauri::AppHandle) -> Result<Vec<u32>, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.capture_camera_ids())
+}
+
+#[tauri::command]
+pub fn set_capture_camera_ids(
+    app_handle: tauri::AppHandle,
+    camera_ids: Vec<u32>,
+) -> Result<(), String> {
+    for camera_id in &camera_ids {
+        camera::ensure_camera_available(*camera_id)?;
+    }
+
+    let state = app_handle.state::<AppState>();
+    let old_camera_ids = state.capture_camera_ids();
+    let new_camera_ids = crate::config::normalize_camera_ids(&camera_ids);
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_capture_camera_ids(new_camera_ids),
+        |state| state.set_capture_camera_ids(old_camera_ids),
+    )?;
+
+    log::info!("触发时拍摄的摄像头已更新为: {:?}", camera_ids);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_restore_armed_state(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.restore_armed_state())
+}
+
+#[tauri::command]
+pub fn set_restore_armed_state(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let old_enabled = state.restore_armed_state();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_restore_armed_state(enabled),
+        |state| state.set_restore_armed_state(old_enabled),
+    )?;
+
+    log::info!("重启后恢复警戒设置已更新为: {}", enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_jpeg_quality(app_handle: tauri::AppHandle) -> Result<u8, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.jpeg_quality())
+}
+
+#[tauri::command]
+pub fn set_jpeg_quality(app_handle: tauri::AppHandle, quality: u8) -> Result<(), String> {
+    let requested_quality = quality;
+    let quality = crate::config::normalize_jpeg_quality(quality);
+    if quality != requested_quality {
+        log::warn!(
+            "JPEG 质量 {} 超出 1-100 范围，已调整为 {}",
+            requested_quality,
+            quality
+        );
+    }
+    let state = app_handle.state::<AppState>();
+    let old_quality = state.jpeg_quality();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_jpeg_quality(quality),
+        |state| state.set_jpeg_quality(old_quality),
+    )?;
+
+    log::info!("照片 JPEG 质量已更新为: {}", quality);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_photo_warmup_frames(app_handle: tauri::AppHandle) -> Result<u32, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.photo_warmup_frames())
+}
+
+#[tauri::command]
+pub fn set_photo_warmup_frames(app_handle: tauri::AppHandle, frames: u32) -> Result<(), String> {
+    let frames = crate::config::normalize_photo_warmup_frames(frames);
+    let state = app_handle.state::<AppState>();
+    let old_frames = state.photo_warmup_frames();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_photo_warmup_frames(frames),
+        |state| state.set_photo_warmup_frames(old_frames),
+    )?;
+
+    log::info!("拍照预热帧数已更新为: {}", frames);
+    Ok(())
+}
+
+/// 摄像头占用重试设置
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CameraBusyRetrySettings {
+    pub attempts: u32,
+    pub backoff_ms: u64,
+}
+
+#[tauri::command]
+pub fn get_camera_busy_retry(
+    app_handle: tauri::AppHandle,
+) -> Result<CameraBusyRetrySettings, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(CameraBusyRetrySettings {
+        attempts: state.camera_busy_retry_attempts(),
+        backoff_ms: state.camera_busy_retry_backoff_ms(),
+    })
+}
+
+#[tauri::command]
+pub fn set_camera_busy_retry(
+    app_handle: tauri::AppHandle,
+    attempts: u32,
+    backoff_ms: u64,
+) -> Result<(), String> {
+    let attempts = crate::config::normalize_camera_busy_retry_attempts(attempts);
+    let state = app_handle.state::<AppState>();
+    let old_attempts = state.camera_busy_retry_attempts();
+    let old_backoff_ms = state.camera_busy_retry_backoff_ms();
+
+    persist_state_change(
+        &app_handle,
+        |state| {
+            state.set_camera_busy_retry_attempts(attempts);
+            state.set_camera_busy_retry_backoff_ms(backoff_ms);
+        },
+        |state| {
+            state.set_camera_busy_retry_attempts(old_attempts);
+            state.set_camera_busy_retry_backoff_ms(old_backoff_ms);
+        },
+    )?;
+
+    log::info!(
+        "摄像头占用重试设置已更新: 次数 {}, 间隔 {} ms",
+        attempts,
+        backoff_ms
+    );
+    Ok(())
+}
+
+/// 屏幕录制画质设置
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RecordingQualitySettings {
+    pub framerate: u32,
+    pub scale_divisor: u32,
+    pub bitrate_kbps: u32,
+}
+
+#[tauri::command]
+pub fn get_recording_quality(
+    app_handle: tauri::AppHandle,
+) -> Result<RecordingQualitySettings, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(RecordingQualitySettings {
+        framerate: state.recording_framerate(),
+        scale_divisor: state.recording_scale_divisor(),
+        bitrate_kbps: state.recording_bitrate_kbps(),
+    })
+}
+
+/// 设置屏幕录制的帧率、缩小倍数与码率，超出范围的值恢复为默认值
+#[tauri::command]
+pub fn set_recording_quality(
+    app_handle: tauri::AppHandle,
+    framerate: u32,
+    scale_divisor: u32,
+    bitrate_kbps: u32,
+) -> Result<(), String> {
+    let requested = (framerate, scale_divisor, bitrate_kbps);
+    let framerate = crate::config::normalize_recording_framerate(framerate);
+    let scale_divisor = crate::config::normalize_recording_scale_divisor(scale_divisor);
+    let bitrate_kbps = crate::config::normalize_recording_bitrate_kbps(bitrate_kbps);
+    if requested != (framerate, scale_divisor, bitrate_kbps) {
+        log::warn!(
+            "屏幕录制参数超出范围，已恢复默认值: 请求 {:?}，实际 {:?}",
+            requested,
+            (framerate, scale_divisor, bitrate_kbps)
+        );
+    }
+
+    let state = app_handle.state::<AppState>();
+    let old_framerate = state.recording_framerate();
+    let old_scale_divisor = state.recording_scale_divisor();
+    let old_bitrate_kbps = state.recording_bitrate_kbps();
+
+    persist_state_change(
+        &app_handle,
+        |state| {
+            state.set_recording_framerate(framerate);
+            state.set_recording_scale_divisor(scale_divisor);
+            state.set_recording_bitrate_kbps(bitrate_kbps);
+        },
+        |state| {
+            state.set_recording_framerate(old_framerate);
+            state.set_recording_scale_divisor(old_scale_divisor);
+            state.set_recording_bitrate_kbps(old_bitrate_kbps);
+        },
+    )?;
+
+    log::info!(
+        "屏幕录制画质已更新: {} 帧/秒, 缩小 {} 倍, {} kbps（下次开始录制时生效）",
+        framerate,
+        scale_divisor,
+        bitrate_kbps
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_capture_timeout_ms(app_handle: tauri::AppHandle) -> Result<u64, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.capture_timeout_ms())
+}
+
+/// 设置单次拍照的最长等待时间，0 表示不限制
+#[tauri::command]
+pub fn set_capture_timeout_ms(app_handle: tauri::AppHandle, timeout_ms: u64) -> Result<(), String> {
+    if timeout_ms > MAX_CAPTURE_TIMEOUT_MS {
+        return Err(format!(
+            "拍照超时时间必须在 0 到 {} 毫秒之间",
+            MAX_CAPTURE_TIMEOUT_MS
+        ));
+    }
+
+    let state = app_handle.state::<AppState>();
+    let old_timeout_ms = state.capture_timeout_ms();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_capture_timeout_ms(timeout_ms),
+        |state| state.set_capture_timeout_ms(old_timeout_ms),
+    )?;
+
+    log::info!("拍照超时时间已更新为: {} ms", timeout_ms);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_prebuffer_seconds(app_handle: tauri::AppHandle) -> Result<u32, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.prebuffer_seconds())
+}
+
+#[tauri::command]
+pub fn set_prebuffer_seconds(app_handle: tauri::AppHandle, seconds: u32) -> Result<(), String> {
+    let seconds = crate::config::normalize_prebuffer_seconds(seconds);
+    let state = app_handle.state::<AppState>();
+    let old_seconds = state.prebuffer_seconds();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_prebuffer_seconds(seconds),
+        |state| state.set_prebuffer_seconds(old_seconds),
+    )?;
+
+    log::info!("预录缓冲时长已更新为: {}秒", seconds);
+    Ok(())
+}
+
+/// 快捷键防抖与输入忽略窗口设置（毫秒）
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TimingWindows {
+    pub shortcut_debounce_ms: u64,
+    pub shortcut_flag_clear_delay_ms: u64,
+    pub event_ignore_window_ms: u64,
+}
+
+/// 输入忽略窗口必须短于警戒准备时间，否则准备期结束后仍会吞掉输入
+fn validate_event_ignore_window(
+    event_ignore_window_ms: u64,
+    preparation_delay_seconds: u32,
+) -> Result<(), String> {
+    let preparation_delay_ms = u64::from(preparation_delay_seconds) * 1000;
+    if preparation_delay_ms > 0 && event_ignore_window_ms >= preparation_delay_ms {
+        return Err(format!(
+            "输入忽略窗口 ({} ms) 必须小于警戒准备时间 ({} ms)",
+            event_ignore_window_ms, preparation_delay_ms
+        ));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_timing_windows(app_handle: tauri::AppHandle) -> Result<TimingWindows, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(TimingWindows {
+        shortcut_debounce_ms: state.shortcut_debounce_ms(),
+        shortcut_flag_clear_delay_ms: state.shortcut_flag_clear_delay_ms(),
+        event_ignore_window_ms: state.event_ignore_window_ms(),
+    })
+}
+
+#[tauri::command]
+pub fn set_timing_windows(
+    app_handle: tauri::AppHandle,
+    shortcut_debounce_ms: u64,
+    shortcut_flag_clear_delay_ms: u64,
+    event_ignore_window_ms: u64,
+) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    validate_event_ignore_window(event_ignore_window_ms, state.preparation_delay_seconds())?;
+
+    let old_windows = get_timing_windows(app_handle.clone())?;
+
+    persist_state_change(
+        &app_handle,
+        |state| {
+            state.set_shortcut_debounce_ms(shortcut_debounce_ms);
+            state.set_shortcut_flag_clear_delay_ms(shortcut_flag_clear_delay_ms);
+            state.set_event_ignore_window_ms(event_ignore_window_ms);
+        },
+        |state| {
+            state.set_shortcut_debounce_ms(old_windows.shortcut_debounce_ms);
+            state.set_shortcut_flag_clear_delay_ms(old_windows.shortcut_flag_clear_delay_ms);
+            state.set_event_ignore_window_ms(old_windows.event_ignore_window_ms);
+        },
+    )?;
+
+    log::info!(
+        "时间窗口设置已更新: 防抖 {} ms, 标志清除 {} ms, 输入忽略 {} ms",
+        shortcut_debounce_ms,
+        shortcut_flag_clear_delay_ms,
+        event_ignore_window_ms
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_language(app_handle: tauri::AppHandle) -> Result<Option<Language>, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.language())
+}
+
+/// 设置通知语言，传入 `None` 时跟随系统区域
+#[tauri::command]
+pub fn set_language(
+    app_handle: tauri::AppHandle,
+    language: Option<Language>,
+) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let old_language = state.language();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_language(language),
+        |state| state.set_language(old_language),
+    )?;
+
+    log::info!("通知语言已更新为: {:?}", language);
+    Ok(())
+}
+
+/// 列出可用于屏幕录制的麦克风
+#[tauri::command]
+pub async fn get_audio_input_devices(app_handle: tauri::AppHandle) -> Result<Vec<String>, String> {
+    tokio::task::spawn_blocking(move || crate::ffmpeg::list_audio_input_devices(&app_handle))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub fn get_audio_device(app_handle: tauri::AppHandle) -> Result<Option<String>, String> {
+    let state = app_handle.state::<AppState>();
+    Ok(state.audio_device())
+}
+
+/// 设置屏幕录制使用的麦克风，传入空值时使用系统默认麦克风
+#[tauri::command]
+pub fn set_audio_device(
+    app_handle: tauri::AppHandle,
+    device: Option<String>,
+) -> Result<(), String> {
+    let device = device
+        .map(|device| device.trim().to_string())
+        .filter(|device| !device.is_empty());
+
+    let state = app_handle.state::<AppState>();
+    let old_device = state.audio_device();
+
+    persist_state_change(
+        &app_handle,
+        |state| state.set_audio_device(device.clone()),
+        |state| state.set_audio_device(old_device.clone()),
+    )?;
+
+    log::info!("录制麦克风已更新为: {:?}", device);
+    Ok(())
+}
+
+/// 在系统文件管理器中打开当前保存目录，目录不存在时先创建
+#[tauri::command]
+pub fn open_save_folder(app_handle: tauri::AppHandle) -> Result<(), String> {
+    let save_path = app_handle.state::<AppState>().get_effective_save_path();
+    std::fs::create_dir_all(&save_path).map_err(|e| format!("无法创建保存目录: {}", e))?;
+
+    app_handle
+        .opener()
+        .open_path(save_path.as_str(), None::<&str>)
+        .map_err(|e| format!("无法打开保存目录: {}", e))
+}
+
+/// 使用系统默认程序打开指定的拍摄文件，只允许打开保存目录内的文件
+#[tauri::command]
+pub fn open_capture(app_handle: tauri::AppHandle, path: String) -> Result<(), String> {
+    let save_path = app_handle.state::<AppState>().get_effective_save_path();
+    resolve_capture_path(&path, std::path::Path::new(&save_path))?;
+
+    app_handle
+        .opener()
+        .open_path(path.as_str(), None::<&str>)
+        .map_err(|e| format!("无法打开文件: {}", e))
+}
+
+/// 解析符号链接与 `..` 后确认文件位于保存目录内，避免前端借此打开任意程序
+fn resolve_capture_path(
+    path: &str,
+    save_dir: &std::path::Path,
+) -> Result<std::path::PathBuf, String> {
+    let file = std::fs::canonicalize(path).map_err(|_| format!("文件不存在: {}", path))?;
+    if !file.is_file() {
+        return Err(format!("文件不存在: {}", path));
+    }
+
+    let save_dir =
+        std::fs::canonicalize(save_dir).map_err(|e| format!("无法访问保存目录: {}", e))?;
+    if !file.starts_with(&save_dir) {
+        return Err(format!("只能打开保存目录中的文件: {}", path));
+    }
+
+    Ok(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{normalize_shortcut, resolve_capture_path, validate_event_ignore_window};
+
+    #[test]
+    fn event_ignore_window_must_be_shorter_than_preparation_delay() {
+        assert!(validate_event_ignore_window(500, 2).is_ok());
+        assert!(validate_event_ignore_window(2_000, 2).is_err());
+        assert!(validate_event_ignore_window(5_000, 0).is_ok());
+    }
+
+    #[test]
+    fn capture_path_must_stay_inside_save_dir() {
+        let root = std::env::temp_dir().join(format!("snaplock_open_{}", std::process::id()));
+        let save_dir = root.join("captures");
+        std::fs::create_dir_all(&save_dir).unwrap();
+        let photo = save_dir.join("photo.jpg");
+        let outside = root.join("outside.exe");
+        std::fs::write(&photo, b"jpg").unwrap();
+        std::fs::write(&outside, b"exe").unwrap();
+
+        assert!(resolve_capture_path(photo.to_str().unwrap(), &save_dir).is_ok());
+        assert!(resolve_capture_path(outside.to_str().unwrap(), &save_dir).is_err());
+        let escaped = save_dir.join("..").join("outside.exe");
+        assert!(resolve_capture_path(escaped.to_str().unwrap(), &save_dir).is_err());
+        assert!(resolve_capture_path(save_dir.to_str().unwrap(), &save_dir).is_err());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn normalizes_casing_and_modifier_order() {
+        assert_eq!(normalize_shortcut("alt+l").unwrap(), "Alt+L");
+        assert_eq!(
+            normalize_shortcut("shift + ctrl + f5").unwrap(),
+            "Ctrl+Shift+F5"
+        );
+        assert_eq!(normalize_shortcut("Cmd+space").unwrap(), "Super+Space");
+    }
+
+    #[test]
+    fn rejects_duplicate_modifiers() {
+        assert!(
+            normalize_shortcut("Ctrl+Ctrl+L")
+                .unwrap_err()
+                .contains("重复的修饰键")
+        );
+        assert!(normalize_shortcut("ctrl+Control+L").is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        assert!(
+            normalize_shortcut("Ctrl+NotAKey")
+                .unwrap_err()
+                .contains("无法识别的按键")
+        );
+        assert!(normalize_shortcut("Ctrl+A+B").is_err());
+    }
+
+    #[test]
+    fn rejects_shortcut_without_modifier() {
+        assert!(normalize_shortcut("L").is_err());
+        assert!(normalize_shortcut("Ctrl+Alt").is_err());
+        assert!(normalize_shortcut("Ctrl++L").is_err());
+    }
+}
@@ -0,0 +1,244 @@
+// snaplock/src-tauri/src/i18n.rs
+//! 后端通知文案的多语言目录
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::state::AppState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    Zh,
+    En,
+}
+
+impl Language {
+    /// 由区域标识（如 `zh_CN.UTF-8`、`en-US`）推断语言，无法识别时返回 `None`
+    pub fn from_locale(locale: &str) -> Option<Self> {
+        let prefix = locale
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match prefix.as_str() {
+            "zh" => Some(Self::Zh),
+            "en" => Some(Self::En),
+            _ => None,
+        }
+    }
+
+    /// 获取系统区域对应的语言，无法识别时使用中文
+    pub fn system() -> Self {
+        system_locale()
+            .and_then(|locale| Self::from_locale(&locale))
+            .unwrap_or(Self::Zh)
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn system_locale() -> Option<String> {
+    use windows::Win32::Globalization::GetUserDefaultLocaleName;
+
+    let mut buffer = [0u16; 85];
+    let len = unsafe { GetUserDefaultLocaleName(&mut buffer) };
+    if len <= 1 {
+        return None;
+    }
+    Some(String::from_utf16_lossy(&buffer[..len as usize - 1]))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn system_locale() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty() && value != "C" && value != "POSIX")
+}
+
+/// 需要本地化的后端消息
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Message {
+    DefaultTitle,
+    SecurityAlertTitle,
+    SecurityAlertBody,
+    AppStarted,
+    AppExited,
+    MonitoringArmed,
+    MonitoringDisarmed,
+    AutoArmFailed,
+    RestoreArmedResuming,
+    RestoreArmedSkipped,
+    NoCameraDetected,
+    UnlockedRearming,
+    UnlockedReset,
+    ListenerRecoveryFailed,
+    SaveDirFallback,
+    DiskSpaceLow,
+    TestNotification,
+    SecurityAlertCoalesced,
+    MonitoringPreparing,
+    CancelArming,
+    ShareUnavailableFallback,
+}
+
+impl Message {
+    pub const ALL: [Message; 21] = [
+        Message::DefaultTitle,
+        Message::SecurityAlertTitle,
+        Message::SecurityAlertBody,
+        Message::AppStarted,
+        Message::AppExited,
+        Message::MonitoringArmed,
+        Message::MonitoringDisarmed,
+        Message::AutoArmFailed,
+        Message::RestoreArmedResuming,
+        Message::RestoreArmedSkipped,
+        Message::NoCameraDetected,
+        Message::UnlockedRearming,
+        Message::UnlockedReset,
+        Message::ListenerRecoveryFailed,
+        Message::SaveDirFallback,
+        Message::DiskSpaceLow,
+        Message::TestNotification,
+        Message::SecurityAlertCoalesced,
+        Message::MonitoringPreparing,
+        Message::CancelArming,
+        Message::ShareUnavailableFallback,
+    ];
+}
+
+/// 查询消息模板，模板中的 `{error}`、`{path}` 等占位符由 [`tr_with`] 替换
+pub fn text(language: Language, message: Message) -> &'static str {
+    match (language, message) {
+        (Language::Zh, Message::DefaultTitle) => "SnapLock",
+        (Language::Zh, Message::SecurityAlertTitle) => "SnapLock 安全警报",
+        (Language::Zh, Message::SecurityAlertBody) => "检测到未授权访问",
+        (Language::Zh, Message::AppStarted) => "SnapLock 已启动",
+        (Language::Zh, Message::AppExited) => "SnapLock 已退出",
+        (Language::Zh, Message::MonitoringArmed) => "已进入警戒状态，正在监控活动",
+        (Language::Zh, Message::MonitoringDisarmed) => "已退出警戒状态",
+        (Language::Zh, Message::AutoArmFailed) => "自动进入警戒状态失败: {error}",
+        (Language::Zh, Message::RestoreArmedResuming) => {
+            "SnapLock 重启前处于警戒状态，正在自动恢复警戒"
+        }
+        (Language::Zh, Message::RestoreArmedSkipped) => {
+            "SnapLock 重启前处于警戒状态，当前未警戒，请按需重新开启监控"
+        }
+        (Language::Zh, Message::NoCameraDetected) => {
+            "未检测到摄像头，触发时将仅执行锁屏或录屏，拍摄功能已禁用"
+        }
+        (Language::Zh, Message::UnlockedRearming) => "系统已解锁，即将重新进入警戒状态",
+        (Language::Zh, Message::UnlockedReset) => "系统已解锁，应用状态已重置",
+        (Language::Zh, Message::ListenerRecoveryFailed) => {
+            "输入监听器异常，自动恢复监控失败: {error}"
+        }
+        (Language::Zh, Message::SaveDirFallback) => "保存目录磁盘空间不足，本次文件将保存到 {path}",
+        (Language::Zh, Message::DiskSpaceLow) => "磁盘空间不足，拍摄或录制可能失败",
+        (Language::Zh, Message::TestNotification) => "这是一条测试通知，通知功能工作正常",
+        (Language::Zh, Message::SecurityAlertCoalesced) => {
+            "{body}（{seconds} 秒内共触发 {count} 次）"
+        }
+        (Language::Zh, Message::MonitoringPreparing) => "{seconds} 秒后进入警戒状态",
+        (Language::Zh, Message::CancelArming) => "取消",
+        (Language::Zh, Message::ShareUnavailableFallback) => {
+            "无法连接网络共享，本次文件将保存到 {path}"
+        }
+
+        (Language::En, Message::DefaultTitle) => "SnapLock",
+        (Language::En, Message::SecurityAlertTitle) => "SnapLock Security Alert",
+        (Language::En, Message::SecurityAlertBody) => "Unauthorized access detected",
+        (Language::En, Message::AppStarted) => "SnapLock started",
+        (Language::En, Message::AppExited) => "SnapLock exited",
+        (Language::En, Message::MonitoringArmed) => "Armed, monitoring for activity",
+        (Language::En, Message::MonitoringDisarmed) => "Disarmed",
+        (Language::En, Message::AutoArmFailed) => "Failed to arm automatically: {error}",
+        (Language::En, Message::RestoreArmedResuming) => {
+            "SnapLock was armed before restarting, re-arming automatically"
+        }
+        (Language::En, Message::RestoreArmedSkipped) => {
+            "SnapLock was armed before restarting and is now disarmed, start monitoring again if needed"
+        }
+        (Language::En, Message::NoCameraDetected) => {
+            "No camera detected, triggers will only lock or record the screen and photos are disabled"
+        }
+        (Language::En, Message::UnlockedRearming) => "System unlocked, re-arming shortly",
+        (Language::En, Message::UnlockedReset) => "System unlocked, app state has been reset",
+        (Language::En, Message::ListenerRecoveryFailed) => {
+            "Input listener failed and monitoring could not be recovered: {error}"
+        }
+        (Language::En, Message::SaveDirFallback) => {
+            "Save folder is low on disk space, files will be saved to {path} this time"
+        }
+        (Language::En, Message::DiskSpaceLow) => "Low disk space, photos or recordings may fail",
+        (Language::En, Message::TestNotification) => {
+            "This is a test notification, notifications are working"
+        }
+        (Language::En, Message::SecurityAlertCoalesced) => {
+            "{body} ({count} triggers in the last {seconds}s)"
+        }
+        (Language::En, Message::MonitoringPreparing) => "Arming in {seconds} seconds",
+        (Language::En, Message::CancelArming) => "Cancel",
+        (Language::En, Message::ShareUnavailableFallback) => {
+            "Could not connect to the network share, files will be saved to {path} this time"
+        }
+    }
+}
+
+/// 当前生效的语言：未配置时跟随系统区域
+pub fn current_language(app_handle: &AppHandle) -> Language {
+    app_handle
+        .try_state::<AppState>()
+        .and_then(|state| state.language())
+        .unwrap_or_else(Language::system)
+}
+
+/// 以当前语言获取消息
+pub fn tr(app_handle: &AppHandle, message: Message) -> String {
+    text(current_language(app_handle), message).to_string()
+}
+
+/// 以当前语言获取消息并替换 `{name}` 形式的占位符
+pub fn tr_with(app_handle: &AppHandle, message: Message, args: &[(&str, &str)]) -> String {
+    render(text(current_language(app_handle), message), args)
+}
+
+fn render(template: &str, args: &[(&str, &str)]) -> String {
+    args.iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), value)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Language, Message, render, text};
+
+    #[test]
+    fn every_message_is_translated() {
+        for message in Message::ALL {
+            assert!(!text(Language::Zh, message).is_empty());
+            assert!(!text(Language::En, message).is_empty());
+        }
+        assert_eq!(text(Language::En, Message::MonitoringDisarmed), "Disarmed");
+    }
+
+    #[test]
+    fn locale_prefix_selects_language() {
+        assert_eq!(Language::from_locale("zh_CN.UTF-8"), Some(Language::Zh));
+        assert_eq!(Language::from_locale("en-US"), Some(Language::En));
+        assert_eq!(Language::from_locale("EN"), Some(Language::En));
+        assert_eq!(Language::from_locale("de_DE"), None);
+    }
+
+    #[test]
+    fn render_replaces_named_placeholders() {
+        assert_eq!(
+            render(
+                text(Language::En, Message::AutoArmFailed),
+                &[("error", "no camera")]
+            ),
+            "Failed to arm automatically: no camera"
+        );
+    }
+}
@@ -0,0 +1,472 @@
+// snaplock/src-tauri/src/input_devices.rs
+//! 枚举键盘/鼠标设备，并通过 Raw Input 只将指定设备的输入交给监控流程（仅 Windows）
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum InputDeviceKind {
+    Keyboard,
+    Mouse,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InputDevice {
+    /// Raw Input 设备路径，作为 `monitored_device_id` 保存
+    pub id: String,
+    pub kind: InputDeviceKind,
+    /// 便于识别的简短名称，例如 `HID VID_046D&PID_C52B`
+    pub name: String,
+}
+
+/// 从 `\\?\HID#VID_046D&PID_C52B&MI_00#7&1a2b&0&0000#{guid}` 形式的设备路径提取简短名称
+fn describe_device_path(path: &str) -> String {
+    let trimmed = path.trim_start_matches(['\\', '?']);
+    let mut parts = trimmed.split('#');
+    match (parts.next(), parts.next()) {
+        (Some(bus), Some(hardware_id)) if !bus.is_empty() && !hardware_id.is_empty() => {
+            format!("{} {}", bus, hardware_id)
+        }
+        _ => path.to_string(),
+    }
+}
+
+/// 枚举键盘和鼠标；非 Windows 平台返回空列表
+pub fn list_input_devices() -> Result<Vec<InputDevice>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        windows_impl::list_input_devices()
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        log::info!("当前平台暂不支持枚举输入设备");
+        Ok(Vec::new())
+    }
+}
+
+/// 监控是否应由 Raw Input 按设备过滤（需已配置设备且 Raw Input 监听已启动）
+pub fn filter_active(app_handle: &tauri::AppHandle) -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        windows_impl::filter_active(app_handle)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = app_handle;
+        false
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) use windows_impl::key_from_virtual_key;
+#[cfg(target_os = "windows")]
+pub use windows_impl::start_raw_input_listener;
+
+#[cfg(target_os = "windows")]
+mod windows_impl {
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Mutex, OnceLock};
+
+    use rdev::{Button, EventType, Key};
+    use tauri::{AppHandle, Manager};
+    use windows::Win32::Foundation::{HANDLE, HWND, LPARAM, LRESULT, POINT, WPARAM};
+    use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+    use windows::Win32::UI::Input::{
+        GetRawInputData, GetRawInputDeviceInfoW, GetRawInputDeviceList, HRAWINPUT, RAWINPUT,
+        RAWINPUTDEVICE, RAWINPUTDEVICELIST, RAWINPUTHEADER, RID_INPUT, RIDEV_DEVNOTIFY,
+        RIDEV_INPUTSINK, RIDI_DEVICENAME, RIM_TYPEKEYBOARD, RIM_TYPEMOUSE, RegisterRawInputDevices,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CreateWindowExW, DefWindowProcW, DispatchMessageW, GetCursorPos, GetMessageW, HWND_MESSAGE,
+        MSG, RI_KEY_BREAK, RegisterClassW, WINDOW_EX_STYLE, WINDOW_STYLE, WM_INPUT,
+        WM_INPUT_DEVICE_CHANGE, WNDCLASSW,
+    };
+    use windows::core::w;
+
+    use super::{InputDevice, InputDeviceKind, describe_device_path};
+    use crate::state::AppState;
+
+    static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+    static LISTENER_READY: AtomicBool = AtomicBool::new(false);
+    static DEVICE_NAMES: OnceLock<Mutex<HashMap<isize, String>>> = OnceLock::new();
+    /// 所选设备是否已连接，按设备 ID 缓存，设备插拔时清空
+    static DEVICE_PRESENCE: Mutex<Option<(String, bool)>> = Mutex::new(None);
+
+    /// 所选设备已拔出时不再过滤，改由低级钩子监控全部设备，避免触发被静默关闭
+    pub fn filter_active(app_handle: &AppHandle) -> bool {
+        if !LISTENER_READY.load(Ordering::SeqCst) {
+            return false;
+        }
+        match app_handle.state::<AppState>().monitored_device_id() {
+            Some(device_id) => monitored_device_present(&device_id),
+            None => false,
+        }
+    }
+
+    fn monitored_device_present(device_id: &str) -> bool {
+        let Ok(mut presence) = DEVICE_PRESENCE.lock() else {
+            return true;
+        };
+        if let Some((cached_id, present)) = presence.as_ref() {
+            if cached_id == device_id {
+                return *present;
+            }
+        }
+
+        let present = match list_input_devices() {
+            Ok(devices) => devices.iter().any(|device| device.id == device_id),
+            Err(error) => {
+                // 无法确认时保持按设备过滤
+                log::warn!("无法确认所选输入设备是否已连接: {}", error);
+                true
+            }
+        };
+        if !present {
+            log::warn!("所选输入设备未连接，暂时监控全部键盘和鼠标: {}", device_id);
+        }
+        *presence = Some((device_id.to_string(), present));
+        present
+    }
+
+    /// 设备插拔后句柄可能被系统复用，清空设备路径与连接状态缓存
+    fn invalidate_device_caches() {
+        if let Some(cache) = DEVICE_NAMES.get() {
+            if let Ok(mut cache) = cache.lock() {
+                cache.clear();
+            }
+        }
+        if let Ok(mut presence) = DEVICE_PRESENCE.lock() {
+            *presence = None;
+        }
+    }
+
+    fn device_name(handle: HANDLE) -> Option<String> {
+        let mut size = 0u32;
+        unsafe { GetRawInputDeviceInfoW(Some(handle), RIDI_DEVICENAME, None, &mut size) };
+        if size == 0 {
+            return None;
+        }
+
+        let mut buffer = vec![0u16; size as usize];
+        let copied = unsafe {
+            GetRawInputDeviceInfoW(
+                Some(handle),
+                RIDI_DEVICENAME,
+                Some(buffer.as_mut_ptr().cast()),
+                &mut size,
+            )
+        };
+        if copied == u32::MAX {
+            return None;
+        }
+
+        let len = buffer
+            .iter()
+            .position(|&ch| ch == 0)
+            .unwrap_or(buffer.len());
+        Some(String::from_utf16_lossy(&buffer[..len]))
+    }
+
+    /// 设备路径的查询结果按句柄缓存，避免每个输入事件都调用系统接口
+    fn cached_device_name(handle: HANDLE) -> Option<String> {
+        let cache = DEVICE_NAMES.get_or_init(|| Mutex::new(HashMap::new()));
+        let key = handle.0 as isize;
+        if let Some(name) = cache.lock().ok()?.get(&key) {
+            return Some(name.clone());
+        }
+
+        let name = device_name(handle)?;
+        if let Ok(mut cache) = cache.lock() {
+            cache.insert(key, name.clone());
+        }
+        Some(name)
+    }
+
+    pub fn list_input_devices() -> Result<Vec<InputDevice>, String> {
+        let entry_size = std::mem::size_of::<RAWINPUTDEVICELIST>() as u32;
+        let mut count = 0u32;
+        if unsafe { GetRawInputDeviceList(None, &mut count, entry_size) } == u32::MAX {
+            return Err("枚举输入设备失败".to_string());
+        }
+
+        let mut entries = vec![RAWINPUTDEVICELIST::default(); count as usize];
+        let filled =
+            unsafe { GetRawInputDeviceList(Some(entries.as_mut_ptr()), &mut count, entry_size) };
+        if filled == u32::MAX {
+            return Err("枚举输入设备失败".to_string());
+        }
+        entries.truncate(filled as usize);
+
+        let devices = entries
+            .into_iter()
+            .filter_map(|entry| {
+                let kind = match entry.dwType {
+                    RIM_TYPEKEYBOARD => InputDeviceKind::Keyboard,
+                    RIM_TYPEMOUSE => InputDeviceKind::Mouse,
+                    _ => return None,
+                };
+                let id = device_name(entry.hDevice)?;
+                Some(InputDevice {
+                    name: describe_device_path(&id),
+                    id,
+                    kind,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        log::info!("检测到 {} 个键盘/鼠标设备", devices.len());
+        Ok(devices)
+    }
+
+    /// 创建仅消息窗口并注册键盘、鼠标的 Raw Input，在独立线程中运行消息循环
+    pub fn start_raw_input_listener(app_handle: AppHandle) -> Result<(), String> {
+        if APP_HANDLE.set(app_handle).is_err() {
+            return Err("Raw Input 监听已在运行中".to_string());
+        }
+
+        let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let result = unsafe { create_input_window() };
+            let created = result.is_ok();
+            let _ = ready_tx.send(result);
+            if created {
+                LISTENER_READY.store(true, Ordering::SeqCst);
+                let mut message = MSG::default();
+                while unsafe { GetMessageW(&mut message, None, 0, 0) }.0 > 0 {
+                    unsafe {
+                        DispatchMessageW(&message);
+                    }
+                }
+                LISTENER_READY.store(false, Ordering::SeqCst);
+                log::warn!("Raw Input 消息循环已退出，设备过滤失效");
+            }
+        });
+
+        ready_rx
+            .recv()
+            .map_err(|_| "Raw Input 监听线程意外退出".to_string())?
+    }
+
+    unsafe fn create_input_window() -> Result<(), String> {
+        let instance =
+            unsafe { GetModuleHandleW(None) }.map_err(|e| format!("获取模块句柄失败: {}", e))?;
+        let class_name = w!("SnapLockRawInput");
+        let class = WNDCLASSW {
+            lpfnWndProc: Some(window_proc),
+            hInstance: instance.into(),
+            lpszClassName: class_name,
+            ..Default::default()
+        };
+        if unsafe { RegisterClassW(&class) } == 0 {
+            return Err("注册 Raw Input 窗口类失败".to_string());
+        }
+
+        let hwnd = unsafe {
+            CreateWindowExW(
+                WINDOW_EX_STYLE::default(),
+                class_name,
+                w!("SnapLock Raw Input"),
+                WINDOW_STYLE::default(),
+                0,
+                0,
+                0,
+                0,
+                Some(HWND_MESSAGE),
+                None,
+                Some(instance.into()),
+                None,
+            )
+        }
+        .map_err(|e| format!("创建 Raw Input 窗口失败: {}", e))?;
+
+        // 通用桌面控制页 (0x01) 中的鼠标 (0x02) 与键盘 (0x06)；DEVNOTIFY 用于接收设备插拔通知
+        let devices = [0x02, 0x06].map(|usage| RAWINPUTDEVICE {
+            usUsagePage: 0x01,
+            usUsage: usage,
+            dwFlags: RIDEV_INPUTSINK | RIDEV_DEVNOTIFY,
+            hwndTarget: hwnd,
+        });
+        unsafe { RegisterRawInputDevices(&devices, std::mem::size_of::<RAWINPUTDEVICE>() as u32) }
+            .map_err(|e| format!("注册 Raw Input 设备失败: {}", e))?;
+
+        log::info!("Raw Input 监听已启动");
+        Ok(())
+    }
+
+    unsafe extern "system" fn window_proc(
+        hwnd: HWND,
+        message: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+    ) -> LRESULT {
+        match message {
+            WM_INPUT => handle_raw_input(HRAWINPUT(lparam.0 as _)),
+            WM_INPUT_DEVICE_CHANGE => invalidate_device_caches(),
+            _ => {}
+        }
+        unsafe { DefWindowProcW(hwnd, message, wparam, lparam) }
+    }
+
+    fn handle_raw_input(raw_input: HRAWINPUT) {
+        let Some(app_handle) = APP_HANDLE.get() else {
+            return;
+        };
+        let Some(monitored_device_id) = app_handle.state::<AppState>().monitored_device_id() else {
+            return;
+        };
+
+        let mut input = RAWINPUT::default();
+        let mut size = std::mem::size_of::<RAWINPUT>() as u32;
+        let copied = unsafe {
+            GetRawInputData(
+                raw_input,
+                RID_INPUT,
+                Some((&mut input as *mut RAWINPUT).cast()),
+                &mut size,
+                std::mem::size_of::<RAWINPUTHEADER>() as u32,
+            )
+        };
+        if copied == u32::MAX {
+            return;
+        }
+
+        if cached_device_name(input.header.hDevice).as_deref() != Some(&monitored_device_id) {
+            return;
+        }
+
+        let event_type = match input.header.dwType {
+            RIM_TYPEKEYBOARD => {
+                let keyboard = unsafe { input.data.keyboard };
+                let key = key_from_virtual_key(keyboard.VKey);
+                if u32::from(keyboard.Flags) & RI_KEY_BREAK != 0 {
+                    EventType::KeyRelease(key)
+                } else {
+                    EventType::KeyPress(key)
+                }
+            }
+            RIM_TYPEMOUSE => {
+                let mouse = unsafe { input.data.mouse };
+                let button_flags = unsafe { mouse.Anonymous.Anonymous.usButtonFlags };
+                if button_flags != 0 {
+                    EventType::ButtonPress(Button::Unknown(0))
+                } else {
+                    // lLastX/lLastY 通常是相对位移，与低级钩子一致改为上报当前光标坐标
+                    let mut cursor = POINT::default();
+                    if unsafe { GetCursorPos(&mut cursor) }.is_err() {
+                        return;
+                    }
+                    EventType::MouseMove {
+                        x: f64::from(cursor.x),
+                        y: f64::from(cursor.y),
+                    }
+                }
+            }
+            _ => return,
+        };
+
+        crate::monitoring::handle_device_input(app_handle, event_type);
+    }
+
+    /// 将虚拟键码转换为 rdev::Key，使忽略按键设置对 Raw Input 与低级钩子同样生效
+    pub(crate) fn key_from_virtual_key(vk: u16) -> Key {
+        match vk {
+            0x08 => Key::Backspace,
+            0x09 => Key::Tab,
+            0x0D => Key::Return,
+            0x10 | 0xA0 => Key::ShiftLeft,
+            0xA1 => Key::ShiftRight,
+            0x11 | 0xA2 => Key::ControlLeft,
+            0xA3 => Key::ControlRight,
+            0x12 | 0xA4 => Key::Alt,
+            0xA5 => Key::AltGr,
+            0x13 => Key::Pause,
+            0x14 => Key::CapsLock,
+            0x1B => Key::Escape,
+            0x20 => Key::Space,
+            0x21 => Key::PageUp,
+            0x22 => Key::PageDown,
+            0x23 => Key::End,
+            0x24 => Key::Home,
+            0x25 => Key::LeftArrow,
+            0x26 => Key::UpArrow,
+            0x27 => Key::RightArrow,
+            0x28 => Key::DownArrow,
+            0x2C => Key::PrintScreen,
+            0x2D => Key::Insert,
+            0x2E => Key::Delete,
+            0x30 => Key::Num0,
+            0x31 => Key::Num1,
+            0x32 => Key::Num2,
+            0x33 => Key::Num3,
+            0x34 => Key::Num4,
+            0x35 => Key::Num5,
+            0x36 => Key::Num6,
+            0x37 => Key::Num7,
+            0x38 => Key::Num8,
+            0x39 => Key::Num9,
+            0x41 => Key::KeyA,
+            0x42 => Key::KeyB,
+            0x43 => Key::KeyC,
+            0x44 => Key::KeyD,
+            0x45 => Key::KeyE,
+            0x46 => Key::KeyF,
+            0x47 => Key::KeyG,
+            0x48 => Key::KeyH,
+            0x49 => Key::KeyI,
+            0x4A => Key::KeyJ,
+            0x4B => Key::KeyK,
+            0x4C => Key::KeyL,
+            0x4D => Key::KeyM,
+            0x4E => Key::KeyN,
+            0x4F => Key::KeyO,
+            0x50 => Key::KeyP,
+            0x51 => Key::KeyQ,
+            0x52 => Key::KeyR,
+            0x53 => Key::KeyS,
+            0x54 => Key::KeyT,
+            0x55 => Key::KeyU,
+            0x56 => Key::KeyV,
+            0x57 => Key::KeyW,
+            0x58 => Key::KeyX,
+            0x59 => Key::KeyY,
+            0x5A => Key::KeyZ,
+            0x5B => Key::MetaLeft,
+            0x5C => Key::MetaRight,
+            0x6A => Key::KpMultiply,
+            0x6B => Key::KpPlus,
+            0x6D => Key::KpMinus,
+            0x6F => Key::KpDivide,
+            0x70 => Key::F1,
+            0x71 => Key::F2,
+            0x72 => Key::F3,
+            0x73 => Key::F4,
+            0x74 => Key::F5,
+            0x75 => Key::F6,
+            0x76 => Key::F7,
+            0x77 => Key::F8,
+            0x78 => Key::F9,
+            0x79 => Key::F10,
+            0x7A => Key::F11,
+            0x7B => Key::F12,
+            0x90 => Key::NumLock,
+            0x91 => Key::ScrollLock,
+            other => Key::Unknown(u32::from(other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::describe_device_path;
+
+    #[test]
+    fn device_path_is_shortened_to_bus_and_hardware_id() {
+        assert_eq!(
+            describe_device_path(r"\\?\HID#VID_046D&PID_C52B&MI_00#7&1a2b&0&0000#{884b96c3}"),
+            "HID VID_046D&PID_C52B&MI_00"
+        );
+        assert_eq!(describe_device_path("unknown"), "unknown");
+    }
+}
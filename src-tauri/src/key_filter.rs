@@ -0,0 +1,241 @@
+use rdev::{EventType, Key};
+
+/// 可配置为忽略的按键名称（与 rdev::Key 的变体名保持一致）
+const KEY_NAMES: &[(&str, Key)] = &[
+    ("Alt", Key::Alt),
+    ("AltGr", Key::AltGr),
+    ("Backspace", Key::Backspace),
+    ("CapsLock", Key::CapsLock),
+    ("ControlLeft", Key::ControlLeft),
+    ("ControlRight", Key::ControlRight),
+    ("Delete", Key::Delete),
+    ("DownArrow", Key::DownArrow),
+    ("End", Key::End),
+    ("Escape", Key::Escape),
+    ("F1", Key::F1),
+    ("F2", Key::F2),
+    ("F3", Key::F3),
+    ("F4", Key::F4),
+    ("F5", Key::F5),
+    ("F6", Key::F6),
+    ("F7", Key::F7),
+    ("F8", Key::F8),
+    ("F9", Key::F9),
+    ("F10", Key::F10),
+    ("F11", Key::F11),
+    ("F12", Key::F12),
+    ("Home", Key::Home),
+    ("LeftArrow", Key::LeftArrow),
+    ("MetaLeft", Key::MetaLeft),
+    ("MetaRight", Key::MetaRight),
+    ("PageDown", Key::PageDown),
+    ("PageUp", Key::PageUp),
+    ("Return", Key::Return),
+    ("RightArrow", Key::RightArrow),
+    ("ShiftLeft", Key::ShiftLeft),
+    ("ShiftRight", Key::ShiftRight),
+    ("Space", Key::Space),
+    ("Tab", Key::Tab),
+    ("UpArrow", Key::UpArrow),
+    ("PrintScreen", Key::PrintScreen),
+    ("ScrollLock", Key::ScrollLock),
+    ("Pause", Key::Pause),
+    ("NumLock", Key::NumLock),
+    ("Insert", Key::Insert),
+    ("Function", Key::Function),
+    ("KpReturn", Key::KpReturn),
+    ("KpMinus", Key::KpMinus),
+    ("KpPlus", Key::KpPlus),
+    ("KpMultiply", Key::KpMultiply),
+    ("KpDivide", Key::KpDivide),
+    ("KpDelete", Key::KpDelete),
+];
+
+/// 多媒体按键在 rdev 中没有独立变体，按平台原始键码映射
+#[cfg(target_os = "windows")]
+const MEDIA_KEY_CODES: &[(&str, u32)] = &[
+    ("VolumeMute", 0xAD),
+    ("VolumeDown", 0xAE),
+    ("VolumeUp", 0xAF),
+    ("MediaNextTrack", 0xB0),
+    ("MediaPrevTrack", 0xB1),
+    ("MediaStop", 0xB2),
+    ("MediaPlayPause", 0xB3),
+];
+
+#[cfg(target_os = "linux")]
+const MEDIA_KEY_CODES: &[(&str, u32)] = &[
+    ("VolumeMute", 121),
+    ("VolumeDown", 122),
+    ("VolumeUp", 123),
+    ("MediaNextTrack", 171),
+    ("MediaPlayPause", 172),
+    ("MediaPrevTrack", 173),
+    ("MediaStop", 174),
+];
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+const MEDIA_KEY_CODES: &[(&str, u32)] = &[];
+
+fn parse_unknown_code(name: &str) -> Option<u32> {
+    let inner = name.strip_prefix("Unknown(")?.strip_suffix(')')?;
+    inner.trim().parse::<u32>().ok()
+}
+
+/// 解析按键名称（不区分大小写），返回规范化名称及对应的 rdev::Key
+pub fn parse_key_name(name: &str) -> Option<(String, Key)> {
+    let name = name.trim();
+
+    if let Some((canonical, key)) = KEY_NAMES
+        .iter()
+        .find(|(candidate, _)| candidate.eq_ignore_ascii_case(name))
+    {
+        return Some((canonical.to_string(), *key));
+    }
+
+    if let Some((canonical, code)) = MEDIA_KEY_CODES
+        .iter()
+        .find(|(candidate, _)| candidate.eq_ignore_ascii_case(name))
+    {
+        return Some((canonical.to_string(), Key::Unknown(*code)));
+    }
+
+    let upper = name.to_ascii_uppercase();
+    if upper.len() == 1 {
+        let ch = upper.chars().next()?;
+        if ch.is_ascii_alphabetic() {
+            let key = letter_key(ch)?;
+            return Some((format!("Key{}", ch), key));
+        }
+        if ch.is_ascii_digit() {
+            let key = digit_key(ch)?;
+            return Some((format!("Num{}", ch), key));
+        }
+    }
+
+    if let Some(letter) = upper.strip_prefix("KEY") {
+        if letter.len() == 1 {
+            return parse_key_name(letter);
+        }
+    }
+
+    if let Some(digit) = upper.strip_prefix("NUM") {
+        if digit.len() == 1 && digit.chars().all(|ch| ch.is_ascii_digit()) {
+            return parse_key_name(digit);
+        }
+    }
+
+    if let Some(code) = parse_unknown_code(name) {
+        return Some((format!("Unknown({})", code), Key::Unknown(code)));
+    }
+
+    None
+}
+
+fn letter_key(ch: char) -> Option<Key> {
+    Some(match ch {
+        'A' => Key::KeyA,
+        'B' => Key::KeyB,
+        'C' => Key::KeyC,
+        'D' => Key::KeyD,
+        'E' => Key::KeyE,
+        'F' => Key::KeyF,
+        'G' => Key::KeyG,
+        'H' => Key::KeyH,
+        'I' => Key::KeyI,
+        'J' => Key::KeyJ,
+        'K' => Key::KeyK,
+        'L' => Key::KeyL,
+        'M' => Key::KeyM,
+        'N' => Key::KeyN,
+        'O' => Key::KeyO,
+        'P' => Key::KeyP,
+        'Q' => Key::KeyQ,
+        'R' => Key::KeyR,
+        'S' => Key::KeyS,
+        'T' => Key::KeyT,
+        'U' => Key::KeyU,
+        'V' => Key::KeyV,
+        'W' => Key::KeyW,
+        'X' => Key::KeyX,
+        'Y' => Key::KeyY,
+        'Z' => Key::KeyZ,
+        _ => return None,
+    })
+}
+
+fn digit_key(ch: char) -> Option<Key> {
+    Some(match ch {
+        '0' => Key::Num0,
+        '1' => Key::Num1,
+        '2' => Key::Num2,
+        '3' => Key::Num3,
+        '4' => Key::Num4,
+        '5' => Key::Num5,
+        '6' => Key::Num6,
+        '7' => Key::Num7,
+        '8' => Key::Num8,
+        '9' => Key::Num9,
+        _ => return None,
+    })
+}
+
+/// 判断输入事件是否为被忽略的按键（按下或释放）
+pub fn is_ignored_key_event(event_type: &EventType, ignored_keys: &[String]) -> bool {
+    let key = match event_type {
+        EventType::KeyPress(key) | EventType::KeyRelease(key) => key,
+        _ => return false,
+    };
+
+    ignored_keys
+        .iter()
+        .filter_map(|name| parse_key_name(name))
+        .any(|(_, ignored)| ignored == *key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_ignored_key_event, parse_key_name};
+    use rdev::{Button, EventType, Key};
+
+    #[test]
+    fn parses_named_keys_case_insensitively() {
+        assert_eq!(
+            parse_key_name("pageup"),
+            Some(("PageUp".to_string(), Key::PageUp))
+        );
+        assert_eq!(parse_key_name("l"), Some(("KeyL".to_string(), Key::KeyL)));
+        assert_eq!(
+            parse_key_name("Unknown(175)"),
+            Some(("Unknown(175)".to_string(), Key::Unknown(175)))
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_key_names() {
+        assert_eq!(parse_key_name("NotAKey"), None);
+        assert_eq!(parse_key_name(""), None);
+    }
+
+    #[test]
+    fn ignores_only_configured_key_events() {
+        let ignored = vec!["F5".to_string()];
+
+        assert!(is_ignored_key_event(
+            &EventType::KeyPress(Key::F5),
+            &ignored
+        ));
+        assert!(is_ignored_key_event(
+            &EventType::KeyRelease(Key::F5),
+            &ignored
+        ));
+        assert!(!is_ignored_key_event(
+            &EventType::KeyPress(Key::KeyA),
+            &ignored
+        ));
+        assert!(!is_ignored_key_event(
+            &EventType::ButtonPress(Button::Left),
+            &ignored
+        ));
+    }
+}
@@ -0,0 +1,298 @@
+// snaplock/src-tauri/src/local_api.rs
+//! 可选的本地 HTTP 接口，供家庭自动化等本机程序警戒、解除警戒、查询状态与触发测试拍摄。
+//!
+//! 只监听 127.0.0.1，每个请求都必须在 `X-SnapLock-Token` 请求头中携带配置的令牌。
+//! 接口只有几个无请求体的端点，直接基于 tokio 解析请求行与请求头，不引入 HTTP 框架。
+
+use std::net::Ipv4Addr;
+use std::sync::Mutex;
+
+use serde_json::{Value, json};
+use tauri::{AppHandle, Manager, async_runtime::JoinHandle};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::constants::LOCAL_API_REQUEST_TIMEOUT;
+use crate::handlers;
+use crate::state::{AppState, MonitoringState};
+
+const TOKEN_HEADER: &str = "x-snaplock-token";
+/// 请求行与请求头的最大长度，超出部分不再读取
+const MAX_REQUEST_HEAD_BYTES: u64 = 8 * 1024;
+
+static SERVER: Mutex<Option<JoinHandle<()>>> = Mutex::new(None);
+
+#[derive(Debug, PartialEq, Eq)]
+struct Request {
+    method: String,
+    path: String,
+    token: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Route {
+    Status,
+    Arm,
+    Disarm,
+    TestCapture,
+}
+
+/// 解析请求行与请求头，只保留路由和鉴权需要的部分
+fn parse_request(head: &str) -> Option<Request> {
+    let mut lines = head.lines();
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let target = request_line.next()?;
+    let path = target.split('?').next().unwrap_or(target).to_string();
+
+    let token = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case(TOKEN_HEADER))
+        .map(|(_, value)| value.trim().to_string());
+
+    Some(Request {
+        method,
+        path,
+        token,
+    })
+}
+
+/// 路径不存在时返回 404，方法不匹配时返回 405
+fn route(method: &str, path: &str) -> Result<Route, u16> {
+    let (expected_method, route) = match path.trim_end_matches('/') {
+        "/status" => ("GET", Route::Status),
+        "/arm" => ("POST", Route::Arm),
+        "/disarm" => ("POST", Route::Disarm),
+        "/test-capture" => ("POST", Route::TestCapture),
+        _ => return Err(404),
+    };
+    if method != expected_method {
+        return Err(405);
+    }
+    Ok(route)
+}
+
+/// 比较令牌时不提前返回，避免通过响应时间逐字节猜测令牌
+fn token_matches(expected: &str, provided: Option<&str>) -> bool {
+    let Some(provided) = provided else {
+        return false;
+    };
+    let (expected, provided) = (expected.as_bytes(), provided.as_bytes());
+    expected.len() == provided.len()
+        && expected
+            .iter()
+            .zip(provided)
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn response(status: u16, body: &Value) -> String {
+    let reason = match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        _ => "Internal Server Error",
+    };
+    let body = body.to_string();
+    format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    )
+}
+
+fn error_body(message: &str) -> Value {
+    json!({ "error": message })
+}
+
+fn result_response(result: Result<(), String>) -> (u16, Value) {
+    match result {
+        Ok(()) => (200, json!({ "ok": true })),
+        Err(error) => (500, error_body(&error)),
+    }
+}
+
+/// 按设置启动或停止本地 HTTP 接口；设置变更后重新调用即可生效
+pub fn restart(app_handle: &AppHandle) {
+    let previous = abort_server();
+
+    let config = app_handle.state::<AppState>().local_api();
+    if !config.enabled {
+        return;
+    }
+    let Some(token) = config.token.filter(|token| !token.trim().is_empty()) else {
+        log::warn!("本地 HTTP 接口未设置访问令牌，已跳过启动");
+        return;
+    };
+
+    let app_handle = app_handle.clone();
+    let handle = tauri::async_runtime::spawn(async move {
+        // 旧监听任务结束后端口才会释放，否则重新绑定同一端口可能失败
+        if let Some(previous) = previous {
+            let _ = previous.await;
+        }
+        serve(app_handle, config.port, token).await;
+    });
+    *SERVER.lock().unwrap() = Some(handle);
+}
+
+/// 停止本地 HTTP 接口并释放端口
+pub fn stop() {
+    abort_server();
+}
+
+/// 中止正在运行的监听任务，返回其句柄供调用方等待端口释放
+fn abort_server() -> Option<JoinHandle<()>> {
+    let handle = SERVER.lock().unwrap().take()?;
+    handle.abort();
+    log::info!("本地 HTTP 接口已停止");
+    Some(handle)
+}
+
+async fn serve(app_handle: AppHandle, port: u16, token: String) {
+    let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, port)).await {
+        Ok(listener) => listener,
+        Err(error) => {
+            log::error!("本地 HTTP 接口无法监听 127.0.0.1:{}: {}", port, error);
+            return;
+        }
+    };
+    log::info!("本地 HTTP 接口已在 127.0.0.1:{} 启动", port);
+
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(error) => {
+                log::warn!("接受本地 HTTP 连接失败: {}", error);
+                continue;
+            }
+        };
+
+        let app_handle = app_handle.clone();
+        let token = token.clone();
+        tauri::async_runtime::spawn(async move {
+            match tokio::time::timeout(
+                LOCAL_API_REQUEST_TIMEOUT,
+                handle_connection(stream, &app_handle, &token),
+            )
+            .await
+            {
+                Ok(Ok(())) => {}
+                Ok(Err(error)) => log::debug!("处理本地 HTTP 请求失败: {}", error),
+                Err(_) => log::debug!("本地 HTTP 请求超时"),
+            }
+        });
+    }
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    app_handle: &AppHandle,
+    token: &str,
+) -> std::io::Result<()> {
+    let head = {
+        let mut reader = BufReader::new((&mut stream).take(MAX_REQUEST_HEAD_BYTES));
+        let mut head = String::new();
+        loop {
+            let read = reader.read_line(&mut head).await?;
+            if read == 0 || head.ends_with("\r\n\r\n") || head.ends_with("\n\n") {
+                break;
+            }
+        }
+        head
+    };
+
+    let (status, body) = match parse_request(&head) {
+        None => (400, error_body("invalid request")),
+        Some(request) if !token_matches(token, request.token.as_deref()) => {
+            log::warn!("本地 HTTP 接口拒绝了令牌无效的请求: {}", request.path);
+            (401, error_body("invalid token"))
+        }
+        Some(request) => match route(&request.method, &request.path) {
+            Ok(route) => {
+                log::info!("本地 HTTP 接口请求: {} {}", request.method, request.path);
+                dispatch(app_handle, route).await
+            }
+            Err(status) => (status, error_body("unsupported endpoint")),
+        },
+    };
+
+    stream.write_all(response(status, &body).as_bytes()).await?;
+    stream.shutdown().await
+}
+
+async fn dispatch(app_handle: &AppHandle, route: Route) -> (u16, Value) {
+    match route {
+        Route::Status => match handlers::get_monitoring_status(app_handle.clone()) {
+            Ok(info) => (200, json!(info)),
+            Err(error) => (500, error_body(&error)),
+        },
+        Route::Arm => {
+            let camera_id = app_handle.state::<AppState>().camera_id();
+            result_response(handlers::start_monitoring_command(app_handle.clone(), camera_id).await)
+        }
+        Route::Disarm => {
+            result_response(handlers::stop_monitoring_command(app_handle.clone()).await)
+        }
+        Route::TestCapture => {
+            if app_handle.state::<AppState>().status() != MonitoringState::Idle {
+                return (
+                    409,
+                    error_body("exit the armed state before a test capture"),
+                );
+            }
+            // 模拟触发需要完成拍摄与保存，立即返回并在后台执行
+            let app_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(error) = handlers::simulate_trigger(app_handle).await {
+                    log::error!("本地 HTTP 接口触发测试拍摄失败: {}", error);
+                }
+            });
+            (202, json!({ "ok": true }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Request, Route, parse_request, route, token_matches};
+
+    #[test]
+    fn request_head_is_parsed_case_insensitively() {
+        let head =
+            "POST /arm?source=ha HTTP/1.1\r\nHost: 127.0.0.1\r\nx-SnapLock-token:  secret \r\n\r\n";
+        assert_eq!(
+            parse_request(head),
+            Some(Request {
+                method: "POST".to_string(),
+                path: "/arm".to_string(),
+                token: Some("secret".to_string()),
+            })
+        );
+        assert_eq!(parse_request(""), None);
+        assert_eq!(parse_request("GET\r\n\r\n"), None);
+    }
+
+    #[test]
+    fn routes_require_matching_method() {
+        assert_eq!(route("GET", "/status"), Ok(Route::Status));
+        assert_eq!(route("POST", "/disarm/"), Ok(Route::Disarm));
+        assert_eq!(route("POST", "/test-capture"), Ok(Route::TestCapture));
+        assert_eq!(route("GET", "/arm"), Err(405));
+        assert_eq!(route("POST", "/unknown"), Err(404));
+    }
+
+    #[test]
+    fn token_must_match_exactly() {
+        assert!(token_matches("secret", Some("secret")));
+        assert!(!token_matches("secret", Some("secreT")));
+        assert!(!token_matches("secret", Some("secret2")));
+        assert!(!token_matches("secret", None));
+    }
+}
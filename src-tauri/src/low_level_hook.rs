@@ -0,0 +1,145 @@
+// snaplock/src-tauri/src/low_level_hook.rs
+//! 基于 Windows 低级键盘/鼠标钩子的全局输入监听，作为 rdev 之外的可选实现（仅 Windows）
+//!
+//! 与 `rdev::listen` 一样阻塞当前线程运行消息循环，并把输入转换为 `rdev::Event` 交给同一套回调。
+
+use std::cell::RefCell;
+use std::time::SystemTime;
+
+use rdev::{Button, Event, EventType};
+use windows::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CallNextHookEx, DispatchMessageW, GetMessageW, HHOOK, KBDLLHOOKSTRUCT, MSG, MSLLHOOKSTRUCT,
+    SetWindowsHookExW, TranslateMessage, UnhookWindowsHookEx, WH_KEYBOARD_LL, WH_MOUSE_LL,
+    WM_KEYDOWN, WM_KEYUP, WM_LBUTTONDOWN, WM_LBUTTONUP, WM_MBUTTONDOWN, WM_MBUTTONUP,
+    WM_MOUSEHWHEEL, WM_MOUSEMOVE, WM_MOUSEWHEEL, WM_RBUTTONDOWN, WM_RBUTTONUP, WM_SYSKEYDOWN,
+    WM_SYSKEYUP, WM_XBUTTONDOWN, WM_XBUTTONUP,
+};
+
+use crate::input_devices::key_from_virtual_key;
+
+/// 滚轮每一格对应的增量
+const WHEEL_DELTA: i16 = 120;
+
+thread_local! {
+    /// 钩子回调在安装钩子的线程上执行，回调随线程保存
+    static CALLBACK: RefCell<Option<Box<dyn FnMut(Event)>>> = RefCell::new(None);
+}
+
+/// 卸载钩子的守卫，消息循环退出或出错时自动卸载
+struct HookGuard(Vec<HHOOK>);
+
+impl Drop for HookGuard {
+    fn drop(&mut self) {
+        for hook in self.0.drain(..) {
+            if let Err(error) = unsafe { UnhookWindowsHookEx(hook) } {
+                log::warn!("卸载低级输入钩子失败: {}", error);
+            }
+        }
+    }
+}
+
+/// 安装低级键盘与鼠标钩子并运行消息循环，直到消息循环退出
+pub fn listen<F>(callback: F) -> Result<(), String>
+where
+    F: FnMut(Event) + 'static,
+{
+    CALLBACK.with(|slot| *slot.borrow_mut() = Some(Box::new(callback)));
+
+    let instance =
+        unsafe { GetModuleHandleW(None) }.map_err(|e| format!("获取模块句柄失败: {}", e))?;
+    let mut guard = HookGuard(Vec::new());
+    for (hook_id, hook_proc, name) in [
+        (
+            WH_KEYBOARD_LL,
+            keyboard_proc as unsafe extern "system" fn(i32, WPARAM, LPARAM) -> LRESULT,
+            "键盘",
+        ),
+        (WH_MOUSE_LL, mouse_proc, "鼠标"),
+    ] {
+        let hook = unsafe { SetWindowsHookExW(hook_id, Some(hook_proc), Some(instance.into()), 0) }
+            .map_err(|e| format!("安装低级{}钩子失败: {}", name, e))?;
+        guard.0.push(hook);
+    }
+
+    log::info!("低级键盘/鼠标钩子已安装");
+    let mut message = MSG::default();
+    while unsafe { GetMessageW(&mut message, None, 0, 0) }.0 > 0 {
+        unsafe {
+            let _ = TranslateMessage(&message);
+            DispatchMessageW(&message);
+        }
+    }
+
+    drop(guard);
+    CALLBACK.with(|slot| *slot.borrow_mut() = None);
+    Err("低级输入钩子消息循环已退出".to_string())
+}
+
+fn dispatch(event_type: EventType) {
+    let event = Event {
+        time: SystemTime::now(),
+        name: None,
+        event_type,
+    };
+    CALLBACK.with(|slot| {
+        // 回调执行期间不会重入消息循环，借用失败时直接丢弃事件
+        if let Ok(mut callback) = slot.try_borrow_mut() {
+            if let Some(callback) = callback.as_mut() {
+                callback(event);
+            }
+        }
+    });
+}
+
+unsafe extern "system" fn keyboard_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    // code 小于 0 时必须直接交给下一个钩子
+    if code >= 0 {
+        let info = unsafe { &*(lparam.0 as *const KBDLLHOOKSTRUCT) };
+        let key = key_from_virtual_key(info.vkCode as u16);
+        match wparam.0 as u32 {
+            WM_KEYDOWN | WM_SYSKEYDOWN => dispatch(EventType::KeyPress(key)),
+            WM_KEYUP | WM_SYSKEYUP => dispatch(EventType::KeyRelease(key)),
+            _ => {}
+        }
+    }
+    unsafe { CallNextHookEx(None, code, wparam, lparam) }
+}
+
+unsafe extern "system" fn mouse_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    // code 小于 0 时必须直接交给下一个钩子
+    if code >= 0 {
+        let info = unsafe { &*(lparam.0 as *const MSLLHOOKSTRUCT) };
+        // 滚轮增量与 X 键编号位于 mouseData 的高 16 位
+        let high_word = (info.mouseData >> 16) as u16;
+        let wheel = i64::from(high_word as i16 / WHEEL_DELTA);
+        let event_type = match wparam.0 as u32 {
+            WM_MOUSEMOVE => Some(EventType::MouseMove {
+                x: f64::from(info.pt.x),
+                y: f64::from(info.pt.y),
+            }),
+            WM_LBUTTONDOWN => Some(EventType::ButtonPress(Button::Left)),
+            WM_LBUTTONUP => Some(EventType::ButtonRelease(Button::Left)),
+            WM_RBUTTONDOWN => Some(EventType::ButtonPress(Button::Right)),
+            WM_RBUTTONUP => Some(EventType::ButtonRelease(Button::Right)),
+            WM_MBUTTONDOWN => Some(EventType::ButtonPress(Button::Middle)),
+            WM_MBUTTONUP => Some(EventType::ButtonRelease(Button::Middle)),
+            WM_XBUTTONDOWN => Some(EventType::ButtonPress(Button::Unknown(high_word as u8))),
+            WM_XBUTTONUP => Some(EventType::ButtonRelease(Button::Unknown(high_word as u8))),
+            WM_MOUSEWHEEL => Some(EventType::Wheel {
+                delta_x: 0,
+                delta_y: wheel,
+            }),
+            WM_MOUSEHWHEEL => Some(EventType::Wheel {
+                delta_x: wheel,
+                delta_y: 0,
+            }),
+            _ => None,
+        };
+        if let Some(event_type) = event_type {
+            dispatch(event_type);
+        }
+    }
+    unsafe { CallNextHookEx(None, code, wparam, lparam) }
+}
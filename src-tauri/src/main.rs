@@ -5,20 +5,43 @@ mod app_setup;
 mod camera;
 mod config;
 mod constants;
+mod email;
+mod event_history;
+mod exif;
+mod ffmpeg;
 mod handlers;
+mod i18n;
+mod input_devices;
+mod key_filter;
+mod local_api;
 mod logger;
 mod monitoring;
+mod motion;
+mod network_share;
+mod notifications;
+mod prebuffer;
+mod preview;
 mod process_utils;
 mod recorder;
+mod secret_store;
 mod state;
+mod storage;
+mod system_info;
+mod watermark;
+mod webhook;
+mod window_geometry;
 
+#[cfg(target_os = "windows")]
+mod low_level_hook;
+#[cfg(target_os = "windows")]
+mod power_monitor;
 #[cfg(target_os = "windows")]
 mod session_monitor;
 
 use crate::state::{AppState, MonitoringFlags, MonitoringLifecycleLock};
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
-use tauri_plugin_notification::NotificationExt;
+use tauri::Manager;
 
 fn main() {
     let app_state = AppState::new(0);
@@ -27,15 +50,17 @@ fn main() {
     let monitoring_flags = Arc::new(MonitoringFlags::new());
     let monitoring_lifecycle = Arc::new(MonitoringLifecycleLock::new(()));
 
+    let launch_options = app_setup::LaunchOptions::from_args(std::env::args());
     let builder = app_setup::setup_tauri_builder();
 
     builder
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_opener::init())
         .manage(app_state)
         .manage(monitoring_flags)
         .manage(monitoring_lifecycle)
         .manage(last_toggle_time)
-        .setup(|app| {
+        .setup(move |app| {
             let handle = app.handle().clone();
 
             // 初始化日志系统
@@ -49,6 +74,12 @@ fn main() {
             } else {
                 log::info!("应用配置已加载");
             }
+            logger::refresh_sensitive_values(&handle.state::<AppState>());
+
+            app_setup::sync_autostart_state(&handle);
+
+            // 恢复上次的窗口位置与大小（窗口此时仍隐藏）
+            window_geometry::restore(&handle);
 
             // 初始化会话监控器 (仅Windows)
             #[cfg(target_os = "windows")]
@@ -63,18 +94,24 @@ fn main() {
                         log::info!("会话监控已启动");
                     }
                 }
-            }
 
-            // 请求通知权限
-            #[cfg(target_os = "windows")]
-            {
-                if let Err(e) = app.notification().request_permission() {
-                    eprintln!("Failed to request notification permission: {}", e);
+                // 按设备过滤输入所需的 Raw Input 监听
+                if let Err(e) = input_devices::start_raw_input_listener(handle.clone()) {
+                    log::error!("启动 Raw Input 监听失败: {}", e);
+                }
+
+                // 合盖/睡眠触发
+                if let Err(e) = power_monitor::start_power_monitoring(handle.clone()) {
+                    log::error!("启动电源事件监控失败: {}", e);
                 }
             }
 
+            // 检查通知权限，被拒绝时后续不再尝试发送通知
+            notifications::init_permission_state(&handle);
+
             // Setup tray icon
             let _tray = app_setup::setup_system_tray(&handle)?;
+            app_setup::apply_stealth_mode(&handle);
 
             // Register global shortcuts - 不要让快捷键注册失败导致程序崩溃
             if let Err(e) = app_setup::register_global_shortcuts(app) {
@@ -82,25 +119,68 @@ fn main() {
                 // 程序继续运行，用户可以通过系统托盘或界面操作
             }
 
+            // 按设置启动本地 HTTP 接口（仅监听 127.0.0.1）
+            local_api::restart(&handle);
+
+            // 警戒期间输入监听器意外退出时自动恢复
+            monitoring::start_listener_watchdog(handle.clone());
+
+            // 应用默认隐藏在托盘中，按需提示已成功启动
+            if app.state::<AppState>().notify_on_startup() {
+                notifications::show(&handle, &i18n::tr(&handle, i18n::Message::AppStarted));
+            }
+
+            // 启动后自动进入警戒（仍会经过准备期，可通过托盘或快捷键解除）
+            if launch_options.arm || app.state::<AppState>().auto_arm_on_start() {
+                log::info!("已启用启动时自动警戒 (命令行: {})", launch_options.arm);
+                let auto_arm_handle = handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    handlers::auto_arm_on_start(&auto_arm_handle).await;
+                });
+            } else {
+                let restore_handle = handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    handlers::restore_armed_state(&restore_handle).await;
+                });
+            }
+
+            // 窗口在配置中默认隐藏，启动时只显示托盘图标；`--hidden` 时确保不会显示
+            if launch_options.hidden {
+                log::info!("以 --hidden 启动，保持主窗口隐藏");
+                if let Some(window) = app.get_webview_window("main") {
+                    if let Err(error) = window.hide() {
+                        log::error!("隐藏主窗口失败: {}", error);
+                    }
+                }
+            }
+            app_setup::run_silent_startup_check(&handle);
+
             Ok(())
         })
         .on_window_event(|window, event| {
             match event {
+                tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_) => {
+                    window_geometry::record(window);
+                }
                 tauri::WindowEvent::CloseRequested { api, .. } => {
                     api.prevent_close();
-                    if let Err(error) = window.hide() {
-                        log::error!("隐藏窗口失败: {}", error);
+                    window_geometry::persist(window.app_handle());
+                    if window.state::<AppState>().close_to_tray() {
+                        if let Err(error) = window.hide() {
+                            log::error!("隐藏窗口失败: {}", error);
+                        }
+                        // 窗口隐藏后预览不可见，释放摄像头
+                        tauri::async_runtime::spawn(preview::stop_async());
+                    } else {
+                        log::info!("关闭窗口，正在退出应用...");
+                        app_setup::request_app_exit(window.app_handle());
                     }
                 }
                 tauri::WindowEvent::Destroyed => {
                     // 应用退出时，确保停止所有后台进程
                     log::info!("窗口已销毁，正在停止后台进程...");
-                    crate::recorder::stop_screen_recording();
-                    tauri::async_runtime::spawn(async {
-                        if let Err(error) = crate::camera::stop_video_recording().await {
-                            log::error!("停止摄像头录像失败: {}", error);
-                        }
-                    });
+                    // 同步停止，避免进程在异步任务完成前退出导致 ffmpeg 残留
+                    app_setup::shutdown(window.app_handle());
                 }
                 _ => {}
             }
@@ -111,10 +191,14 @@ fn main() {
             camera::get_camera_list,
             camera::check_camera_permission,
             camera::get_camera_preview,
+            preview::start_preview_stream,
+            preview::stop_preview_stream,
+            camera::get_camera_formats,
             handlers::set_camera_id,
             camera::set_save_path,
             handlers::get_shortcut_key,
             handlers::set_shortcut_key,
+            handlers::get_active_shortcut,
             handlers::disable_shortcuts,
             handlers::enable_shortcuts,
             handlers::get_show_debug_logs,
@@ -131,6 +215,7 @@ fn main() {
             config::save_config,
             config::load_config,
             config::save_dark_mode_setting,
+            handlers::reset_config,
             logger::get_debug_logs,
             logger::clear_debug_logs,
             logger::set_log_to_file,
@@ -142,8 +227,141 @@ fn main() {
             handlers::get_capture_delay_seconds,
             handlers::set_capture_delay_seconds,
             handlers::get_capture_mode,
-            handlers::set_capture_mode
+            handlers::set_capture_mode,
+            handlers::get_capture_image_format,
+            handlers::set_capture_image_format,
+            handlers::get_watermark_enabled,
+            handlers::set_watermark_enabled,
+            handlers::get_max_segment_seconds,
+            handlers::set_max_segment_seconds,
+            handlers::get_min_free_mb,
+            handlers::set_min_free_mb,
+            handlers::get_ignored_keys,
+            handlers::add_ignored_key,
+            handlers::remove_ignored_key,
+            event_history::get_event_history,
+            event_history::clear_event_history,
+            handlers::get_event_history_limit,
+            handlers::set_event_history_limit,
+            handlers::get_preparation_delay,
+            handlers::set_preparation_delay,
+            handlers::get_notification_templates,
+            handlers::set_notification_templates,
+            handlers::get_retrigger_cooldown_ms,
+            handlers::set_retrigger_cooldown_ms,
+            handlers::get_rearm_after_trigger,
+            handlers::set_rearm_after_trigger,
+            handlers::get_autostart,
+            handlers::set_autostart,
+            handlers::get_auto_arm_on_start,
+            handlers::set_auto_arm_on_start,
+            handlers::get_close_behavior,
+            handlers::set_close_behavior,
+            handlers::get_allow_no_camera,
+            handlers::set_allow_no_camera,
+            handlers::get_notify_on_startup,
+            handlers::set_notify_on_startup,
+            handlers::get_recording_region,
+            handlers::set_recording_region,
+            handlers::get_capture_camera_ids,
+            handlers::set_capture_camera_ids,
+            handlers::get_restore_armed_state,
+            handlers::set_restore_armed_state,
+            handlers::get_monitoring_status,
+            handlers::get_jpeg_quality,
+            handlers::set_jpeg_quality,
+            handlers::get_photo_warmup_frames,
+            handlers::set_photo_warmup_frames,
+            handlers::get_camera_busy_retry,
+            handlers::set_camera_busy_retry,
+            handlers::get_prebuffer_seconds,
+            handlers::set_prebuffer_seconds,
+            handlers::get_timing_windows,
+            handlers::set_timing_windows,
+            handlers::get_language,
+            handlers::set_language,
+            handlers::get_capture_timeout_ms,
+            handlers::set_capture_timeout_ms,
+            handlers::get_recording_duration_seconds,
+            handlers::set_recording_duration_seconds,
+            handlers::get_recording_quality,
+            handlers::set_recording_quality,
+            handlers::get_ffmpeg_path,
+            handlers::set_ffmpeg_path,
+            handlers::check_ffmpeg,
+            handlers::run_self_check,
+            notifications::send_test_notification,
+            handlers::get_trigger_mode,
+            handlers::set_trigger_mode,
+            handlers::get_idle_trigger_minutes,
+            handlers::set_idle_trigger_minutes,
+            handlers::get_camera_recording_seconds,
+            handlers::set_camera_recording_seconds,
+            handlers::get_recording_idle_timeout,
+            handlers::set_recording_idle_timeout,
+            handlers::get_organize_by_date,
+            handlers::set_organize_by_date,
+            handlers::open_save_folder,
+            handlers::open_capture,
+            handlers::get_audio_input_devices,
+            handlers::get_audio_device,
+            handlers::set_audio_device,
+            handlers::get_trigger_on_lid_close,
+            handlers::set_trigger_on_lid_close,
+            notifications::get_notification_permission_denied,
+            handlers::simulate_trigger,
+            handlers::capture_now,
+            handlers::start_recording_now,
+            handlers::stop_recording_now,
+            handlers::get_input_devices,
+            handlers::get_monitored_device_id,
+            handlers::set_monitored_device_id,
+            handlers::get_record_before_lock_seconds,
+            handlers::set_record_before_lock_seconds,
+            handlers::get_photo_with_screen_recording,
+            handlers::set_photo_with_screen_recording,
+            handlers::get_capture_screenshot_on_trigger,
+            handlers::set_capture_screenshot_on_trigger,
+            system_info::get_system_info,
+            handlers::get_on_unlock_action,
+            handlers::set_on_unlock_action,
+            handlers::get_panic_shortcut_key,
+            handlers::set_panic_shortcut_key,
+            handlers::get_stealth_mode,
+            handlers::set_stealth_mode,
+            handlers::get_hide_tray_icon,
+            handlers::set_hide_tray_icon,
+            handlers::get_lock_first,
+            handlers::set_lock_first,
+            handlers::get_alert_min_interval_seconds,
+            handlers::set_alert_min_interval_seconds,
+            handlers::get_shortcut_retry,
+            handlers::set_shortcut_retry,
+            handlers::get_arm_settle_ms,
+            handlers::set_arm_settle_ms,
+            handlers::get_monitoring_backend,
+            handlers::set_monitoring_backend,
+            handlers::get_trigger_sources,
+            handlers::set_trigger_sources,
+            handlers::get_camera_fourcc,
+            handlers::set_camera_fourcc,
+            handlers::get_local_api,
+            handlers::set_local_api,
+            handlers::get_share_credentials,
+            handlers::set_share_credentials,
+            handlers::get_webhook_url,
+            handlers::set_webhook_url,
+            handlers::get_smtp_config,
+            handlers::set_smtp_config,
+            handlers::get_motion_detection,
+            handlers::set_motion_detection
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            if let tauri::RunEvent::Exit = event {
+                // 兜底：任何退出路径都不应留下摄像头或屏幕录制进程
+                app_setup::shutdown(app_handle);
+            }
+        });
 }
@@ -1,16 +1,112 @@
 use std::process::Command;
 use std::sync::{mpsc, Arc};
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use crate::{
     camera,
-    constants::EVENT_IGNORE_WINDOW_MS,
-    state::{AppState, MonitoringFlags, MonitoringState},
+    config::MonitoringBackend,
+    constants::{
+        FFMPEG_RESTART_BASE_DELAY, FFMPEG_RESTART_MAX_ATTEMPTS, FFMPEG_RESTART_MAX_DELAY,
+        FFMPEG_RESTART_STABLE_PERIOD, FFMPEG_WATCHDOG_INTERVAL, IDLE_TRIGGER_CHECK_INTERVAL,
+        LISTENER_RECOVERY_MAX_ATTEMPTS, LISTENER_WATCHDOG_INTERVAL,
+        RECORDING_ALWAYS_ON_RECHECK_INTERVAL, SHORTCUT_KEY_RELEASE_TIMEOUT_MS,
+    },
+    state::{AppState, MonitoringFlags, MonitoringState, ShortcutKeyState},
 };
-use rdev::{listen, Event};
+use chrono::Local;
+use rdev::{listen, Event, EventType};
+use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter, Manager};
 use tokio::{task, time::sleep};
 
+/// 触发锁定的输入来源
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TriggerSource {
+    Keyboard,
+    Mouse,
+    /// 无活动触发模式下超过阈值时间无任何输入
+    Idle,
+    /// 合上笔记本盖子或系统即将睡眠
+    LidClose,
+    /// 通过 `simulate_trigger` 发起的模拟触发
+    Simulated,
+    /// 摄像头画面变化超过阈值
+    Motion,
+}
+
+impl TriggerSource {
+    fn from_event_type(event_type: &EventType) -> Self {
+        match event_type {
+            EventType::KeyPress(_) | EventType::KeyRelease(_) => TriggerSource::Keyboard,
+            _ => TriggerSource::Mouse,
+        }
+    }
+}
+
+/// 各类输入是否允许触发锁定的开关
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TriggerSourceToggles {
+    pub keyboard: bool,
+    pub mouse_buttons: bool,
+    pub mouse_move: bool,
+    pub scroll: bool,
+}
+
+impl TriggerSourceToggles {
+    pub fn from_state(state: &AppState) -> Self {
+        Self {
+            keyboard: state.trigger_on_keyboard(),
+            mouse_buttons: state.trigger_on_mouse_buttons(),
+            mouse_move: state.trigger_on_mouse_move(),
+            scroll: state.trigger_on_scroll(),
+        }
+    }
+
+    pub fn apply_to_state(self, state: &AppState) {
+        state.set_trigger_on_keyboard(self.keyboard);
+        state.set_trigger_on_mouse_buttons(self.mouse_buttons);
+        state.set_trigger_on_mouse_move(self.mouse_move);
+        state.set_trigger_on_scroll(self.scroll);
+    }
+
+    /// 事件所属的输入类别是否允许触发
+    fn allows(self, event_type: &EventType) -> bool {
+        match event_type {
+            EventType::KeyPress(_) | EventType::KeyRelease(_) => self.keyboard,
+            EventType::ButtonPress(_) | EventType::ButtonRelease(_) => self.mouse_buttons,
+            EventType::MouseMove { .. } => self.mouse_move,
+            EventType::Wheel { .. } => self.scroll,
+        }
+    }
+}
+
+/// 触发流程中生成的文件
+#[derive(Debug, Clone, Default)]
+struct CapturedFiles {
+    photo_paths: Vec<String>,
+    video_path: Option<String>,
+    screenshot_path: Option<String>,
+}
+
+/// `lockdown_triggered` 事件负载，同时作为持久化的触发历史记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockdownTriggeredPayload {
+    pub trigger_source: TriggerSource,
+    pub timestamp: String,
+    pub photo_path: Option<String>,
+    /// 多摄像头拍摄时的全部照片（`photo_path` 为其中第一张）
+    #[serde(default)]
+    pub photo_paths: Vec<String>,
+    pub video_path: Option<String>,
+    /// 触发前后的预录缓冲视频
+    #[serde(default)]
+    pub prebuffer_video_path: Option<String>,
+    /// 触发时的桌面截图
+    #[serde(default)]
+    pub screenshot_path: Option<String>,
+    pub post_trigger_action: crate::config::PostTriggerAction,
+}
+
 fn emit_monitoring_status(app_handle: &AppHandle, status: &str) {
     if let Err(error) = app_handle.emit("monitoring_status_changed", status) {
         log::error!("无法发送监控状态事件 '{}': {}", status, error);
@@ -21,28 +117,92 @@ fn should_ignore_input_event(shortcut_in_progress: bool, within_shortcut_window:
     shortcut_in_progress || within_shortcut_window
 }
 
+/// 快捷键按键松开前忽略输入；未能跟踪到按键状态时退回固定的忽略窗口
+fn within_shortcut_window(
+    key_state: ShortcutKeyState,
+    elapsed_since_shortcut_ms: u64,
+    event_ignore_window_ms: u64,
+) -> bool {
+    match key_state {
+        ShortcutKeyState::Held => elapsed_since_shortcut_ms < SHORTCUT_KEY_RELEASE_TIMEOUT_MS,
+        ShortcutKeyState::Released => false,
+        ShortcutKeyState::Untracked => elapsed_since_shortcut_ms < event_ignore_window_ms,
+    }
+}
+
+/// 模拟触发不经过状态机，只要求流程代次未失效
 fn is_action_still_current(app_handle: &AppHandle, action_generation: u64) -> bool {
     let monitoring_flags = app_handle.state::<Arc<MonitoringFlags>>().inner().clone();
-    monitoring_flags.is_action_generation_current(action_generation)
-        && app_handle.state::<AppState>().status() == MonitoringState::Triggered
+    monitoring_flags.is_simulation_current(action_generation)
+        || (monitoring_flags.is_action_generation_current(action_generation)
+            && app_handle.state::<AppState>().status() == MonitoringState::Triggered)
 }
 
+/// 按顺序尝试的锁屏命令，第一个执行成功的生效
+#[cfg(target_os = "windows")]
+const LOCK_COMMANDS: &[(&str, &[&str])] = &[("rundll32.exe", &["user32.dll,LockWorkStation"])];
+/// CGSession 直接回到登录窗口；新版本系统移除了它时退回到关闭显示器
+/// （需在系统设置中开启“显示器关闭后立即要求密码”才会锁定）
+#[cfg(target_os = "macos")]
+const LOCK_COMMANDS: &[(&str, &[&str])] = &[
+    (
+        "/System/Library/CoreServices/Menu Extras/User.menu/Contents/Resources/CGSession",
+        &["-suspend"],
+    ),
+    ("pmset", &["displaysleepnow"]),
+];
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const LOCK_COMMANDS: &[(&str, &[&str])] = &[
+    ("loginctl", &["lock-session"]),
+    ("xdg-screensaver", &["lock"]),
+    ("gnome-screensaver-command", &["-l"]),
+];
+
+fn run_lock_command(program: &str, args: &[&str]) -> Result<(), String> {
+    let mut command = Command::new(program);
+    command.args(args);
+    crate::process_utils::configure_background_command(&mut command);
+    let mut child = command
+        .spawn()
+        .map_err(|error| format!("启动失败: {}", error))?;
+    log::debug!("锁屏命令已启动，进程ID: {:?}", child.id());
+    match child.wait() {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(format!("退出码: {:?}", status.code())),
+        Err(error) => Err(format!("等待命令完成时发生错误: {}", error)),
+    }
+}
+
+/// 依次尝试当前平台的锁屏方式；全部失败时只记录警告，不影响触发流程的其余步骤
 pub fn lock_screen() {
     log::info!("执行锁屏命令...");
-    match Command::new("rundll32.exe")
-        .args(["user32.dll,LockWorkStation"])
-        .spawn()
-    {
-        Ok(mut child) => {
-            log::info!("锁屏命令已启动，进程ID: {:?}", child.id());
-            match child.wait() {
-                Ok(status) if status.success() => log::info!("锁屏命令执行成功"),
-                Ok(status) => log::error!("锁屏命令执行失败，退出码: {:?}", status.code()),
-                Err(error) => log::error!("等待锁屏命令完成时发生错误: {}", error),
+    for (program, args) in LOCK_COMMANDS {
+        match run_lock_command(program, args) {
+            Ok(()) => {
+                log::info!("锁屏命令执行成功: {} {}", program, args.join(" "));
+                return;
             }
+            Err(error) => log::warn!("锁屏命令 {} 执行失败: {}", program, error),
         }
-        Err(error) => {
-            log::error!("启动锁屏命令失败: {}", error);
+    }
+    log::warn!("当前系统没有可用的锁屏方式，已跳过锁屏");
+}
+
+/// 按所选实现运行全局输入监听，阻塞直到监听器出错退出
+fn run_input_listener<F>(backend: MonitoringBackend, callback: F) -> Result<(), String>
+where
+    F: FnMut(Event) + 'static,
+{
+    match backend {
+        #[cfg(target_os = "windows")]
+        MonitoringBackend::WindowsHook => crate::low_level_hook::listen(callback),
+        #[cfg(not(target_os = "windows"))]
+        MonitoringBackend::WindowsHook => {
+            log::warn!("当前平台不支持 Windows 低级钩子，改用 rdev 监听");
+            listen(callback).map_err(|error| format!("rdev 事件监听器故障: {:?}", error))
+        }
+        MonitoringBackend::Rdev => {
+            listen(callback).map_err(|error| format!("rdev 事件监听器故障: {:?}", error))
         }
     }
 }
@@ -72,38 +232,50 @@ pub fn ensure_listener_started(
     monitoring_flags.set_listener_ready(false);
     monitoring_flags.clear_listener_error();
 
+    let backend = app_handle.state::<AppState>().monitoring_backend();
     let handle = std::thread::spawn(move || {
-        log::info!("启动常驻 rdev 事件监听器...");
+        log::info!("启动常驻输入监听器 ({:?})...", backend);
 
         let callback_handle = listener_app_handle.clone();
         let callback_flags = listener_flags.clone();
 
-        if let Err(error) = listen(move |event| {
+        if let Err(error_message) = run_input_listener(backend, move |event| {
             callback(event, &callback_handle, &callback_flags);
         }) {
-            let error_message = format!("rdev 事件监听器故障: {:?}", error);
             log::error!("{}", error_message);
             let _ = tx.send(error_message.clone());
+            let was_armed = matches!(
+                listener_app_handle.state::<AppState>().status(),
+                MonitoringState::Active | MonitoringState::Triggered
+            );
             listener_flags.set_listener_ready(false);
             listener_flags.set_listener_error(Some(error_message));
             listener_flags.stop_monitoring_thread();
-            crate::recorder::stop_screen_recording();
+            crate::prebuffer::stop();
+            crate::motion::stop();
 
-            if let Ok(runtime) = tokio::runtime::Runtime::new() {
-                runtime.block_on(async {
-                    if let Err(error) = camera::stop_video_recording().await {
-                        log::error!("监听器故障后停止摄像头录像失败: {}", error);
-                    }
-                });
-            }
+            // 复用应用共享的运行时，避免每次故障都新建一套工作线程
+            tauri::async_runtime::block_on(async {
+                if let Err(error) = crate::recorder::stop_screen_recording().await {
+                    log::error!("监听器故障后停止屏幕录制失败: {}", error);
+                }
+                if let Err(error) = crate::recorder::stop_webcam_recordings().await {
+                    log::error!("监听器故障后停止摄像头录像失败: {}", error);
+                }
+            });
 
             let state = listener_app_handle.state::<AppState>();
             if state.set_status(MonitoringState::Idle).is_ok() {
                 emit_monitoring_status(&listener_app_handle, "空闲");
             }
+
+            // 用户仍处于警戒意图中，交由看门狗重新拉起监听器
+            if was_armed {
+                listener_flags.set_recovery_pending(true);
+            }
         }
 
-        log::info!("rdev 事件监听器线程退出");
+        log::info!("输入监听器线程退出");
     });
 
     monitoring_flags.set_listener_handle(handle);
@@ -133,38 +305,198 @@ pub fn ensure_listener_started(
     }
 }
 
-pub fn start_idle_check_loop(
+/// 周期性检查输入监听器，警戒期间监听器失效时自动重新进入监控
+pub fn start_listener_watchdog(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut failed_attempts = 0;
+
+        loop {
+            sleep(LISTENER_WATCHDOG_INTERVAL).await;
+
+            let monitoring_flags = app_handle.state::<Arc<MonitoringFlags>>().inner().clone();
+            let listener_alive =
+                monitoring_flags.listener_ready() && monitoring_flags.is_listener_thread_alive();
+            let status = app_handle.state::<AppState>().status();
+            let armed_without_listener = status == MonitoringState::Active && !listener_alive;
+
+            if !monitoring_flags.take_recovery_pending() && !armed_without_listener {
+                failed_attempts = 0;
+                continue;
+            }
+
+            let reason = monitoring_flags
+                .listener_error()
+                .unwrap_or_else(|| "输入监听线程已退出".to_string());
+            log::warn!("检测到输入监听器失效（{}），尝试自动恢复监控", reason);
+
+            match crate::handlers::rearm_monitoring(&app_handle).await {
+                Ok(()) => {
+                    failed_attempts = 0;
+                    log::info!("✓ 输入监听器已恢复，重新进入警戒流程");
+                    if let Err(error) = app_handle.emit("monitoring_recovered", reason) {
+                        log::error!("无法发送监控恢复事件: {}", error);
+                    }
+                }
+                Err(error) => {
+                    failed_attempts += 1;
+                    log::error!(
+                        "自动恢复监控失败 ({}/{}): {}",
+                        failed_attempts,
+                        LISTENER_RECOVERY_MAX_ATTEMPTS,
+                        error
+                    );
+                    if failed_attempts < LISTENER_RECOVERY_MAX_ATTEMPTS {
+                        monitoring_flags.set_recovery_pending(true);
+                    } else {
+                        failed_attempts = 0;
+                        crate::notifications::show(
+                            &app_handle,
+                            &crate::i18n::tr_with(
+                                &app_handle,
+                                crate::i18n::Message::ListenerRecoveryFailed,
+                                &[("error", &error)],
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// 屏幕录制模式下持续录制的 ffmpeg 意外退出（如编码器崩溃、磁盘异常）时自动重启录制。
+/// 仅在警戒期间运行；连续重启按指数退避，达到次数上限后放弃，避免反复拉起注定失败的进程。
+pub fn start_ffmpeg_watchdog(
     app_handle: AppHandle,
     monitoring_flags: Arc<MonitoringFlags>,
 ) -> task::JoinHandle<()> {
-    log::info!("启动空闲检测循环...");
     tokio::spawn(async move {
-        loop {
-            sleep(Duration::from_secs(2)).await;
+        // 警戒前遗留的退出标记与本次警戒无关
+        crate::recorder::take_screen_recording_unexpected_exit();
+        let mut restart_attempts = 0;
+        let mut last_restart_at: Option<Instant> = None;
+        let mut restart_pending = false;
 
+        loop {
+            sleep(FFMPEG_WATCHDOG_INTERVAL).await;
             if !monitoring_flags.monitoring_active() {
-                log::debug!("监控非激活状态，空闲检测循环终止");
+                log::debug!("监控非激活状态，ffmpeg 看门狗终止");
                 break;
             }
 
-            let last_activity = monitoring_flags.last_activity_time();
-            if last_activity == 0 {
+            if !crate::recorder::take_screen_recording_unexpected_exit() && !restart_pending {
                 continue;
             }
+            restart_pending = false;
+
+            let state = app_handle.state::<AppState>();
+            let current_time = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64;
+            let user_active = idle_pause_remaining(
+                monitoring_flags.last_activity_time(),
+                current_time,
+                state.recording_idle_timeout_secs(),
+            )
+            .is_some();
+
+            if state.status() != MonitoringState::Active
+                || !state.idle_managed_recording()
+                || !user_active
+                || crate::recorder::is_screen_recording_running()
+            {
+                log::warn!("屏幕录制 ffmpeg 意外退出，当前无需恢复录制");
+                continue;
+            }
+
+            if last_restart_at.is_some_and(|at| at.elapsed() >= FFMPEG_RESTART_STABLE_PERIOD) {
+                restart_attempts = 0;
+            }
+            if restart_attempts >= FFMPEG_RESTART_MAX_ATTEMPTS {
+                log::error!(
+                    "屏幕录制 ffmpeg 已连续重启 {} 次仍然退出，停止自动恢复",
+                    restart_attempts
+                );
+                break;
+            }
+
+            let delay = ffmpeg_restart_delay(restart_attempts);
+            restart_attempts += 1;
+            log::warn!(
+                "屏幕录制 ffmpeg 意外退出，{} 秒后重新启动 ({}/{})",
+                delay.as_secs(),
+                restart_attempts,
+                FFMPEG_RESTART_MAX_ATTEMPTS
+            );
+            sleep(delay).await;
+            if !monitoring_flags.monitoring_active() {
+                break;
+            }
+
+            last_restart_at = Some(Instant::now());
+            match crate::recorder::start_screen_recording_with_options(app_handle.clone(), false)
+                .await
+            {
+                Ok(()) => log::info!("屏幕录制已自动恢复"),
+                Err(error) => {
+                    log::error!("自动恢复屏幕录制失败: {}", error);
+                    restart_pending = true;
+                }
+            }
+        }
+    })
+}
+
+/// 第 `attempt` 次重启前的等待时间（从 0 开始计数）
+fn ffmpeg_restart_delay(attempt: u32) -> Duration {
+    FFMPEG_RESTART_BASE_DELAY
+        .saturating_mul(1 << attempt.min(16))
+        .min(FFMPEG_RESTART_MAX_DELAY)
+}
+
+/// 屏幕录制模式下的空闲计时器：有活动时睡到暂停截止时间，空闲后等待下一次输入唤醒，不再定时轮询
+pub fn start_idle_check_loop(
+    app_handle: AppHandle,
+    monitoring_flags: Arc<MonitoringFlags>,
+) -> task::JoinHandle<()> {
+    let timeout_secs = app_handle.state::<AppState>().recording_idle_timeout_secs();
+    if timeout_secs == 0 {
+        log::info!("启动空闲检测计时器，录制不会因无操作而暂停");
+    } else {
+        log::info!("启动空闲检测计时器，无操作 {} 秒后暂停录制", timeout_secs);
+    }
+    tokio::spawn(async move {
+        loop {
+            if !monitoring_flags.monitoring_active() {
+                log::debug!("监控非激活状态，空闲检测计时器终止");
+                break;
+            }
 
+            let last_activity = monitoring_flags.last_activity_time();
             let current_time = SystemTime::now()
                 .duration_since(UNIX_EPOCH)
                 .unwrap_or_default()
                 .as_millis() as u64;
-            let idle_time_ms = current_time.saturating_sub(last_activity);
-            let is_recording = crate::recorder::is_screen_recording_running();
 
-            if idle_time_ms > 20_000 && is_recording {
-                log::info!("超过20秒无操作，暂停屏幕录制...");
-                crate::recorder::stop_screen_recording();
-            } else if idle_time_ms <= 20_000 && !is_recording {
+            // 每轮重新读取设置，修改后无需重新警戒即可生效
+            let timeout_secs = app_handle.state::<AppState>().recording_idle_timeout_secs();
+            let Some(remaining) = idle_pause_remaining(last_activity, current_time, timeout_secs)
+            else {
+                if last_activity != 0 && crate::recorder::is_screen_recording_running() {
+                    log::info!("超过 {} 秒无操作，暂停屏幕录制...", timeout_secs);
+                    if let Err(error) = crate::recorder::stop_screen_recording().await {
+                        log::error!("暂停屏幕录制失败: {}", error);
+                    }
+                }
+                monitoring_flags.activity_recorded().await;
+                continue;
+            };
+
+            if !crate::recorder::is_screen_recording_running() {
                 if let Some(remaining_ms) = crate::recorder::screen_recording_retry_remaining_ms() {
                     log::debug!("屏幕录制处于冷却中，剩余 {} ms", remaining_ms);
+                    sleep(Duration::from_millis(remaining_ms).min(remaining)).await;
                     continue;
                 }
 
@@ -181,11 +513,57 @@ pub fn start_idle_check_loop(
                     }
                 });
             }
+
+            // 期间的新活动只会推迟截止时间，醒来后重新计算即可
+            sleep(remaining).await;
         }
     })
 }
 
+/// 距离因无操作而暂停录制还剩多久；尚无活动记录或已超时时返回 None。
+/// `timeout_secs` 为 0 时录制始终保持，只按固定间隔重新检查
+fn idle_pause_remaining(
+    last_activity: u64,
+    current_time: u64,
+    timeout_secs: u32,
+) -> Option<Duration> {
+    if timeout_secs == 0 {
+        return Some(RECORDING_ALWAYS_ON_RECHECK_INTERVAL);
+    }
+    if last_activity == 0 {
+        return None;
+    }
+    let deadline = last_activity + u64::from(timeout_secs) * 1_000;
+    // 与原先的判断保持一致：恰好到达超时时长时仍视为活跃
+    (current_time <= deadline).then(|| Duration::from_millis(deadline - current_time + 1))
+}
+
 fn callback(event: Event, app_handle: &AppHandle, monitoring_flags: &Arc<MonitoringFlags>) {
+    // 始终跟踪按键状态；松开快捷键最后一个按键的事件本身也应忽略
+    if monitoring_flags.track_key_event(&event.event_type) {
+        log::debug!("快捷键按键已全部松开，结束输入忽略");
+        return;
+    }
+
+    // 按设备过滤时改由 Raw Input 转发指定设备的输入
+    if !monitoring_flags.monitoring_active() || crate::input_devices::filter_active(app_handle) {
+        return;
+    }
+
+    process_input_event(event.event_type, app_handle, monitoring_flags);
+}
+
+/// 处理 Raw Input 转发的、来自所监控设备的输入
+pub fn handle_device_input(app_handle: &AppHandle, event_type: EventType) {
+    let monitoring_flags = app_handle.state::<Arc<MonitoringFlags>>().inner().clone();
+    process_input_event(event_type, app_handle, &monitoring_flags);
+}
+
+fn process_input_event(
+    event_type: EventType,
+    app_handle: &AppHandle,
+    monitoring_flags: &Arc<MonitoringFlags>,
+) {
     if !monitoring_flags.monitoring_active() {
         return;
     }
@@ -195,8 +573,12 @@ fn callback(event: Event, app_handle: &AppHandle, monitoring_flags: &Arc<Monitor
         .unwrap_or_default()
         .as_millis() as u64;
 
-    let within_shortcut_window =
-        current_time.saturating_sub(monitoring_flags.last_shortcut_time()) < EVENT_IGNORE_WINDOW_MS;
+    let state = app_handle.state::<AppState>();
+    let within_shortcut_window = within_shortcut_window(
+        monitoring_flags.shortcut_key_state(),
+        current_time.saturating_sub(monitoring_flags.last_shortcut_time()),
+        state.event_ignore_window_ms(),
+    );
 
     if should_ignore_input_event(
         monitoring_flags.shortcut_in_progress(),
@@ -205,36 +587,232 @@ fn callback(event: Event, app_handle: &AppHandle, monitoring_flags: &Arc<Monitor
         return;
     }
 
+    if matches!(
+        event_type,
+        EventType::KeyPress(_) | EventType::KeyRelease(_)
+    ) && crate::key_filter::is_ignored_key_event(&event_type, &state.ignored_keys())
+    {
+        log::debug!("忽略白名单按键事件: {:?}", event_type);
+        return;
+    }
+
     monitoring_flags.set_last_activity_time(current_time);
 
-    let state = app_handle.state::<AppState>();
-    if state.post_trigger_action() == crate::config::PostTriggerAction::ScreenRecording {
+    // 无活动触发模式下，输入只用于重置计时
+    if state.trigger_mode() == crate::config::TriggerMode::Idle {
+        return;
+    }
+
+    // 被关闭的输入类别只记录活动，不触发也不视为录屏模式下的真实活动
+    if !TriggerSourceToggles::from_state(&state).allows(&event_type) {
+        log::debug!("该类输入未启用触发，忽略本次事件: {:?}", event_type);
+        return;
+    }
+
+    // 刚进入警戒时的输入多来自用户自己的警戒操作，只记录活动不触发
+    if monitoring_flags.is_within_arm_settle(current_time, state.arm_settle_ms()) {
+        log::debug!("处于警戒稳定期内，忽略本次事件");
+        return;
+    }
+
+    if state.idle_managed_recording() {
         log::debug!("屏幕录制模式下检测到真实活动");
         trigger_screen_recording_activity(app_handle.clone());
         return;
     }
 
-    log::info!("✓ 触发锁定！事件类型: {:?}", event.event_type);
+    if monitoring_flags.is_within_retrigger_cooldown(current_time, state.retrigger_cooldown_ms()) {
+        log::debug!("处于重复触发冷却期内，忽略本次事件");
+        return;
+    }
+
+    log::info!("✓ 触发锁定！事件类型: {:?}", event_type);
+    let trigger_source = TriggerSource::from_event_type(&event_type);
 
+    let Some(action_generation) = enter_triggered_state(&state, monitoring_flags, current_time)
+    else {
+        return;
+    };
+
+    // 与合盖、画面变化触发一致，在应用共享的运行时上执行，不为每次触发新建运行时
+    tauri::async_runtime::spawn(trigger_lockdown(
+        app_handle.clone(),
+        action_generation,
+        trigger_source,
+        false,
+    ));
+}
+
+/// 切换到 Triggered 状态并停止继续响应输入，返回本次触发流程的动作代号
+fn enter_triggered_state(
+    state: &AppState,
+    monitoring_flags: &MonitoringFlags,
+    current_time: u64,
+) -> Option<u64> {
     if state.set_status(MonitoringState::Triggered).is_err() {
         log::warn!("状态转换到 Triggered 失败，忽略本次事件");
-        return;
+        return None;
     }
 
+    monitoring_flags.set_last_trigger_time(current_time);
     let action_generation = monitoring_flags.current_action_generation();
     monitoring_flags.set_monitoring_active(false);
+    Some(action_generation)
+}
 
-    let app_handle_clone = app_handle.clone();
-    std::thread::spawn(move || match tokio::runtime::Runtime::new() {
-        Ok(runtime) => {
-            runtime.block_on(async move {
-                trigger_lockdown(app_handle_clone, action_generation).await;
-            });
-        }
-        Err(error) => {
-            log::error!("创建内部运行时失败: {}", error);
+fn idle_threshold_exceeded(last_activity: u64, current_time: u64, idle_minutes: u32) -> bool {
+    let threshold_ms = u64::from(idle_minutes) * 60_000;
+    current_time.saturating_sub(last_activity) >= threshold_ms
+}
+
+/// 无活动触发模式：超过设定时间没有任何输入时执行触发流程
+pub fn start_idle_trigger_loop(
+    app_handle: AppHandle,
+    monitoring_flags: Arc<MonitoringFlags>,
+) -> task::JoinHandle<()> {
+    log::info!("启动无活动触发检测循环...");
+    tokio::spawn(async move {
+        loop {
+            sleep(IDLE_TRIGGER_CHECK_INTERVAL).await;
+
+            if !monitoring_flags.monitoring_active() {
+                log::debug!("监控非激活状态，无活动触发检测循环终止");
+                break;
+            }
+
+            let current_time = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64;
+            let state = app_handle.state::<AppState>();
+            let idle_minutes = state.idle_trigger_minutes();
+            if !idle_threshold_exceeded(
+                monitoring_flags.last_activity_time(),
+                current_time,
+                idle_minutes,
+            ) {
+                continue;
+            }
+
+            log::info!("✓ 超过 {} 分钟无活动，触发锁定！", idle_minutes);
+            let Some(action_generation) =
+                enter_triggered_state(&state, &monitoring_flags, current_time)
+            else {
+                continue;
+            };
+
+            tokio::spawn(trigger_lockdown(
+                app_handle.clone(),
+                action_generation,
+                TriggerSource::Idle,
+                false,
+            ));
+            break;
         }
-    });
+    })
+}
+
+/// 合盖或即将睡眠时触发：仅在警戒状态且启用了对应设置时执行，
+/// 返回触发流程的任务句柄，便于调用方在系统挂起前等待拍摄完成
+pub fn trigger_on_lid_close(
+    app_handle: &AppHandle,
+) -> Option<tauri::async_runtime::JoinHandle<()>> {
+    let state = app_handle.state::<AppState>();
+    if !state.trigger_on_lid_close() || state.status() != MonitoringState::Active {
+        return None;
+    }
+
+    let monitoring_flags = app_handle.state::<Arc<MonitoringFlags>>().inner().clone();
+    let current_time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+
+    log::info!("✓ 检测到合盖/睡眠，触发锁定！");
+    let action_generation = enter_triggered_state(&state, &monitoring_flags, current_time)?;
+    Some(tauri::async_runtime::spawn(trigger_lockdown(
+        app_handle.clone(),
+        action_generation,
+        TriggerSource::LidClose,
+        false,
+    )))
+}
+
+/// 画面变化检测发现变化时触发：与输入触发一样遵守警戒稳定期与重复触发冷却，
+/// 不满足条件时返回 `None`，由检测线程继续检测
+pub fn trigger_on_motion(
+    app_handle: &AppHandle,
+    score: f32,
+) -> Option<tauri::async_runtime::JoinHandle<()>> {
+    let state = app_handle.state::<AppState>();
+    if state.status() != MonitoringState::Active {
+        return None;
+    }
+
+    let monitoring_flags = app_handle.state::<Arc<MonitoringFlags>>().inner().clone();
+    let current_time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    if monitoring_flags.is_within_arm_settle(current_time, state.arm_settle_ms())
+        || monitoring_flags
+            .is_within_retrigger_cooldown(current_time, state.retrigger_cooldown_ms())
+    {
+        return None;
+    }
+
+    log::info!("✓ 检测到画面变化 {:.1}%，触发锁定！", score);
+    let action_generation = enter_triggered_state(&state, &monitoring_flags, current_time)?;
+    Some(tauri::async_runtime::spawn(trigger_lockdown(
+        app_handle.clone(),
+        action_generation,
+        TriggerSource::Motion,
+        false,
+    )))
+}
+
+/// 紧急快捷键：不经过触发判定与拍摄延迟，立即拍照并锁屏
+pub async fn panic_capture_and_lock(app_handle: &AppHandle) {
+    let (camera_id, capture_camera_ids) = {
+        let state = app_handle.state::<AppState>();
+        (state.camera_id(), state.capture_camera_ids())
+    };
+    let photo_camera_ids = if capture_camera_ids.is_empty() {
+        vec![camera_id]
+    } else {
+        capture_camera_ids
+    };
+
+    if camera::has_cameras() {
+        crate::preview::stop_async().await;
+        let save_path = crate::storage::resolve_capture_save_path(app_handle);
+        let photo_options = camera::PhotoOptions {
+            capture_delay_seconds: 0,
+            burst_count: 1,
+            ..camera::PhotoOptions::from_state(&app_handle.state::<AppState>())
+        };
+        let photo_paths =
+            capture_photos(app_handle, &photo_camera_ids, save_path, photo_options).await;
+        log::info!("紧急拍照完成，共 {} 张", photo_paths.len());
+    } else {
+        log::warn!("未检测到摄像头，紧急快捷键仅执行锁屏");
+    }
+
+    lock_screen();
+}
+
+/// 完整执行一次触发流程（拍摄、通知、录制），但不锁屏、不退出、不改变监控状态
+pub async fn run_simulated_trigger(app_handle: AppHandle, action_generation: u64) {
+    trigger_lockdown(
+        app_handle.clone(),
+        action_generation,
+        TriggerSource::Simulated,
+        true,
+    )
+    .await;
+    app_handle
+        .state::<Arc<MonitoringFlags>>()
+        .end_simulation(action_generation);
 }
 
 fn trigger_screen_recording_activity(app_handle: AppHandle) {
@@ -256,15 +834,28 @@ fn trigger_screen_recording_activity(app_handle: AppHandle) {
     });
 }
 
-async fn trigger_lockdown(app_handle: AppHandle, action_generation: u64) {
-    log::info!("=== 开始执行锁定流程 ===");
+/// `simulate` 为 true 时锁屏与退出只记录日志，并以 `simulated_trigger` 事件代替 `lockdown_triggered`
+async fn trigger_lockdown(
+    app_handle: AppHandle,
+    action_generation: u64,
+    trigger_source: TriggerSource,
+    simulate: bool,
+) {
+    if simulate {
+        log::info!("=== 开始执行模拟触发流程 ===");
+    } else {
+        log::info!("=== 开始执行锁定流程 ===");
+    }
+    let triggered_at = Local::now();
 
     if !is_action_still_current(&app_handle, action_generation) {
         log::info!("锁定流程已失效，取消执行");
         return;
     }
 
-    emit_monitoring_status(&app_handle, "锁定中");
+    if !simulate {
+        emit_monitoring_status(&app_handle, "锁定中");
+    }
 
     let (
         camera_id,
@@ -274,23 +865,33 @@ async fn trigger_lockdown(app_handle: AppHandle, action_generation: u64) {
         notifications_enabled,
         capture_delay_seconds,
         capture_mode,
+        capture_camera_ids,
+        camera_recording_seconds,
     ) = {
         let state = app_handle.state::<AppState>();
         (
             state.camera_id(),
-            state.save_path(),
+            crate::storage::resolve_capture_save_path(&app_handle),
             state.exit_on_lock(),
             state.post_trigger_action(),
             state.enable_notifications(),
             state.capture_delay_seconds(),
             state.capture_mode(),
+            state.capture_camera_ids(),
+            state.camera_recording_seconds(),
         )
     };
+    let photo_camera_ids = if capture_camera_ids.is_empty() {
+        vec![camera_id]
+    } else {
+        capture_camera_ids
+    };
 
-    let screen_lock_enabled = matches!(
-        post_trigger_action,
-        crate::config::PostTriggerAction::CaptureAndLock
-    );
+    let screen_lock_enabled = post_trigger_action.locks_screen();
+    // 先锁屏可缩短入侵者能操作的时间，拍摄与锁屏同时进行
+    let lock_first = screen_lock_enabled
+        && post_trigger_action == crate::config::PostTriggerAction::CaptureAndLock
+        && app_handle.state::<AppState>().lock_first();
 
     log::info!(
         "监控触发，使用摄像头ID: {}, 触发后动作: {:?}, 通知功能: {}, 锁定时退出: {}, 拍摄延迟: {}秒, 拍摄模式: {:?}",
@@ -302,116 +903,263 @@ async fn trigger_lockdown(app_handle: AppHandle, action_generation: u64) {
         capture_mode
     );
 
-    if capture_delay_seconds > 0 {
-        if !await_delayed_capture(
+    let mut captured_files = CapturedFiles::default();
+
+    // 锁屏后只能截到安全桌面，截图需在任何锁屏动作之前完成
+    let capture_screenshot = app_handle
+        .state::<AppState>()
+        .capture_screenshot_on_trigger();
+    if capture_screenshot {
+        match crate::recorder::capture_screenshot(app_handle.clone(), save_path.clone()).await {
+            Ok(path) => captured_files.screenshot_path = Some(path),
+            Err(error) => log::error!("触发时桌面截图失败: {}", error),
+        }
+    }
+
+    if lock_first {
+        if simulate {
+            log::info!("模拟触发：跳过先行锁屏");
+        } else {
+            log::info!("先锁屏，拍摄同时进行");
+            tokio::task::spawn_blocking(lock_screen);
+        }
+    }
+
+    // 先结束预录缓冲以释放摄像头，再执行正常拍摄；触发后的画面在锁屏后于后台补录
+    let prebuffer_capture = crate::prebuffer::take_on_trigger(&app_handle, save_path.clone()).await;
+    let prebuffer_video_path = prebuffer_capture
+        .as_ref()
+        .map(crate::prebuffer::TriggerCapture::output_path);
+
+    // 屏幕录制动作及无摄像头时只走拍照/录屏流程，拍摄模式不生效；
+    // 摄像头录像动作总是录像，是否先拍照仍由拍摄模式决定
+    let record_video = !post_trigger_action.records_screen()
+        && camera::has_cameras()
+        && (capture_mode.records_video() || post_trigger_action.records_camera());
+    let take_photo = !record_video || capture_mode.takes_photo();
+
+    if take_photo
+        && !execute_capture_and_lock(
             app_handle.clone(),
-            camera_id,
+            &photo_camera_ids,
             save_path.clone(),
-            capture_delay_seconds,
-            capture_mode,
+            post_trigger_action.clone(),
             action_generation,
+            simulate,
+            &mut captured_files,
         )
         .await
-        {
-            return;
+    {
+        if let Some(capture) = prebuffer_capture {
+            capture.finish_in_background();
         }
-    } else if !execute_capture_and_lock(
-        app_handle.clone(),
-        camera_id,
-        save_path.clone(),
-        post_trigger_action.clone(),
-        action_generation,
-    )
-    .await
+        return;
+    }
+
+    if record_video
+        && !record_trigger_video(
+            app_handle.clone(),
+            camera_id,
+            save_path.clone(),
+            camera_recording_seconds,
+            action_generation,
+            &mut captured_files,
+        )
+        .await
     {
+        if let Some(capture) = prebuffer_capture {
+            capture.finish_in_background();
+        }
         return;
     }
 
+    let trigger_payload = LockdownTriggeredPayload {
+        trigger_source,
+        timestamp: triggered_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        photo_path: captured_files.photo_paths.first().cloned(),
+        photo_paths: captured_files.photo_paths.clone(),
+        video_path: captured_files.video_path.clone(),
+        prebuffer_video_path,
+        screenshot_path: captured_files.screenshot_path.clone(),
+        post_trigger_action: post_trigger_action.clone(),
+    };
+    if let Some(url) = app_handle.state::<AppState>().webhook_url() {
+        let payload = crate::webhook::WebhookPayload {
+            timestamp: trigger_payload.timestamp.clone(),
+            camera_id,
+            action: post_trigger_action.clone(),
+            photo_path: trigger_payload.photo_path.clone(),
+            hostname: crate::notifications::system_hostname(),
+            trigger_count: 1,
+            test: simulate,
+        };
+        if simulate {
+            // 模拟触发用于检查配置，总是立即发送且不占用告警间隔
+            crate::webhook::send(url, payload);
+        } else {
+            crate::webhook::send_throttled(&app_handle, url, payload);
+        }
+    }
+    let email_alert = crate::email::EmailAlert {
+        timestamp: trigger_payload.timestamp.clone(),
+        photo_path: trigger_payload.photo_path.clone(),
+        trigger_count: 1,
+        test: simulate,
+    };
+    let smtp = app_handle.state::<AppState>().smtp();
+    if simulate {
+        crate::email::send_alert(smtp, email_alert);
+        if let Err(error) = app_handle.emit("simulated_trigger", trigger_payload) {
+            log::error!("无法发送模拟触发事件: {}", error);
+        }
+    } else {
+        crate::email::send_alert_throttled(&app_handle, smtp, email_alert);
+        crate::event_history::append_event(
+            trigger_payload.clone(),
+            app_handle.state::<AppState>().event_history_limit(),
+        );
+        emit_lockdown_triggered(&app_handle, trigger_payload);
+    }
+
     if notifications_enabled && is_action_still_current(&app_handle, action_generation) {
-        send_security_notification(&app_handle);
+        send_security_notification(&app_handle, captured_files.photo_paths.first());
     }
 
-    if screen_lock_enabled && is_action_still_current(&app_handle, action_generation) {
-        lock_screen();
-        sleep(Duration::from_millis(1_000)).await;
+    if screen_lock_enabled
+        && post_trigger_action == crate::config::PostTriggerAction::RecordAndLock
+        && captured_files.video_path.is_some()
+    {
+        // 锁屏后安全桌面无法被 gdigrab 采集，先保证录到足够时长
+        let seconds = app_handle.state::<AppState>().record_before_lock_seconds();
+        log::info!("锁屏前先录制屏幕 {} 秒", seconds);
+        sleep(Duration::from_secs(seconds.into())).await;
+    }
+
+    if screen_lock_enabled && !lock_first && is_action_still_current(&app_handle, action_generation)
+    {
+        if simulate {
+            log::info!("模拟触发：跳过锁屏");
+        } else {
+            lock_screen();
+            sleep(Duration::from_millis(1_000)).await;
+        }
+    }
+    if let Some(capture) = prebuffer_capture {
+        capture.finish_in_background();
+    }
+
+    let fixed_duration_recording = post_trigger_action
+        == crate::config::PostTriggerAction::ScreenRecording
+        && captured_files.video_path.is_some();
+    if fixed_duration_recording {
+        log::info!("等待固定时长屏幕录制完成...");
+        crate::recorder::wait_for_screen_recording().await;
+        log::info!("固定时长屏幕录制已完成");
+    }
+
+    if simulate {
+        if exit_on_lock_enabled {
+            log::info!("模拟触发：跳过锁定时退出程序");
+        }
+        log::info!("=== 模拟触发流程执行完成 ===");
+        return;
     }
 
     if exit_on_lock_enabled && is_action_still_current(&app_handle, action_generation) {
-        crate::recorder::stop_screen_recording();
-        if let Err(error) = camera::stop_video_recording().await {
-            log::error!("退出前停止摄像头录像失败: {}", error);
+        let shutdown_handle = app_handle.clone();
+        if let Err(error) = tokio::task::spawn_blocking(move || {
+            crate::app_setup::shutdown(&shutdown_handle);
+        })
+        .await
+        {
+            log::error!("退出前停止后台任务失败: {}", error);
         }
         std::process::exit(0);
     }
 
-    if post_trigger_action == crate::config::PostTriggerAction::CaptureOnly
+    let rearm_after_trigger = app_handle.state::<AppState>().rearm_after_trigger();
+    if rearm_after_trigger && is_action_still_current(&app_handle, action_generation) {
+        log::info!("已启用触发后重新警戒，准备重新进入监控");
+        schedule_rearm(app_handle.clone());
+    } else if (post_trigger_action == crate::config::PostTriggerAction::CaptureOnly
+        || post_trigger_action.records_camera()
+        || fixed_duration_recording)
         && is_action_still_current(&app_handle, action_generation)
     {
         let state = app_handle.state::<AppState>();
         if state.set_status(MonitoringState::Idle).is_ok() {
             emit_monitoring_status(&app_handle, "空闲");
         }
+        crate::handlers::persist_armed_state(&app_handle, false);
     }
 
     log::info!("=== 锁定流程执行完成 ===");
 }
 
-fn send_security_notification(app_handle: &AppHandle) {
-    use tauri_plugin_notification::NotificationExt;
+/// 在应用主运行时上重新进入警戒流程（触发流程所在的临时运行时结束后任务仍需存活）
+pub fn schedule_rearm(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(error) = crate::handlers::rearm_monitoring(&app_handle).await {
+            log::error!("重新进入警戒状态失败: {}", error);
+        }
+    });
+}
 
-    let state = app_handle.state::<AppState>();
-    if !state.enable_notifications() {
-        return;
+fn emit_lockdown_triggered(app_handle: &AppHandle, payload: LockdownTriggeredPayload) {
+    if let Err(error) = app_handle.emit("lockdown_triggered", payload) {
+        log::error!("无法发送锁定触发事件: {}", error);
     }
+}
 
-    match app_handle
-        .notification()
-        .builder()
-        .title("SnapLock 安全警报")
-        .body("检测到未授权访问")
-        .icon("📷")
-        .show()
-    {
-        Ok(_) => log::info!("安全通知发送成功"),
-        Err(error) => log::error!("发送安全通知失败: {}", error),
-    }
+fn send_security_notification(app_handle: &AppHandle, photo_path: Option<&String>) {
+    crate::notifications::show_security_alert(app_handle, photo_path.map(std::path::PathBuf::from));
 }
 
-async fn await_delayed_capture(
+/// 使用摄像头录制指定时长的视频
+async fn record_trigger_video(
     app_handle: AppHandle,
     camera_id: u32,
     save_path: Option<String>,
-    delay_seconds: u32,
-    capture_mode: crate::config::CaptureMode,
+    duration_seconds: u32,
     action_generation: u64,
+    captured_files: &mut CapturedFiles,
 ) -> bool {
-    log::info!(
-        "开始延迟拍摄，模式: {:?}, 延迟: {}秒",
-        capture_mode,
-        delay_seconds
-    );
+    log::info!("开始摄像头录像，时长: {}秒", duration_seconds);
 
     if !is_action_still_current(&app_handle, action_generation) {
-        log::info!("延迟拍摄前流程已取消");
+        log::info!("录像前流程已取消");
         return false;
     }
 
-    if let Err(error) =
-        camera::start_video_recording(app_handle.clone(), camera_id, save_path, Some(delay_seconds))
-            .await
+    match crate::recorder::start_webcam_recording(
+        app_handle.clone(),
+        camera_id,
+        save_path,
+        Some(duration_seconds),
+    )
+    .await
     {
-        log::error!("启动录像失败: {}", error);
-        return is_action_still_current(&app_handle, action_generation);
+        Ok(video_path) => captured_files.video_path = Some(video_path),
+        Err(error) => {
+            log::error!("启动录像失败: {}", error);
+            return is_action_still_current(&app_handle, action_generation);
+        }
     }
 
-    sleep(Duration::from_secs((delay_seconds + 2).into())).await;
+    // 录像期间每秒发送剩余秒数，0 表示倒计时结束
+    for remaining_seconds in (1..=duration_seconds).rev() {
+        camera::emit_capture_countdown(&app_handle, remaining_seconds);
+        sleep(Duration::from_secs(1)).await;
+    }
+    camera::emit_capture_countdown(&app_handle, 0);
+    sleep(Duration::from_secs(2)).await;
 
-    if let Err(error) = camera::stop_video_recording().await {
+    if let Err(error) = crate::recorder::stop_webcam_recordings().await {
         log::error!("清理录像进程失败: {}", error);
     }
 
     if !is_action_still_current(&app_handle, action_generation) {
-        log::info!("延迟拍摄完成后流程已取消");
+        log::info!("录像完成后流程已取消");
         return false;
     }
 
@@ -419,35 +1167,235 @@ async fn await_delayed_capture(
     is_action_still_current(&app_handle, action_generation)
 }
 
+/// 启动触发后的屏幕录制，录制失败只记录日志；固定时长录制返回录像路径
+async fn start_trigger_screen_recording(
+    app_handle: AppHandle,
+    duration_seconds: u32,
+    capture_photo: bool,
+) -> Option<String> {
+    if duration_seconds > 0 {
+        match crate::recorder::start_fixed_duration_recording(
+            app_handle,
+            duration_seconds,
+            capture_photo,
+        )
+        .await
+        {
+            Ok(video_path) => return Some(video_path),
+            Err(error) => log::error!("启动固定时长屏幕录制失败: {}", error),
+        }
+    } else if let Err(error) =
+        crate::recorder::start_screen_recording_with_options(app_handle, capture_photo).await
+    {
+        log::error!("启动屏幕录制失败: {}", error);
+    }
+    None
+}
+
+/// 录屏类动作的拍照安排，返回（是否与录制并发拍摄所有摄像头，录制流程是否自行拍照）。
+/// 同时拍照时由触发流程并发拍摄，录制流程不再单独拍照，避免重复占用摄像头
+fn screen_recording_photo_plan(photo_with_recording: bool, has_cameras: bool) -> (bool, bool) {
+    let pair_photos = photo_with_recording && has_cameras;
+    (pair_photos, !pair_photos)
+}
+
+/// 使用所有选定摄像头拍照，单个摄像头失败不影响其他摄像头及后续锁屏与通知
+async fn capture_photos(
+    app_handle: &AppHandle,
+    camera_ids: &[u32],
+    save_path: Option<String>,
+    photo_options: camera::PhotoOptions,
+) -> Vec<String> {
+    let mut photo_paths = Vec::new();
+    for (camera_id, result) in
+        camera::take_photos(app_handle, camera_ids, save_path, photo_options).await
+    {
+        match result {
+            Ok(paths) => photo_paths.extend(paths),
+            Err(camera::CameraError::NotFound) => {
+                log::warn!("未检测到摄像头，跳过拍照");
+                break;
+            }
+            Err(error @ camera::CameraError::Busy(_)) => {
+                // 拍照失败不影响后续锁屏与通知
+                log::error!(
+                    "摄像头 {} 被占用，本次仅执行锁屏/通知: {}",
+                    camera_id,
+                    error
+                );
+                if let Err(emit_error) = app_handle.emit("camera_busy", camera_id) {
+                    log::error!("无法发送摄像头占用事件: {}", emit_error);
+                }
+            }
+            Err(error @ camera::CameraError::TimedOut(_)) => {
+                log::warn!(
+                    "摄像头 {} 拍照超时，跳过照片继续锁屏/通知: {}",
+                    camera_id,
+                    error
+                );
+            }
+            Err(error) => log::error!("摄像头 {} 拍照失败: {}", camera_id, error),
+        }
+    }
+    photo_paths
+}
+
 async fn execute_capture_and_lock(
     app_handle: AppHandle,
-    camera_id: u32,
+    camera_ids: &[u32],
     save_path: Option<String>,
     post_trigger_action: crate::config::PostTriggerAction,
     action_generation: u64,
+    simulate: bool,
+    captured_files: &mut CapturedFiles,
 ) -> bool {
     if !is_action_still_current(&app_handle, action_generation) {
         log::info!("执行触发动作前流程已取消");
         return false;
     }
 
-    if post_trigger_action == crate::config::PostTriggerAction::ScreenRecording {
-        if let Err(error) = crate::recorder::start_screen_recording(app_handle).await {
-            log::error!("启动屏幕录制失败: {}", error);
+    if post_trigger_action.records_screen() {
+        let (recording_duration, record_before_lock) = {
+            let state = app_handle.state::<AppState>();
+            (
+                state.recording_duration_seconds(),
+                state.record_before_lock_seconds(),
+            )
+        };
+        // 录屏并锁屏时录制时长至少覆盖锁屏前的等待时间
+        let duration_seconds =
+            if post_trigger_action == crate::config::PostTriggerAction::RecordAndLock {
+                recording_duration.max(record_before_lock)
+            } else {
+                recording_duration
+            };
+        // 模拟触发结束后不会有人停止录制，不限时录制改为固定时长
+        let duration_seconds = if simulate && duration_seconds == 0 {
+            log::info!(
+                "模拟触发：录制时长未限制，改为录制 {} 秒",
+                crate::constants::SIMULATED_RECORDING_SECONDS
+            );
+            crate::constants::SIMULATED_RECORDING_SECONDS
+        } else {
+            duration_seconds
+        };
+        let (pair_photos, recorder_captures_photo) = screen_recording_photo_plan(
+            app_handle.state::<AppState>().photo_with_screen_recording(),
+            camera::has_cameras(),
+        );
+        let recording = start_trigger_screen_recording(
+            app_handle.clone(),
+            duration_seconds,
+            recorder_captures_photo,
+        );
+
+        if pair_photos {
+            log::info!("拍照与屏幕录制同时进行");
+            let (video_path, photo_paths) = tokio::join!(
+                recording,
+                capture_photos(
+                    &app_handle,
+                    camera_ids,
+                    save_path,
+                    camera::PhotoOptions::from_state(&app_handle.state::<AppState>())
+                )
+            );
+            captured_files.video_path = video_path;
+            captured_files.photo_paths = photo_paths;
+            return is_action_still_current(&app_handle, action_generation);
         }
-        return true;
-    }
 
-    if let Err(error) = camera::take_photo(camera_id, save_path).await {
-        log::error!("拍照失败: {}", error);
+        captured_files.video_path = recording.await;
+        return true;
     }
 
+    let photo_options = camera::PhotoOptions::from_state(&app_handle.state::<AppState>());
+    captured_files.photo_paths =
+        capture_photos(&app_handle, camera_ids, save_path, photo_options).await;
     is_action_still_current(&app_handle, action_generation)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::should_ignore_input_event;
+    use super::{
+        TriggerSourceToggles, ffmpeg_restart_delay, idle_pause_remaining, idle_threshold_exceeded,
+        screen_recording_photo_plan, should_ignore_input_event, within_shortcut_window,
+    };
+    use crate::constants::RECORDING_ALWAYS_ON_RECHECK_INTERVAL;
+    use crate::state::ShortcutKeyState;
+    use std::time::Duration;
+
+    #[test]
+    fn photo_is_taken_alongside_screen_recording_when_enabled() {
+        // 开启且有摄像头时与录制并发拍照，录制流程不再重复拍照
+        assert_eq!(screen_recording_photo_plan(true, true), (true, false));
+        // 没有摄像头时退回录制流程自身的处理
+        assert_eq!(screen_recording_photo_plan(true, false), (false, true));
+        assert_eq!(screen_recording_photo_plan(false, true), (false, true));
+    }
+
+    #[test]
+    fn ffmpeg_restart_delay_doubles_up_to_the_cap() {
+        assert_eq!(ffmpeg_restart_delay(0), Duration::from_secs(2));
+        assert_eq!(ffmpeg_restart_delay(1), Duration::from_secs(4));
+        assert_eq!(ffmpeg_restart_delay(3), Duration::from_secs(16));
+        assert_eq!(ffmpeg_restart_delay(10), Duration::from_secs(60));
+        assert_eq!(ffmpeg_restart_delay(u32::MAX), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn idle_trigger_fires_only_after_threshold() {
+        let start = 1_000_000;
+        assert!(!idle_threshold_exceeded(start, start + 59_999, 1));
+        assert!(idle_threshold_exceeded(start, start + 60_000, 1));
+        assert!(!idle_threshold_exceeded(start, start - 1, 1));
+    }
+
+    #[test]
+    fn idle_recording_pauses_after_twenty_seconds() {
+        let start = 1_000_000;
+        assert_eq!(idle_pause_remaining(0, start, 20), None);
+        assert_eq!(
+            idle_pause_remaining(start, start, 20),
+            Some(Duration::from_millis(20_001))
+        );
+        assert_eq!(
+            idle_pause_remaining(start, start + 20_000, 20),
+            Some(Duration::from_millis(1))
+        );
+        assert_eq!(idle_pause_remaining(start, start + 20_001, 20), None);
+    }
+
+    #[test]
+    fn idle_recording_timeout_is_configurable() {
+        let start = 1_000_000;
+        assert_eq!(
+            idle_pause_remaining(start, start + 5_000, 60),
+            Some(Duration::from_millis(55_001))
+        );
+        assert_eq!(idle_pause_remaining(start, start + 60_001, 60), None);
+
+        // 0 表示不自动暂停，即使尚无活动记录或长时间无操作
+        assert_eq!(
+            idle_pause_remaining(0, start, 0),
+            Some(RECORDING_ALWAYS_ON_RECHECK_INTERVAL)
+        );
+        assert_eq!(
+            idle_pause_remaining(start, start + 3_600_000, 0),
+            Some(RECORDING_ALWAYS_ON_RECHECK_INTERVAL)
+        );
+    }
+
+    #[test]
+    fn shortcut_window_follows_key_release() {
+        use ShortcutKeyState::{Held, Released, Untracked};
+
+        assert!(within_shortcut_window(Held, 1_500, 500));
+        assert!(!within_shortcut_window(Held, 3_000, 500));
+        assert!(!within_shortcut_window(Released, 100, 500));
+        assert!(within_shortcut_window(Untracked, 100, 500));
+        assert!(!within_shortcut_window(Untracked, 500, 500));
+    }
 
     #[test]
     fn ignores_event_while_shortcut_is_in_progress() {
@@ -463,4 +1411,29 @@ mod tests {
     fn does_not_ignore_normal_input() {
         assert!(!should_ignore_input_event(false, false));
     }
+
+    #[test]
+    fn trigger_source_toggles_match_event_category() {
+        use rdev::{Button, EventType, Key};
+
+        let toggles = TriggerSourceToggles {
+            keyboard: true,
+            mouse_buttons: true,
+            mouse_move: false,
+            scroll: false,
+        };
+        assert!(toggles.allows(&EventType::KeyPress(Key::KeyA)));
+        assert!(toggles.allows(&EventType::ButtonRelease(Button::Left)));
+        assert!(!toggles.allows(&EventType::MouseMove { x: 1.0, y: 2.0 }));
+        assert!(!toggles.allows(&EventType::Wheel {
+            delta_x: 0,
+            delta_y: -1,
+        }));
+
+        let keyboard_off = TriggerSourceToggles {
+            keyboard: false,
+            ..toggles
+        };
+        assert!(!keyboard_off.allows(&EventType::KeyRelease(Key::Space)));
+    }
 }
@@ -0,0 +1,205 @@
+// snaplock/src-tauri/src/motion.rs
+//! 警戒期间定时抓取摄像头画面并与上一帧比较，画面变化超过阈值时触发锁定。
+//!
+//! 用于没有人触碰键盘鼠标、但有人出现在镜头前的场景；比较前先缩小并转为灰度，降低噪点影响。
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use image::GrayImage;
+use image::imageops::{self, FilterType};
+use tauri::AppHandle;
+
+use crate::config::CameraFourcc;
+use crate::constants::{
+    MIN_MOTION_SAMPLE_INTERVAL_MS, MOTION_FRAME_SIZE, MOTION_PIXEL_DELTA,
+    MOTION_STOP_CHECK_INTERVAL, MOTION_WARMUP_FRAMES, PHOTO_WARMUP_FRAME_INTERVAL,
+};
+
+/// 画面比较参数
+#[derive(Debug, Clone, Copy)]
+pub struct MotionSettings {
+    pub camera_id: u32,
+    pub fourcc: CameraFourcc,
+    pub threshold_percent: u8,
+    pub sample_interval_ms: u64,
+}
+
+struct MotionHandle {
+    stop_flag: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+static DETECTOR: Mutex<Option<MotionHandle>> = Mutex::new(None);
+
+/// 两帧之间亮度变化超过 `MOTION_PIXEL_DELTA` 的像素所占百分比；尺寸不同时视为无变化
+pub fn motion_score(previous: &GrayImage, current: &GrayImage) -> f32 {
+    if previous.dimensions() != current.dimensions() || previous.is_empty() {
+        return 0.0;
+    }
+
+    let changed = previous
+        .as_raw()
+        .iter()
+        .zip(current.as_raw())
+        .filter(|(a, b)| a.abs_diff(**b) > MOTION_PIXEL_DELTA)
+        .count();
+    changed as f32 * 100.0 / previous.as_raw().len() as f32
+}
+
+/// 开始在后台检测画面变化（已有检测线程时先停止）
+pub fn start(app_handle: AppHandle, settings: MotionSettings) {
+    stop();
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let thread = {
+        let stop_flag = stop_flag.clone();
+        std::thread::spawn(move || detection_loop(app_handle, settings, stop_flag))
+    };
+
+    *DETECTOR.lock().unwrap() = Some(MotionHandle { stop_flag, thread });
+    log::info!(
+        "画面变化检测已启动，摄像头 {}，阈值 {}%，采样间隔 {} 毫秒",
+        settings.camera_id,
+        settings.threshold_percent,
+        settings.sample_interval_ms
+    );
+}
+
+/// 停止检测并释放摄像头
+pub fn stop() {
+    let Some(handle) = DETECTOR.lock().unwrap().take() else {
+        return;
+    };
+    handle.stop_flag.store(true, Ordering::SeqCst);
+    if handle.thread.join().is_err() {
+        log::error!("画面变化检测线程异常退出");
+    }
+    log::info!("画面变化检测已停止");
+}
+
+/// 分段休眠，停止标志被设置后尽快返回，避免长采样间隔拖慢解除警戒
+fn sleep_unless_stopped(duration: Duration, stop_flag: &AtomicBool) {
+    let deadline = Instant::now() + duration;
+    while !stop_flag.load(Ordering::SeqCst) {
+        let Some(remaining) = deadline.checked_duration_since(Instant::now()) else {
+            return;
+        };
+        std::thread::sleep(remaining.min(MOTION_STOP_CHECK_INTERVAL));
+    }
+}
+
+fn sample_frame(camera: &mut nokhwa::Camera, camera_id: u32) -> Result<GrayImage, String> {
+    let frame = crate::camera::capture_rgb_frame(camera, camera_id)?;
+    let gray = imageops::grayscale(&frame);
+    let (width, height) = MOTION_FRAME_SIZE;
+    Ok(imageops::resize(&gray, width, height, FilterType::Triangle))
+}
+
+fn detection_loop(app_handle: AppHandle, settings: MotionSettings, stop_flag: Arc<AtomicBool>) {
+    while !stop_flag.load(Ordering::SeqCst) {
+        let Some(score) = watch_until_motion(&settings, &stop_flag) else {
+            return;
+        };
+        if crate::monitoring::trigger_on_motion(&app_handle, score).is_some() {
+            return;
+        }
+        // 处于稳定期或冷却期时不触发，重新打开摄像头继续检测
+        log::debug!("画面变化 {:.1}% 未触发锁定，继续检测", score);
+    }
+}
+
+/// 持续比较相邻帧，检测到超过阈值的变化时释放摄像头并返回变化比例；被停止或出错时返回 `None`
+fn watch_until_motion(settings: &MotionSettings, stop_flag: &AtomicBool) -> Option<f32> {
+    let mut camera = match crate::camera::open_camera_stream(settings.camera_id, settings.fourcc) {
+        Ok(camera) => camera,
+        Err(error) => {
+            log::error!("画面变化检测无法打开摄像头: {}", error);
+            return None;
+        }
+    };
+
+    // 刚打开时曝光与白平衡仍在变化，直接作为基准画面会误判为画面变化
+    for _ in 0..MOTION_WARMUP_FRAMES {
+        if stop_flag.load(Ordering::SeqCst) {
+            break;
+        }
+        let _ = camera.frame();
+        sleep_unless_stopped(PHOTO_WARMUP_FRAME_INTERVAL, stop_flag);
+    }
+
+    let interval = Duration::from_millis(
+        settings
+            .sample_interval_ms
+            .max(MIN_MOTION_SAMPLE_INTERVAL_MS),
+    );
+    let threshold = f32::from(settings.threshold_percent);
+    let mut previous: Option<GrayImage> = None;
+    let mut detected_score = None;
+
+    while !stop_flag.load(Ordering::SeqCst) {
+        let started_at = Instant::now();
+        match sample_frame(&mut camera, settings.camera_id) {
+            Ok(current) => {
+                if let Some(previous) = previous.as_ref() {
+                    let score = motion_score(previous, &current);
+                    log::debug!("画面变化比例: {:.1}%", score);
+                    if score >= threshold {
+                        detected_score = Some(score);
+                        break;
+                    }
+                }
+                previous = Some(current);
+            }
+            Err(error) => log::debug!("画面变化检测取帧失败: {}", error),
+        }
+
+        if let Some(remaining) = interval.checked_sub(started_at.elapsed()) {
+            sleep_unless_stopped(remaining, stop_flag);
+        }
+    }
+
+    // 触发流程需要用同一摄像头拍照，返回前先释放摄像头
+    if let Err(error) = camera.stop_stream() {
+        log::warn!("画面变化检测关闭摄像头失败: {}", error);
+    }
+    detected_score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{motion_score, sleep_unless_stopped};
+    use image::{GrayImage, Luma};
+    use std::sync::atomic::AtomicBool;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn motion_score_counts_changed_pixels() {
+        let previous = GrayImage::from_pixel(10, 10, Luma([100]));
+        let mut current = previous.clone();
+        assert_eq!(motion_score(&previous, &current), 0.0);
+
+        // 轻微的亮度噪点不计入变化
+        for x in 0..10 {
+            current.put_pixel(x, 0, Luma([110]));
+        }
+        assert_eq!(motion_score(&previous, &current), 0.0);
+
+        for x in 0..10 {
+            current.put_pixel(x, 1, Luma([200]));
+        }
+        assert_eq!(motion_score(&previous, &current), 10.0);
+
+        let resized = GrayImage::from_pixel(5, 5, Luma([0]));
+        assert_eq!(motion_score(&previous, &resized), 0.0);
+    }
+
+    #[test]
+    fn sleep_returns_immediately_once_stopped() {
+        let started_at = Instant::now();
+        sleep_unless_stopped(Duration::from_secs(60), &AtomicBool::new(true));
+        assert!(started_at.elapsed() < Duration::from_secs(1));
+    }
+}
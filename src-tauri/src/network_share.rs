@@ -0,0 +1,191 @@
+// snaplock/src-tauri/src/network_share.rs
+//! 保存目录为需要认证的 SMB 共享（UNC 路径）时，在写入前使用配置的凭据建立连接。
+//!
+//! 连接在警戒期间保持，解除警戒或退出应用时断开；共享断开后，下一次解析保存路径时会在后台重新连接。
+//! 触发流程不等待连接，共享尚未连接时本次文件先保存到本机目录。
+
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::config::NetworkShareCredentials;
+use crate::constants::{NETWORK_SHARE_CONNECT_ATTEMPTS, NETWORK_SHARE_RETRY_DELAY};
+
+/// 当前由本程序建立的共享连接（`\\server\share`）
+static CONNECTED_SHARE: Mutex<Option<String>> = Mutex::new(None);
+/// 后台连接线程是否正在运行，避免重复发起连接
+static CONNECTING: AtomicBool = AtomicBool::new(false);
+
+/// 从 UNC 路径中取出共享根 `\\server\share`，不是 UNC 路径时返回 `None`
+pub fn share_root(path: &str) -> Option<String> {
+    let path = path.trim().replace('/', "\\");
+    let rest = path.strip_prefix("\\\\")?;
+    // `\\?\` 与 `\\.\` 是设备路径而非网络共享
+    if rest.starts_with('?') || rest.starts_with('.') {
+        return None;
+    }
+
+    let mut parts = rest.split('\\').filter(|part| !part.is_empty());
+    let server = parts.next()?;
+    let share = parts.next()?;
+    Some(format!("\\\\{}\\{}", server, share))
+}
+
+fn share_reachable(root: &str) -> bool {
+    Path::new(&format!("{}\\", root)).exists()
+}
+
+/// 确保保存路径所在的网络共享已用凭据连接；已连接且可访问时直接返回。
+///
+/// 会重试并等待网络，只能在后台线程或 `spawn_blocking` 中调用
+pub fn ensure_connected(
+    save_path: &str,
+    credentials: &NetworkShareCredentials,
+) -> Result<(), String> {
+    let Some(root) = share_root(save_path) else {
+        return Ok(());
+    };
+
+    // 访问共享可能长时间阻塞，检查期间不持有锁，以免阻塞 `is_connected`
+    let connected = CONNECTED_SHARE.lock().unwrap().clone();
+    if connected.as_deref() == Some(root.as_str()) && share_reachable(&root) {
+        return Ok(());
+    }
+    // 共享已更换或连接已断开，先清理旧连接再重新连接
+    let previous = CONNECTED_SHARE.lock().unwrap().take();
+    if let Some(previous) = previous {
+        log::info!("网络共享连接已失效或已更换，正在重新连接: {}", previous);
+        cancel_connection(&previous);
+    }
+
+    let mut last_error = String::new();
+    for attempt in 1..=NETWORK_SHARE_CONNECT_ATTEMPTS {
+        match add_connection(&root, credentials) {
+            Ok(()) => {
+                log::info!("已连接网络共享: {}", root);
+                *CONNECTED_SHARE.lock().unwrap() = Some(root);
+                return Ok(());
+            }
+            Err(error) => {
+                log::warn!(
+                    "连接网络共享 {} 失败 ({}/{}): {}",
+                    root,
+                    attempt,
+                    NETWORK_SHARE_CONNECT_ATTEMPTS,
+                    error
+                );
+                last_error = error;
+            }
+        }
+        if attempt < NETWORK_SHARE_CONNECT_ATTEMPTS {
+            std::thread::sleep(NETWORK_SHARE_RETRY_DELAY);
+        }
+    }
+
+    Err(format!("无法连接网络共享 {}: {}", root, last_error))
+}
+
+/// 保存路径不是网络共享，或其共享已由本程序连接时返回 true；不访问网络，不会阻塞
+pub fn is_connected(save_path: &str) -> bool {
+    match share_root(save_path) {
+        Some(root) => CONNECTED_SHARE.lock().unwrap().as_deref() == Some(root.as_str()),
+        None => true,
+    }
+}
+
+/// 在后台线程中连接（或重新验证）保存路径所在的网络共享，立即返回
+pub fn connect_in_background(save_path: &str, credentials: &NetworkShareCredentials) {
+    if share_root(save_path).is_none() || CONNECTING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let save_path = save_path.to_string();
+    let credentials = credentials.clone();
+    std::thread::spawn(move || {
+        if let Err(error) = ensure_connected(&save_path, &credentials) {
+            log::error!("{}", error);
+        }
+        CONNECTING.store(false, Ordering::SeqCst);
+    });
+}
+
+/// 断开由本程序建立的共享连接
+pub fn disconnect() {
+    if let Some(root) = CONNECTED_SHARE.lock().unwrap().take() {
+        cancel_connection(&root);
+        log::info!("已断开网络共享: {}", root);
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn add_connection(root: &str, credentials: &NetworkShareCredentials) -> Result<(), String> {
+    use windows::Win32::Foundation::ERROR_SESSION_CREDENTIAL_CONFLICT;
+    use windows::Win32::NetworkManagement::WNet::{
+        CONNECT_TEMPORARY, NETRESOURCEW, RESOURCETYPE_DISK, WNetAddConnection2W,
+    };
+    use windows::core::{HSTRING, PWSTR};
+
+    let mut remote_name: Vec<u16> = root.encode_utf16().chain(std::iter::once(0)).collect();
+    let resource = NETRESOURCEW {
+        dwType: RESOURCETYPE_DISK,
+        lpRemoteName: PWSTR(remote_name.as_mut_ptr()),
+        ..Default::default()
+    };
+
+    let result = unsafe {
+        WNetAddConnection2W(
+            &resource,
+            &HSTRING::from(credentials.password.as_str()),
+            &HSTRING::from(credentials.username.as_str()),
+            CONNECT_TEMPORARY,
+        )
+    };
+    if result == ERROR_SESSION_CREDENTIAL_CONFLICT {
+        // 当前用户已用其他凭据连接到同一服务器，沿用已有连接
+        log::warn!("已存在到 {} 的其他凭据连接，沿用已有连接", root);
+        return Ok(());
+    }
+    result.ok().map_err(|error| error.to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn cancel_connection(root: &str) {
+    use windows::Win32::NetworkManagement::WNet::{NET_CONNECT_FLAGS, WNetCancelConnection2W};
+    use windows::core::HSTRING;
+
+    // 不强制断开，仍有录制文件打开时保留连接
+    let result = unsafe {
+        WNetCancelConnection2W(&HSTRING::from(root), NET_CONNECT_FLAGS::default(), false)
+    };
+    if let Err(error) = result.ok() {
+        log::debug!("断开网络共享 {} 失败: {}", root, error);
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn add_connection(_root: &str, _credentials: &NetworkShareCredentials) -> Result<(), String> {
+    Err("当前平台不支持使用凭据连接网络共享".to_string())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn cancel_connection(_root: &str) {}
+
+#[cfg(test)]
+mod tests {
+    use super::share_root;
+
+    #[test]
+    fn share_root_is_extracted_from_unc_paths() {
+        assert_eq!(
+            share_root(r"\\nas\evidence\snaplock\2024-03-07"),
+            Some(r"\\nas\evidence".to_string())
+        );
+        assert_eq!(
+            share_root("//nas/evidence/"),
+            Some(r"\\nas\evidence".to_string())
+        );
+        assert_eq!(share_root(r"\\nas"), None);
+        assert_eq!(share_root(r"\\?\C:\captures"), None);
+        assert_eq!(share_root(r"D:\captures"), None);
+    }
+}
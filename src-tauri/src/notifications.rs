@@ -0,0 +1,638 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::Local;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::{NotificationExt, PermissionState};
+
+use crate::i18n::{self, Message};
+use crate::state::AppState;
+
+lazy_static::lazy_static! {
+    static ref ALERT_THROTTLE: Mutex<AlertThrottle> = Mutex::new(AlertThrottle::default());
+}
+
+/// 准备通知上“取消”按钮回传的参数
+const CANCEL_ARMING_ARGUMENT: &str = "cancel_arming";
+
+/// 正在显示的准备通知，进入警戒或取消后移除
+#[cfg(target_os = "windows")]
+static PREPARATION_TOAST: Mutex<
+    Option<(
+        windows::UI::Notifications::ToastNotifier,
+        windows::UI::Notifications::ToastNotification,
+    )>,
+> = Mutex::new(None);
+
+/// 获取当前计算机名称
+pub fn system_hostname() -> String {
+    let from_env = if cfg!(target_os = "windows") {
+        std::env::var("COMPUTERNAME")
+    } else {
+        std::env::var("HOSTNAME")
+    };
+
+    from_env
+        .ok()
+        .or_else(|| {
+            std::fs::read_to_string("/etc/hostname")
+                .ok()
+                .map(|name| name.trim().to_string())
+        })
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// 替换模板中的 `{time}` 与 `{hostname}` 占位符
+pub fn render_template(template: &str, time: &str, hostname: &str) -> String {
+    template
+        .replace("{time}", time)
+        .replace("{hostname}", hostname)
+}
+
+fn render_or_default(template: Option<String>, default: &str) -> String {
+    match template {
+        Some(template) if !template.trim().is_empty() => render_template(
+            &template,
+            &Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            &system_hostname(),
+        ),
+        _ => default.to_string(),
+    }
+}
+
+/// 启动时检查一次通知权限；被拒绝时记录到状态中并提示前端显示横幅
+pub fn init_permission_state(app_handle: &AppHandle) {
+    let notification = app_handle.notification();
+    // Windows 需要主动请求权限，其他平台只读取当前状态
+    let permission = if cfg!(target_os = "windows") {
+        notification.request_permission()
+    } else {
+        notification.permission_state()
+    };
+
+    match permission {
+        Ok(PermissionState::Denied) => {
+            log::warn!("系统拒绝了通知权限，将不再尝试发送通知");
+            app_handle
+                .state::<AppState>()
+                .set_notification_permission_denied(true);
+            if let Err(error) = app_handle.emit("notification_permission_denied", ()) {
+                log::error!("无法发送通知权限被拒绝事件: {}", error);
+            }
+        }
+        Ok(_) => {}
+        Err(error) => log::warn!("请求通知权限失败: {}", error),
+    }
+}
+
+/// 通知开关已开启且系统未拒绝权限时才尝试发送
+fn notifications_available(state: &AppState) -> bool {
+    state.enable_notifications() && !state.notification_permission_denied()
+}
+
+/// 显示普通通知，标题使用自定义模板（未设置时为 "SnapLock"）
+pub fn show(app_handle: &AppHandle, body: &str) {
+    let state = app_handle.state::<AppState>();
+    if !notifications_available(&state) {
+        return;
+    }
+
+    let title = render_or_default(
+        state.notification_title(),
+        &i18n::tr(app_handle, Message::DefaultTitle),
+    );
+    if let Err(error) = app_handle
+        .notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .show()
+    {
+        log::error!("无法显示通知: {}", error);
+    }
+}
+
+/// 安全警报的节流决策
+#[derive(Debug, PartialEq, Eq)]
+enum AlertDecision {
+    /// 立即发送
+    SendNow,
+    /// 间隔未到，在指定时长后发送合并后的警报
+    Defer(Duration),
+    /// 已有待发送的合并警报，仅累加次数
+    Coalesce,
+}
+
+/// 在最小间隔内合并多次触发，避免重新警戒与频繁输入叠加时连续弹出警报
+#[derive(Debug, Default)]
+struct AlertThrottle {
+    last_sent: Option<Instant>,
+    pending: u32,
+    /// 合并期间最近一次拍摄的照片，随合并后的警报一起显示
+    pending_photo: Option<PathBuf>,
+}
+
+impl AlertThrottle {
+    fn on_trigger(&mut self, now: Instant, min_interval: Duration) -> AlertDecision {
+        if self.pending > 0 {
+            self.pending += 1;
+            return AlertDecision::Coalesce;
+        }
+
+        let elapsed = self
+            .last_sent
+            .map(|last_sent| now.saturating_duration_since(last_sent));
+        match elapsed {
+            Some(elapsed) if elapsed < min_interval => {
+                self.pending = 1;
+                AlertDecision::Defer(min_interval - elapsed)
+            }
+            _ => {
+                self.last_sent = Some(now);
+                AlertDecision::SendNow
+            }
+        }
+    }
+
+    /// 取出合并的触发次数并记为已发送
+    fn take_pending(&mut self, now: Instant) -> u32 {
+        self.last_sent = Some(now);
+        std::mem::take(&mut self.pending)
+    }
+}
+
+/// Webhook、邮件等远程告警渠道各自持有的合并状态，间隔内只保留最近一次触发的内容
+#[derive(Debug)]
+pub(crate) struct RemoteAlertThrottle<T> {
+    throttle: AlertThrottle,
+    pending: Option<T>,
+}
+
+impl<T> RemoteAlertThrottle<T> {
+    pub(crate) const fn new() -> Self {
+        Self {
+            throttle: AlertThrottle {
+                last_sent: None,
+                pending: 0,
+                pending_photo: None,
+            },
+            pending: None,
+        }
+    }
+}
+
+/// 与系统警报使用相同的最小间隔发送远程告警；间隔内的多次触发合并为一次，
+/// 到期后以最近一次的内容和合并次数调用 `send`
+pub(crate) fn throttle_remote_alert<T: Send + 'static>(
+    app_handle: &AppHandle,
+    channel: &'static Mutex<RemoteAlertThrottle<T>>,
+    alert: T,
+    send: fn(T, u32),
+) {
+    let min_interval =
+        Duration::from_secs(app_handle.state::<AppState>().alert_min_interval_seconds());
+    let mut alert = Some(alert);
+    let decision = {
+        let mut channel = channel.lock().unwrap();
+        let decision = channel.throttle.on_trigger(Instant::now(), min_interval);
+        if decision != AlertDecision::SendNow {
+            channel.pending = alert.take();
+        }
+        decision
+    };
+
+    match decision {
+        AlertDecision::SendNow => {
+            if let Some(alert) = alert {
+                send(alert, 1);
+            }
+        }
+        AlertDecision::Coalesce => log::debug!("远程告警已合并到待发送的告警中"),
+        AlertDecision::Defer(remaining) => {
+            log::info!("远程告警间隔未到，将在 {:?} 后合并发送", remaining);
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(remaining).await;
+                let (count, pending) = {
+                    let mut channel = channel.lock().unwrap();
+                    (
+                        channel.throttle.take_pending(Instant::now()),
+                        channel.pending.take(),
+                    )
+                };
+                if let (true, Some(alert)) = (count > 0, pending) {
+                    send(alert, count);
+                }
+            });
+        }
+    }
+}
+
+/// 丢弃尚未发送的合并警报，退出应用时调用
+pub fn cancel_pending_alerts() {
+    let mut throttle = ALERT_THROTTLE.lock().unwrap();
+    throttle.pending = 0;
+    throttle.pending_photo = None;
+}
+
+/// 触发后发送安全警报，`photo_path` 为本次拍摄的照片，可用时作为通知图片显示；
+/// 设置了最小间隔时，间隔内的多次触发合并为一条带次数的警报
+pub fn show_security_alert(app_handle: &AppHandle, photo_path: Option<PathBuf>) {
+    let min_interval =
+        Duration::from_secs(app_handle.state::<AppState>().alert_min_interval_seconds());
+    let decision = {
+        let mut throttle = ALERT_THROTTLE.lock().unwrap();
+        let decision = throttle.on_trigger(Instant::now(), min_interval);
+        if decision != AlertDecision::SendNow && photo_path.is_some() {
+            throttle.pending_photo = photo_path.clone();
+        }
+        decision
+    };
+
+    match decision {
+        AlertDecision::SendNow => send_security_alert(app_handle, 1, photo_path.as_deref()),
+        AlertDecision::Coalesce => log::debug!("安全警报已合并到待发送的警报中"),
+        AlertDecision::Defer(remaining) => {
+            log::info!("安全警报间隔未到，将在 {:?} 后合并发送", remaining);
+            let app_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(remaining).await;
+                let (count, photo_path) = {
+                    let mut throttle = ALERT_THROTTLE.lock().unwrap();
+                    (
+                        throttle.take_pending(Instant::now()),
+                        throttle.pending_photo.take(),
+                    )
+                };
+                if count > 0 {
+                    send_security_alert(&app_handle, count, photo_path.as_deref());
+                }
+            });
+        }
+    }
+}
+
+fn send_security_alert(app_handle: &AppHandle, trigger_count: u32, photo_path: Option<&Path>) {
+    let state = app_handle.state::<AppState>();
+    if !notifications_available(&state) {
+        log::debug!("通知不可用，跳过安全通知");
+        return;
+    }
+
+    let title = render_or_default(
+        state.notification_title(),
+        &i18n::tr(app_handle, Message::SecurityAlertTitle),
+    );
+    let mut body = render_or_default(
+        state.notification_body(),
+        &i18n::tr(app_handle, Message::SecurityAlertBody),
+    );
+    if trigger_count > 1 {
+        body = i18n::tr_with(
+            app_handle,
+            Message::SecurityAlertCoalesced,
+            &[
+                ("body", &body),
+                ("count", &trigger_count.to_string()),
+                ("seconds", &state.alert_min_interval_seconds().to_string()),
+            ],
+        );
+    }
+
+    if let Some(photo_path) = photo_path {
+        match show_with_image(app_handle, &title, &body, photo_path) {
+            Ok(()) => {
+                log::info!("安全通知发送成功（附带拍摄照片）");
+                return;
+            }
+            Err(error) => log::warn!("无法在通知中附带照片，改为发送纯文本通知: {}", error),
+        }
+    }
+
+    match app_handle
+        .notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .icon("📷")
+        .show()
+    {
+        Ok(_) => log::info!("安全通知发送成功"),
+        Err(error) => log::error!("发送安全通知失败: {}", error),
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// 生成带大图（hero）的 Windows Toast 通知 XML
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn toast_xml_with_image(title: &str, body: &str, image_uri: &str) -> String {
+    format!(
+        concat!(
+            "<toast><visual><binding template=\"ToastGeneric\">",
+            "<text>{}</text><text>{}</text>",
+            "<image placement=\"hero\" src=\"{}\"/>",
+            "</binding></visual></toast>"
+        ),
+        escape_xml(title),
+        escape_xml(body),
+        escape_xml(image_uri)
+    )
+}
+
+/// 生成带“取消”按钮的准备通知 XML；按钮以前台方式激活，回传 [`CANCEL_ARMING_ARGUMENT`]
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn preparation_toast_xml(title: &str, body: &str, cancel_label: &str) -> String {
+    format!(
+        concat!(
+            "<toast scenario=\"reminder\"><visual><binding template=\"ToastGeneric\">",
+            "<text>{}</text><text>{}</text>",
+            "</binding></visual>",
+            "<actions><action content=\"{}\" arguments=\"{}\" activationType=\"foreground\"/></actions>",
+            "</toast>"
+        ),
+        escape_xml(title),
+        escape_xml(body),
+        escape_xml(cancel_label),
+        CANCEL_ARMING_ARGUMENT
+    )
+}
+
+/// 按 XML 创建 Toast 通知，返回发送所用的通知器与通知本身
+#[cfg(target_os = "windows")]
+fn create_toast(
+    app_handle: &AppHandle,
+    xml: &str,
+) -> windows::core::Result<(
+    windows::UI::Notifications::ToastNotifier,
+    windows::UI::Notifications::ToastNotification,
+)> {
+    use windows::Data::Xml::Dom::XmlDocument;
+    use windows::UI::Notifications::{ToastNotification, ToastNotificationManager};
+    use windows::core::HSTRING;
+
+    // 开发模式下应用未注册 AppUserModelID，与通知插件一样借用 PowerShell 的 ID
+    const POWERSHELL_APP_ID: &str =
+        "{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\\WindowsPowerShell\\v1.0\\powershell.exe";
+
+    let app_id = if tauri::is_dev() {
+        POWERSHELL_APP_ID.to_string()
+    } else {
+        app_handle.config().identifier.clone()
+    };
+
+    let document = XmlDocument::new()?;
+    document.LoadXml(&HSTRING::from(xml))?;
+    let toast = ToastNotification::CreateToastNotification(&document)?;
+    let notifier = ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(app_id))?;
+    Ok((notifier, toast))
+}
+
+/// 通知插件在桌面端不支持图片，Windows 上直接通过 Toast XML 发送带照片的通知
+#[cfg(target_os = "windows")]
+fn show_with_image(
+    app_handle: &AppHandle,
+    title: &str,
+    body: &str,
+    photo_path: &Path,
+) -> Result<(), String> {
+    let image_uri = tauri::Url::from_file_path(photo_path)
+        .map_err(|_| format!("照片路径无效: {}", photo_path.display()))?;
+
+    let xml = toast_xml_with_image(title, body, image_uri.as_str());
+    create_toast(app_handle, &xml)
+        .and_then(|(notifier, toast)| notifier.Show(&toast))
+        .map_err(|error| error.to_string())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn show_with_image(
+    _app_handle: &AppHandle,
+    _title: &str,
+    _body: &str,
+    _photo_path: &Path,
+) -> Result<(), String> {
+    Err("当前平台不支持在通知中显示图片".to_string())
+}
+
+/// 准备期开始时显示通知；Windows 上通知带“取消”按钮，点击后放弃本次警戒
+pub fn show_preparation(app_handle: &AppHandle, delay_seconds: u32) {
+    if !notifications_available(&app_handle.state::<AppState>()) {
+        return;
+    }
+
+    let body = i18n::tr_with(
+        app_handle,
+        Message::MonitoringPreparing,
+        &[("seconds", &delay_seconds.to_string())],
+    );
+    if let Err(error) = show_cancelable_preparation(app_handle, &body) {
+        log::warn!("无法显示可取消的准备通知，改为发送普通通知: {}", error);
+        show(app_handle, &body);
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn show_cancelable_preparation(app_handle: &AppHandle, body: &str) -> Result<(), String> {
+    use windows::Foundation::TypedEventHandler;
+    use windows::UI::Notifications::{ToastActivatedEventArgs, ToastNotification};
+    use windows::core::{IInspectable, Interface};
+
+    let state = app_handle.state::<AppState>();
+    let title = render_or_default(
+        state.notification_title(),
+        &i18n::tr(app_handle, Message::DefaultTitle),
+    );
+    let xml = preparation_toast_xml(&title, body, &i18n::tr(app_handle, Message::CancelArming));
+
+    let show = || -> windows::core::Result<()> {
+        let (notifier, toast) = create_toast(app_handle, &xml)?;
+        let handler_app = app_handle.clone();
+        toast.Activated(&TypedEventHandler::<ToastNotification, IInspectable>::new(
+            move |_, args| {
+                let canceled = args
+                    .ok()
+                    .and_then(|args| args.cast::<ToastActivatedEventArgs>())
+                    .and_then(|args| args.Arguments())
+                    .is_ok_and(|arguments| arguments == CANCEL_ARMING_ARGUMENT);
+                if canceled {
+                    log::info!("用户通过准备通知取消了警戒");
+                    let app_handle = handler_app.clone();
+                    tauri::async_runtime::spawn(async move {
+                        crate::handlers::cancel_preparation(&app_handle).await;
+                    });
+                }
+                Ok(())
+            },
+        ))?;
+        notifier.Show(&toast)?;
+        if let Some((old_notifier, old_toast)) =
+            PREPARATION_TOAST.lock().unwrap().replace((notifier, toast))
+        {
+            let _ = old_notifier.Hide(&old_toast);
+        }
+        Ok(())
+    };
+    show().map_err(|error| error.to_string())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn show_cancelable_preparation(_app_handle: &AppHandle, _body: &str) -> Result<(), String> {
+    Err("当前平台不支持通知操作按钮".to_string())
+}
+
+/// 进入警戒或取消准备后移除仍在显示的准备通知
+pub fn dismiss_preparation() {
+    #[cfg(target_os = "windows")]
+    if let Some((notifier, toast)) = PREPARATION_TOAST.lock().unwrap().take() {
+        if let Err(error) = notifier.Hide(&toast) {
+            log::debug!("移除准备通知失败: {}", error);
+        }
+    }
+}
+
+/// 测试通知失败的原因，前端据此决定是否引导用户到系统设置中开启通知权限
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub enum TestNotificationError {
+    PermissionDenied,
+    Failed(String),
+}
+
+/// 忽略通知开关发送一条测试通知，用于确认系统通知是否可用
+#[tauri::command]
+pub fn send_test_notification(app_handle: AppHandle) -> Result<(), TestNotificationError> {
+    let notification = app_handle.notification();
+    let state = app_handle.state::<AppState>();
+    match notification.permission_state() {
+        Ok(PermissionState::Denied) => {
+            log::warn!("测试通知失败: 系统拒绝了通知权限");
+            state.set_notification_permission_denied(true);
+            return Err(TestNotificationError::PermissionDenied);
+        }
+        // 用户可能已在系统设置中重新开启权限
+        Ok(_) => state.set_notification_permission_denied(false),
+        Err(error) => log::warn!("读取通知权限状态失败: {}", error),
+    }
+
+    let title = render_or_default(
+        state.notification_title(),
+        &i18n::tr(&app_handle, Message::DefaultTitle),
+    );
+    notification
+        .builder()
+        .title(title)
+        .body(i18n::tr(&app_handle, Message::TestNotification))
+        .show()
+        .map_err(|error| {
+            log::error!("发送测试通知失败: {}", error);
+            TestNotificationError::Failed(error.to_string())
+        })?;
+
+    log::info!("测试通知发送成功");
+    Ok(())
+}
+
+/// 前端启动时查询通知权限是否被拒绝，用于显示提示横幅
+#[tauri::command]
+pub fn get_notification_permission_denied(app_handle: AppHandle) -> bool {
+    app_handle
+        .state::<AppState>()
+        .notification_permission_denied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AlertDecision, AlertThrottle, CANCEL_ARMING_ARGUMENT, preparation_toast_xml,
+        render_or_default, render_template, toast_xml_with_image,
+    };
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn render_template_replaces_all_placeholders() {
+        let rendered = render_template(
+            "{hostname}: access at {time} ({time})",
+            "14:32",
+            "OFFICE-PC",
+        );
+
+        assert_eq!(rendered, "OFFICE-PC: access at 14:32 (14:32)");
+    }
+
+    #[test]
+    fn blank_template_falls_back_to_default() {
+        assert_eq!(render_or_default(None, "默认"), "默认");
+        assert_eq!(render_or_default(Some("   ".to_string()), "默认"), "默认");
+        assert_eq!(
+            render_or_default(Some("Alert".to_string()), "默认"),
+            "Alert"
+        );
+    }
+
+    #[test]
+    fn alerts_inside_interval_are_coalesced() {
+        let interval = Duration::from_secs(30);
+        let start = Instant::now();
+        let mut throttle = AlertThrottle::default();
+
+        assert_eq!(throttle.on_trigger(start, interval), AlertDecision::SendNow);
+        assert_eq!(
+            throttle.on_trigger(start + Duration::from_secs(10), interval),
+            AlertDecision::Defer(Duration::from_secs(20))
+        );
+        assert_eq!(
+            throttle.on_trigger(start + Duration::from_secs(15), interval),
+            AlertDecision::Coalesce
+        );
+        assert_eq!(throttle.take_pending(start + Duration::from_secs(30)), 2);
+        assert_eq!(
+            throttle.on_trigger(start + Duration::from_secs(61), interval),
+            AlertDecision::SendNow
+        );
+    }
+
+    #[test]
+    fn zero_interval_never_throttles() {
+        let start = Instant::now();
+        let mut throttle = AlertThrottle::default();
+        assert_eq!(
+            throttle.on_trigger(start, Duration::ZERO),
+            AlertDecision::SendNow
+        );
+        assert_eq!(
+            throttle.on_trigger(start, Duration::ZERO),
+            AlertDecision::SendNow
+        );
+    }
+
+    #[test]
+    fn toast_xml_escapes_text_and_image_uri() {
+        assert_eq!(
+            toast_xml_with_image("A & B", "<access>", "file:///C:/Photos/a'b.jpg"),
+            "<toast><visual><binding template=\"ToastGeneric\">\
+             <text>A &amp; B</text><text>&lt;access&gt;</text>\
+             <image placement=\"hero\" src=\"file:///C:/Photos/a&apos;b.jpg\"/>\
+             </binding></visual></toast>"
+        );
+    }
+
+    #[test]
+    fn preparation_toast_has_cancel_action() {
+        let xml = preparation_toast_xml("SnapLock", "3 秒后进入警戒状态", "<取消>");
+
+        assert!(xml.contains("<text>3 秒后进入警戒状态</text>"));
+        assert!(xml.contains(&format!(
+            "<action content=\"&lt;取消&gt;\" arguments=\"{}\"",
+            CANCEL_ARMING_ARGUMENT
+        )));
+    }
+}
@@ -0,0 +1,150 @@
+// snaplock/src-tauri/src/power_monitor.rs
+//! 监听合盖与系统睡眠事件（仅 Windows），警戒期间按设置视为触发
+
+use std::sync::OnceLock;
+
+use tauri::AppHandle;
+use windows::Win32::Foundation::{HANDLE, HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::System::Power::{POWERBROADCAST_SETTING, RegisterPowerSettingNotification};
+use windows::Win32::System::SystemServices::GUID_LIDSWITCH_STATE_CHANGE;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DEVICE_NOTIFY_WINDOW_HANDLE, DefWindowProcW, DispatchMessageW, GetMessageW,
+    MSG, PBT_APMSUSPEND, PBT_POWERSETTINGCHANGE, RegisterClassW, WINDOW_EX_STYLE, WINDOW_STYLE,
+    WM_POWERBROADCAST, WNDCLASSW,
+};
+use windows::core::w;
+
+use crate::constants::LID_CLOSE_SUSPEND_GRACE;
+
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+/// 创建隐藏窗口接收电源广播，并在独立线程中运行消息循环
+pub fn start_power_monitoring(app_handle: AppHandle) -> Result<(), String> {
+    if APP_HANDLE.set(app_handle).is_err() {
+        return Err("电源事件监控已在运行中".to_string());
+    }
+
+    let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        // 窗口必须在运行消息循环的线程上创建
+        let result = unsafe { create_notification_window() };
+        let created = result.is_ok();
+        let _ = ready_tx.send(result.map(|_| ()));
+        if created {
+            run_message_loop();
+        }
+    });
+
+    ready_rx
+        .recv()
+        .map_err(|_| "电源事件监控线程意外退出".to_string())?
+}
+
+/// 注意：仅消息窗口 (HWND_MESSAGE) 收不到 PBT_APMSUSPEND 广播，因此使用不可见的顶层窗口
+unsafe fn create_notification_window() -> Result<HWND, String> {
+    let instance =
+        unsafe { GetModuleHandleW(None) }.map_err(|e| format!("获取模块句柄失败: {}", e))?;
+    let class_name = w!("SnapLockPowerMonitor");
+    let class = WNDCLASSW {
+        lpfnWndProc: Some(window_proc),
+        hInstance: instance.into(),
+        lpszClassName: class_name,
+        ..Default::default()
+    };
+    if unsafe { RegisterClassW(&class) } == 0 {
+        return Err("注册电源监控窗口类失败".to_string());
+    }
+
+    let hwnd = unsafe {
+        CreateWindowExW(
+            WINDOW_EX_STYLE::default(),
+            class_name,
+            w!("SnapLock Power Monitor"),
+            WINDOW_STYLE::default(),
+            0,
+            0,
+            0,
+            0,
+            None,
+            None,
+            Some(instance.into()),
+            None,
+        )
+    }
+    .map_err(|e| format!("创建电源监控窗口失败: {}", e))?;
+
+    unsafe {
+        RegisterPowerSettingNotification(
+            HANDLE(hwnd.0),
+            &GUID_LIDSWITCH_STATE_CHANGE,
+            DEVICE_NOTIFY_WINDOW_HANDLE,
+        )
+    }
+    .map_err(|e| format!("注册合盖事件通知失败: {}", e))?;
+
+    log::info!("电源事件监控已启动");
+    Ok(hwnd)
+}
+
+fn run_message_loop() {
+    let mut message = MSG::default();
+    // GetMessageW 出错时返回 -1，只在收到正常消息时继续
+    while unsafe { GetMessageW(&mut message, None, 0, 0) }.0 > 0 {
+        unsafe {
+            DispatchMessageW(&message);
+        }
+    }
+    log::info!("电源事件监控消息循环已退出");
+}
+
+unsafe extern "system" fn window_proc(
+    hwnd: HWND,
+    message: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if message == WM_POWERBROADCAST {
+        match wparam.0 as u32 {
+            PBT_APMSUSPEND => {
+                log::info!("系统即将进入睡眠");
+                handle_power_event();
+            }
+            PBT_POWERSETTINGCHANGE if lparam.0 != 0 => {
+                let setting = unsafe { &*(lparam.0 as *const POWERBROADCAST_SETTING) };
+                // 合盖状态：0 表示已合上，1 表示打开
+                if setting.PowerSetting == GUID_LIDSWITCH_STATE_CHANGE
+                    && setting.DataLength >= 1
+                    && setting.Data[0] == 0
+                {
+                    log::info!("检测到笔记本合盖");
+                    handle_power_event();
+                }
+            }
+            _ => {}
+        }
+        return LRESULT(1);
+    }
+
+    unsafe { DefWindowProcW(hwnd, message, wparam, lparam) }
+}
+
+/// 在窗口过程中短暂阻塞以推迟挂起，争取在系统睡眠前完成拍摄
+fn handle_power_event() {
+    let Some(app_handle) = APP_HANDLE.get() else {
+        return;
+    };
+    let Some(task) = crate::monitoring::trigger_on_lid_close(app_handle) else {
+        return;
+    };
+
+    let finished = tauri::async_runtime::block_on(async {
+        tokio::time::timeout(LID_CLOSE_SUSPEND_GRACE, task).await
+    });
+    if finished.is_err() {
+        log::warn!(
+            "触发流程未能在 {:?} 内完成，系统可能在拍摄完成前进入睡眠",
+            LID_CLOSE_SUSPEND_GRACE
+        );
+    }
+}
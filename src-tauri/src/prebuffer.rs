@@ -0,0 +1,336 @@
+// snaplock/src-tauri/src/prebuffer.rs
+//! 警戒期间持续缓存最近几秒的摄像头画面，触发时连同触发后的画面一起保存为视频。
+//!
+//! 触发时立即停止缓存以释放摄像头，不延误拍照与锁屏；触发后的画面在锁屏后于后台补录，再与缓存画面一起编码。
+
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use chrono::Local;
+use tauri::AppHandle;
+
+use crate::config::CameraFourcc;
+use crate::constants::{PREBUFFER_FPS, PREBUFFER_POST_TRIGGER};
+
+/// 固定容量的 JPEG 帧环形缓冲区，写满后丢弃最旧的帧
+struct FrameRing {
+    frames: VecDeque<Vec<u8>>,
+    capacity: usize,
+}
+
+impl FrameRing {
+    fn new(capacity: usize) -> Self {
+        Self {
+            frames: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    fn push(&mut self, frame: Vec<u8>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        while self.frames.len() >= self.capacity {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(frame);
+    }
+
+    fn drain(&mut self) -> Vec<Vec<u8>> {
+        self.frames.drain(..).collect()
+    }
+}
+
+/// 缓存与补录共用的取帧参数
+#[derive(Debug, Clone, Copy)]
+struct CaptureSettings {
+    camera_id: u32,
+    jpeg_quality: u8,
+    fourcc: CameraFourcc,
+}
+
+struct PrebufferHandle {
+    settings: CaptureSettings,
+    stop_flag: Arc<AtomicBool>,
+    frames: Arc<Mutex<FrameRing>>,
+    thread: JoinHandle<()>,
+}
+
+lazy_static::lazy_static! {
+    static ref PREBUFFER: Mutex<Option<PrebufferHandle>> = Mutex::new(None);
+    /// 触发后补录画面的后台线程，结束时已释放摄像头
+    static ref TAIL: Mutex<Option<JoinHandle<()>>> = Mutex::new(None);
+}
+
+/// 开始在后台缓存摄像头画面（已有缓存线程时先停止，仍在补录上一次触发的画面时等待其释放摄像头）
+pub fn start(camera_id: u32, seconds: u32, jpeg_quality: u8, fourcc: CameraFourcc) {
+    stop();
+    wait_for_tail();
+    if seconds == 0 {
+        return;
+    }
+
+    let settings = CaptureSettings {
+        camera_id,
+        jpeg_quality,
+        fourcc,
+    };
+    let capacity = (seconds * PREBUFFER_FPS) as usize;
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let frames = Arc::new(Mutex::new(FrameRing::new(capacity)));
+
+    let thread = {
+        let stop_flag = stop_flag.clone();
+        let frames = frames.clone();
+        std::thread::spawn(move || capture_loop(settings, stop_flag, frames, None))
+    };
+
+    *PREBUFFER.lock().unwrap() = Some(PrebufferHandle {
+        settings,
+        stop_flag,
+        frames,
+        thread,
+    });
+    log::info!("预录缓冲已启动，摄像头 {}，缓存 {} 秒", camera_id, seconds);
+}
+
+/// 停止缓存并释放摄像头，丢弃已缓存的画面
+pub fn stop() {
+    if take_frames_after_stop().is_some() {
+        log::info!("预录缓冲已停止");
+    }
+}
+
+pub fn is_running() -> bool {
+    PREBUFFER
+        .lock()
+        .map(|guard| {
+            guard
+                .as_ref()
+                .is_some_and(|handle| !handle.thread.is_finished())
+        })
+        .unwrap_or(false)
+}
+
+/// 等待上一次触发的补录线程释放摄像头
+fn wait_for_tail() {
+    let Some(tail) = TAIL.lock().unwrap().take() else {
+        return;
+    };
+    if tail.join().is_err() {
+        log::error!("预录补录线程异常退出");
+    }
+}
+
+fn take_frames_after_stop() -> Option<(CaptureSettings, Vec<Vec<u8>>)> {
+    let handle = PREBUFFER.lock().unwrap().take()?;
+    handle.stop_flag.store(true, Ordering::SeqCst);
+    if handle.thread.join().is_err() {
+        log::error!("预录缓冲线程异常退出");
+    }
+
+    let frames = handle.frames.lock().unwrap().drain();
+    Some((handle.settings, frames))
+}
+
+/// 持续取帧直到 `stop_flag` 被设置；指定 `duration` 时到时自动结束
+fn capture_loop(
+    settings: CaptureSettings,
+    stop_flag: Arc<AtomicBool>,
+    frames: Arc<Mutex<FrameRing>>,
+    duration: Option<Duration>,
+) {
+    let camera_id = settings.camera_id;
+    let mut camera = match crate::camera::open_camera_stream(camera_id, settings.fourcc) {
+        Ok(camera) => camera,
+        Err(error) => {
+            log::error!("预录缓冲无法打开摄像头: {}", error);
+            return;
+        }
+    };
+
+    let deadline = duration.map(|duration| Instant::now() + duration);
+    let frame_interval = Duration::from_millis(1_000 / u64::from(PREBUFFER_FPS));
+    while !stop_flag.load(Ordering::SeqCst)
+        && deadline.is_none_or(|deadline| Instant::now() < deadline)
+    {
+        let started_at = Instant::now();
+        match crate::camera::capture_jpeg_frame(&mut camera, camera_id, settings.jpeg_quality) {
+            Ok(frame) => frames.lock().unwrap().push(frame),
+            Err(error) => log::debug!("预录缓冲取帧失败: {}", error),
+        }
+
+        if let Some(remaining) = frame_interval.checked_sub(started_at.elapsed()) {
+            std::thread::sleep(remaining);
+        }
+    }
+
+    if let Err(error) = camera.stop_stream() {
+        log::warn!("预录缓冲关闭摄像头失败: {}", error);
+    }
+}
+
+/// 触发时取出的缓存画面，锁屏后由 [`TriggerCapture::finish_in_background`] 补录并编码
+pub struct TriggerCapture {
+    settings: CaptureSettings,
+    frames: Vec<Vec<u8>>,
+    ffmpeg_path: PathBuf,
+    filepath: PathBuf,
+}
+
+impl TriggerCapture {
+    /// 编码完成后视频文件的路径
+    pub fn output_path(&self) -> String {
+        self.filepath.to_string_lossy().to_string()
+    }
+
+    /// 在后台线程中补录触发后片刻的画面，释放摄像头后再将全部画面编码为视频
+    pub fn finish_in_background(self) {
+        let thread = std::thread::spawn(move || {
+            let tail = Arc::new(Mutex::new(FrameRing::new(
+                (PREBUFFER_POST_TRIGGER.as_secs() as u32 * PREBUFFER_FPS) as usize + 1,
+            )));
+            capture_loop(
+                self.settings,
+                Arc::new(AtomicBool::new(false)),
+                tail.clone(),
+                Some(PREBUFFER_POST_TRIGGER),
+            );
+
+            let mut frames = self.frames;
+            frames.extend(tail.lock().unwrap().drain());
+            let (ffmpeg_path, filepath) = (self.ffmpeg_path, self.filepath);
+            log::info!("保存 {} 帧预录画面至: {}", frames.len(), filepath.display());
+            // 编码不占用摄像头，不必让重新警戒等待
+            std::thread::spawn(move || {
+                if let Err(error) = encode_frames(&ffmpeg_path, &frames, &filepath) {
+                    log::error!("预录画面编码失败: {}", error);
+                }
+            });
+        });
+
+        *TAIL.lock().unwrap() = Some(thread);
+    }
+}
+
+/// 触发时立即停止缓存并释放摄像头，取出已缓存的画面。
+/// 缓存未启用或为空时返回 `None`。
+pub async fn take_on_trigger(
+    app_handle: &AppHandle,
+    save_path: Option<String>,
+) -> Option<TriggerCapture> {
+    if !is_running() {
+        return None;
+    }
+
+    let (settings, frames) = tokio::task::spawn_blocking(take_frames_after_stop)
+        .await
+        .ok()
+        .flatten()?;
+
+    if frames.is_empty() {
+        log::warn!("预录缓冲中没有可用画面");
+        return None;
+    }
+
+    let base_path = save_path.unwrap_or_else(crate::config::get_default_save_path);
+    let timestamp = Local::now().format("%Y%m%d_%H%M%S");
+    let filepath = Path::new(&base_path).join(format!("snaplock_prebuffer_{}.mkv", timestamp));
+
+    let ffmpeg_path = match crate::ffmpeg::resolve(app_handle) {
+        Ok(path) => path,
+        Err(error) => {
+            log::error!("无法定位ffmpeg，丢弃预录画面: {}", error);
+            return None;
+        }
+    };
+
+    Some(TriggerCapture {
+        settings,
+        frames,
+        ffmpeg_path,
+        filepath,
+    })
+}
+
+fn encode_frames(ffmpeg_path: &Path, frames: &[Vec<u8>], filepath: &Path) -> Result<(), String> {
+    let mut command = Command::new(ffmpeg_path);
+    command
+        .args(["-f", "image2pipe", "-framerate"])
+        .arg(PREBUFFER_FPS.to_string())
+        .args([
+            "-c:v",
+            "mjpeg",
+            "-i",
+            "-",
+            "-c:v",
+            "libx264",
+            "-preset",
+            "ultrafast",
+            "-pix_fmt",
+            "yuv420p",
+            "-y",
+        ])
+        .arg(filepath);
+    crate::process_utils::configure_background_command(&mut command);
+    command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("启动ffmpeg失败: {}", e))?;
+    if let Err(error) = crate::process_utils::assign_child_to_kill_on_close_job(&mut child) {
+        crate::process_utils::terminate_child_process(&mut child, "prebuffer ffmpeg");
+        return Err(error);
+    }
+
+    if let Some(mut stdin) = child.stdin.take() {
+        for frame in frames {
+            if let Err(error) = stdin.write_all(frame) {
+                crate::process_utils::terminate_child_process(&mut child, "prebuffer ffmpeg");
+                return Err(format!("写入预录画面失败: {}", error));
+            }
+        }
+    }
+
+    let status = child
+        .wait()
+        .map_err(|e| format!("等待ffmpeg完成失败: {}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("ffmpeg 退出码异常: {:?}", status.code()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FrameRing;
+
+    #[test]
+    fn frame_ring_keeps_only_the_newest_frames() {
+        let mut ring = FrameRing::new(3);
+        for frame in 0u8..5 {
+            ring.push(vec![frame]);
+        }
+
+        assert_eq!(ring.drain(), vec![vec![2], vec![3], vec![4]]);
+        assert!(ring.drain().is_empty());
+    }
+
+    #[test]
+    fn zero_capacity_ring_stores_nothing() {
+        let mut ring = FrameRing::new(0);
+        ring.push(vec![1]);
+        assert!(ring.drain().is_empty());
+    }
+}
@@ -0,0 +1,208 @@
+// snaplock/src-tauri/src/preview.rs
+//! 设置界面的实时摄像头预览：在后台线程中保持摄像头打开，按设定帧率通过 `preview_frame` 事件推送画面。
+//!
+//! 相比逐次调用 `get_camera_preview`，不必每一帧都重新打开摄像头。其他流程打开摄像头前（拍照、模拟触发、
+//! 查询格式、进入警戒）以及窗口隐藏到托盘时会停止预览，预览线程退出时发送 `preview_stopped` 事件。
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, mpsc};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::config::CameraFourcc;
+use crate::constants::{DEFAULT_PREVIEW_FPS, DEFAULT_PREVIEW_WIDTH, MAX_PREVIEW_FPS};
+use crate::state::{AppState, MonitoringState};
+
+/// 预览流参数
+#[derive(Debug, Clone, Copy)]
+struct PreviewSettings {
+    camera_id: u32,
+    fourcc: CameraFourcc,
+    fps: u32,
+    jpeg_quality: u8,
+}
+
+struct PreviewHandle {
+    camera_id: u32,
+    stop_flag: Arc<AtomicBool>,
+    /// 最近一帧预览（base64 JPEG data URI），供 `get_camera_preview` 直接复用
+    latest_frame: Arc<Mutex<Option<String>>>,
+    thread: JoinHandle<()>,
+}
+
+static STREAM: Mutex<Option<PreviewHandle>> = Mutex::new(None);
+
+/// 每帧间隔，帧率限制在 1 到 `MAX_PREVIEW_FPS` 之间
+fn frame_interval(fps: u32) -> Duration {
+    Duration::from_millis(1_000 / u64::from(fps.clamp(1, MAX_PREVIEW_FPS)))
+}
+
+fn stop_handle(handle: PreviewHandle) {
+    handle.stop_flag.store(true, Ordering::SeqCst);
+    if handle.thread.join().is_err() {
+        log::error!("摄像头预览线程异常退出");
+    }
+}
+
+/// 打开摄像头并开始推送预览（已有预览时先停止）；摄像头打开失败时返回错误
+fn start(app_handle: AppHandle, settings: PreviewSettings) -> Result<(), String> {
+    stop();
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let latest_frame = Arc::new(Mutex::new(None));
+    let (ready_tx, ready_rx) = mpsc::sync_channel(1);
+    let thread = {
+        let stop_flag = stop_flag.clone();
+        let latest_frame = latest_frame.clone();
+        std::thread::spawn(move || {
+            stream_loop(app_handle, settings, stop_flag, latest_frame, ready_tx)
+        })
+    };
+
+    let handle = PreviewHandle {
+        camera_id: settings.camera_id,
+        stop_flag,
+        latest_frame,
+        thread,
+    };
+    match ready_rx.recv() {
+        Ok(Ok(())) => {}
+        Ok(Err(error)) => {
+            stop_handle(handle);
+            return Err(error);
+        }
+        Err(_) => {
+            stop_handle(handle);
+            return Err("摄像头预览线程意外退出".to_string());
+        }
+    }
+
+    if let Some(previous) = STREAM.lock().unwrap().replace(handle) {
+        stop_handle(previous);
+    }
+    log::info!(
+        "摄像头预览已启动，摄像头 {}，帧率 {} fps",
+        settings.camera_id,
+        settings.fps
+    );
+    Ok(())
+}
+
+/// 停止预览并释放摄像头
+pub fn stop() {
+    let Some(handle) = STREAM.lock().unwrap().take() else {
+        return;
+    };
+    stop_handle(handle);
+    log::info!("摄像头预览已停止");
+}
+
+/// 在阻塞线程中停止预览，供异步流程在打开摄像头前调用
+pub async fn stop_async() {
+    if STREAM.lock().unwrap().is_none() {
+        return;
+    }
+    if let Err(error) = tokio::task::spawn_blocking(stop).await {
+        log::error!("停止摄像头预览失败: {}", error);
+    }
+}
+
+/// 预览正在使用该摄像头时返回最近一帧，不必重新打开摄像头
+pub fn latest_frame(camera_id: u32) -> Option<String> {
+    let stream = STREAM.lock().unwrap();
+    let handle = stream
+        .as_ref()
+        .filter(|handle| handle.camera_id == camera_id)?;
+    handle.latest_frame.lock().unwrap().clone()
+}
+
+fn stream_loop(
+    app_handle: AppHandle,
+    settings: PreviewSettings,
+    stop_flag: Arc<AtomicBool>,
+    latest_frame: Arc<Mutex<Option<String>>>,
+    ready: mpsc::SyncSender<Result<(), String>>,
+) {
+    let mut camera = match crate::camera::open_camera_stream(settings.camera_id, settings.fourcc) {
+        Ok(camera) => camera,
+        Err(error) => {
+            let _ = ready.send(Err(error));
+            return;
+        }
+    };
+    let _ = ready.send(Ok(()));
+
+    let interval = frame_interval(settings.fps);
+    while !stop_flag.load(Ordering::SeqCst) {
+        let started_at = Instant::now();
+        let frame =
+            crate::camera::capture_rgb_frame(&mut camera, settings.camera_id).and_then(|image| {
+                crate::camera::encode_preview(&image, DEFAULT_PREVIEW_WIDTH, settings.jpeg_quality)
+            });
+        match frame {
+            Ok(data_uri) => {
+                *latest_frame.lock().unwrap() = Some(data_uri.clone());
+                if let Err(error) = app_handle.emit("preview_frame", data_uri) {
+                    log::debug!("推送预览帧失败: {}", error);
+                }
+            }
+            Err(error) => log::debug!("摄像头预览取帧失败: {}", error),
+        }
+
+        if let Some(remaining) = interval.checked_sub(started_at.elapsed()) {
+            std::thread::sleep(remaining);
+        }
+    }
+
+    if let Err(error) = camera.stop_stream() {
+        log::warn!("摄像头预览关闭摄像头失败: {}", error);
+    }
+    if let Err(error) = app_handle.emit("preview_stopped", settings.camera_id) {
+        log::debug!("发送预览停止事件失败: {}", error);
+    }
+}
+
+/// 开始连续推送摄像头预览帧；`fps` 默认 10，最大 30
+#[tauri::command]
+pub async fn start_preview_stream(
+    app_handle: AppHandle,
+    camera_id: u32,
+    fps: Option<u32>,
+) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    if state.status() != MonitoringState::Idle {
+        return Err("警戒期间摄像头由监控流程使用，无法开启预览".to_string());
+    }
+
+    let settings = PreviewSettings {
+        camera_id,
+        fourcc: state.camera_fourcc(),
+        fps: fps.unwrap_or(DEFAULT_PREVIEW_FPS).clamp(1, MAX_PREVIEW_FPS),
+        jpeg_quality: state.jpeg_quality(),
+    };
+    tokio::task::spawn_blocking(move || start(app_handle, settings))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// 停止摄像头预览
+#[tauri::command]
+pub async fn stop_preview_stream() -> Result<(), String> {
+    stop_async().await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::frame_interval;
+    use std::time::Duration;
+
+    #[test]
+    fn frame_interval_follows_clamped_fps() {
+        assert_eq!(frame_interval(10), Duration::from_millis(100));
+        assert_eq!(frame_interval(0), Duration::from_millis(1_000));
+        assert_eq!(frame_interval(1_000), frame_interval(30));
+    }
+}
@@ -1,4 +1,6 @@
+use std::io::Write;
 use std::process::{Child, Command};
+use std::time::{Duration, Instant};
 
 #[cfg(all(windows, not(debug_assertions)))]
 use std::os::windows::process::CommandExt;
@@ -60,6 +62,59 @@ pub fn terminate_child_process(child: &mut Child, process_name: &str) {
     }
 }
 
+/// Asks an ffmpeg child to finish its output by writing `q` to its stdin,
+/// waiting up to `timeout` before falling back to a hard kill.
+pub fn stop_ffmpeg_gracefully(child: &mut Child, process_name: &str, timeout: Duration) {
+    match child.try_wait() {
+        Ok(Some(status)) => {
+            log::info!("{}进程已退出，状态: {:?}", process_name, status);
+            return;
+        }
+        Ok(None) => {}
+        Err(error) => {
+            log::error!("检查{}进程状态失败: {}", process_name, error);
+        }
+    }
+
+    let quit_sent = match child.stdin.take() {
+        Some(mut stdin) => match stdin.write_all(b"q").and_then(|_| stdin.flush()) {
+            Ok(_) => {
+                log::info!("已向{}进程发送退出指令", process_name);
+                true
+            }
+            Err(error) => {
+                log::warn!("向{}进程发送退出指令失败: {}", process_name, error);
+                false
+            }
+        },
+        None => {
+            log::warn!("{}进程没有可用的标准输入，无法优雅停止", process_name);
+            false
+        }
+    };
+
+    if quit_sent {
+        let deadline = Instant::now() + timeout;
+        while Instant::now() < deadline {
+            match child.try_wait() {
+                Ok(Some(status)) => {
+                    log::info!("{}进程已正常退出，状态: {:?}", process_name, status);
+                    return;
+                }
+                Ok(None) => std::thread::sleep(Duration::from_millis(100)),
+                Err(error) => {
+                    log::error!("等待{}进程退出时发生错误: {}", process_name, error);
+                    break;
+                }
+            }
+        }
+
+        log::warn!("{}进程未在 {:?} 内退出，强制终止", process_name, timeout);
+    }
+
+    terminate_child_process(child, process_name);
+}
+
 #[cfg(windows)]
 fn ensure_job_object() -> Result<HANDLE, String> {
     let mut job_guard = JOB_HANDLE.lock().unwrap();
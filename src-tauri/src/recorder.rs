@@ -1,17 +1,42 @@
+use crate::config::RecordingRegion;
+use crate::constants::SCREENSHOT_TIMEOUT;
 use chrono::Local;
-use std::process::{Child, Command};
-#[cfg(all(windows, not(debug_assertions)))]
-use std::process::Stdio;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::sync::{Arc, Mutex};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tauri::{AppHandle, Manager};
 
+/// 录制来源：摄像头录像与屏幕录制共用同一套进程登记与清理逻辑
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RecordingSource {
+    Webcam(u32),
+    Screen,
+}
+
+impl std::fmt::Display for RecordingSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecordingSource::Webcam(camera_id) => write!(f, "camera {} ffmpeg", camera_id),
+            RecordingSource::Screen => write!(f, "screen ffmpeg"),
+        }
+    }
+}
+
 lazy_static::lazy_static! {
-    pub static ref FFMPEG_PROCESS: Arc<Mutex<Option<Child>>> = Arc::new(Mutex::new(None));
+    static ref RECORDING_PROCESSES: Mutex<HashMap<RecordingSource, Child>> =
+        Mutex::new(HashMap::new());
 }
 static SCREEN_RECORDING_STARTING: AtomicBool = AtomicBool::new(false);
 static LAST_SCREEN_RECORDING_FAILURE_MS: AtomicU64 = AtomicU64::new(0);
+/// 当前屏幕录制是否为不限时长的持续录制（此类进程自行退出即视为异常）
+static SCREEN_RECORDING_CONTINUOUS: AtomicBool = AtomicBool::new(false);
+static SCREEN_RECORDING_EXITED_UNEXPECTEDLY: AtomicBool = AtomicBool::new(false);
+/// 当前屏幕录制是否由用户手动开始
+static SCREEN_RECORDING_MANUAL: AtomicBool = AtomicBool::new(false);
 const SCREEN_RECORDING_RETRY_COOLDOWN_MS: u64 = 5_000;
 
 fn now_millis() -> u64 {
@@ -43,52 +68,401 @@ pub fn screen_recording_retry_remaining_ms() -> Option<u64> {
     }
 }
 
-fn refresh_screen_recording_state(process_guard: &mut Option<Child>) -> bool {
-    let mut should_clear = false;
-    let is_running = match process_guard.as_mut() {
-        Some(child) => match child.try_wait() {
-            Ok(None) => true,
-            Ok(Some(status)) => {
-                log::warn!("ffmpeg 录制进程已退出，状态: {:?}", status);
-                should_clear = true;
-                false
+/// 移除已经退出的录制进程
+fn prune_finished_processes(processes: &mut HashMap<RecordingSource, Child>) {
+    processes.retain(|source, child| match child.try_wait() {
+        Ok(None) => true,
+        Ok(Some(status)) => {
+            log::warn!("{} 录制进程已退出，状态: {:?}", source, status);
+            note_unexpected_exit(*source);
+            false
+        }
+        Err(error) => {
+            log::error!("检查 {} 录制进程状态失败: {}", source, error);
+            note_unexpected_exit(*source);
+            false
+        }
+    });
+}
+
+/// 主动停止的进程会先从登记表移除，因此这里发现持续录制退出即为意外退出
+fn note_unexpected_exit(source: RecordingSource) {
+    if source == RecordingSource::Screen && SCREEN_RECORDING_CONTINUOUS.load(Ordering::SeqCst) {
+        SCREEN_RECORDING_EXITED_UNEXPECTEDLY.store(true, Ordering::SeqCst);
+    }
+}
+
+/// 检查持续屏幕录制的 ffmpeg 是否意外退出，返回后清除该标记
+pub fn take_screen_recording_unexpected_exit() -> bool {
+    {
+        let mut processes = RECORDING_PROCESSES.lock().unwrap();
+        prune_finished_processes(&mut processes);
+    }
+    SCREEN_RECORDING_EXITED_UNEXPECTEDLY.swap(false, Ordering::SeqCst)
+}
+
+/// 将新启动的录制进程纳入 Job Object 后登记，确保应用异常退出时也会被系统结束
+fn track_process(
+    processes: &mut HashMap<RecordingSource, Child>,
+    source: RecordingSource,
+    mut child: Child,
+) -> Result<(), String> {
+    if let Err(error) = crate::process_utils::assign_child_to_kill_on_close_job(&mut child) {
+        log::error!("无法将 {} 进程纳入 Job Object: {}", source, error);
+        crate::process_utils::terminate_child_process(&mut child, &source.to_string());
+        return Err(error);
+    }
+
+    processes.insert(source, child);
+    Ok(())
+}
+
+pub fn is_recording(source: RecordingSource) -> bool {
+    let mut processes = RECORDING_PROCESSES.lock().unwrap();
+    prune_finished_processes(&mut processes);
+    processes.contains_key(&source)
+}
+
+/// 优雅停止所有匹配的录制进程
+fn stop_matching(matches: impl Fn(&RecordingSource) -> bool) {
+    let stopping: Vec<(RecordingSource, Child)> = {
+        let mut processes = RECORDING_PROCESSES.lock().unwrap();
+        prune_finished_processes(&mut processes);
+        let sources: Vec<RecordingSource> = processes
+            .keys()
+            .copied()
+            .filter(|source| matches(source))
+            .collect();
+        sources
+            .into_iter()
+            .filter_map(|source| processes.remove(&source).map(|child| (source, child)))
+            .collect()
+    };
+
+    if stopping.is_empty() {
+        log::info!("没有正在运行的ffmpeg录制进程");
+        return;
+    }
+
+    for (source, mut child) in stopping {
+        log::info!("正在停止 {} 录制进程 (PID: {})...", source, child.id());
+        crate::process_utils::stop_ffmpeg_gracefully(
+            &mut child,
+            &source.to_string(),
+            crate::constants::FFMPEG_GRACEFUL_STOP_TIMEOUT,
+        );
+    }
+}
+
+/// 停止指定来源的录制
+pub fn stop_recording(source: RecordingSource) {
+    stop_matching(|candidate| *candidate == source);
+}
+
+/// 停止所有摄像头录像与屏幕录制
+pub fn stop_all_recordings() {
+    stop_matching(|_| true);
+}
+
+/// 屏幕上的矩形区域（虚拟桌面坐标）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ScreenRect {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+impl ScreenRect {
+    fn contains(&self, other: &ScreenRect) -> bool {
+        let right = i64::from(self.x) + i64::from(self.width);
+        let bottom = i64::from(self.y) + i64::from(self.height);
+        other.x >= self.x
+            && other.y >= self.y
+            && i64::from(other.x) + i64::from(other.width) <= right
+            && i64::from(other.y) + i64::from(other.height) <= bottom
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn virtual_screen_bounds() -> Option<ScreenRect> {
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetSystemMetrics, SM_CXVIRTUALSCREEN, SM_CYVIRTUALSCREEN, SM_XVIRTUALSCREEN,
+        SM_YVIRTUALSCREEN,
+    };
+
+    let (x, y, width, height) = unsafe {
+        (
+            GetSystemMetrics(SM_XVIRTUALSCREEN),
+            GetSystemMetrics(SM_YVIRTUALSCREEN),
+            GetSystemMetrics(SM_CXVIRTUALSCREEN),
+            GetSystemMetrics(SM_CYVIRTUALSCREEN),
+        )
+    };
+    screen_rect_from_metrics(x, y, width, height)
+}
+
+#[cfg(target_os = "windows")]
+fn primary_monitor_bounds() -> Option<ScreenRect> {
+    use windows::Win32::UI::WindowsAndMessaging::{GetSystemMetrics, SM_CXSCREEN, SM_CYSCREEN};
+
+    let (width, height) = unsafe { (GetSystemMetrics(SM_CXSCREEN), GetSystemMetrics(SM_CYSCREEN)) };
+    screen_rect_from_metrics(0, 0, width, height)
+}
+
+/// 按系统枚举顺序列出各显示器在虚拟桌面中的范围
+#[cfg(target_os = "windows")]
+fn monitor_bounds() -> Vec<ScreenRect> {
+    use windows::Win32::Foundation::{LPARAM, RECT};
+    use windows::Win32::Graphics::Gdi::{EnumDisplayMonitors, HDC, HMONITOR};
+    use windows::core::BOOL;
+
+    unsafe extern "system" fn collect(
+        _monitor: HMONITOR,
+        _hdc: HDC,
+        rect: *mut RECT,
+        data: LPARAM,
+    ) -> BOOL {
+        let monitors = unsafe { &mut *(data.0 as *mut Vec<ScreenRect>) };
+        if let Some(rect) = unsafe { rect.as_ref() } {
+            if let Some(bounds) = screen_rect_from_metrics(
+                rect.left,
+                rect.top,
+                rect.right - rect.left,
+                rect.bottom - rect.top,
+            ) {
+                monitors.push(bounds);
             }
-            Err(error) => {
-                log::error!("检查 ffmpeg 录制进程状态失败: {}", error);
-                should_clear = true;
-                false
+        }
+        true.into()
+    }
+
+    let mut monitors: Vec<ScreenRect> = Vec::new();
+    let enumerated = unsafe {
+        EnumDisplayMonitors(
+            None,
+            None,
+            Some(collect),
+            LPARAM(&mut monitors as *mut Vec<ScreenRect> as isize),
+        )
+    };
+    if !enumerated.as_bool() {
+        log::warn!("枚举显示器失败");
+    }
+    monitors
+}
+
+/// 非Windows平台不支持 gdigrab，区域查询返回空
+#[cfg(not(target_os = "windows"))]
+fn virtual_screen_bounds() -> Option<ScreenRect> {
+    None
+}
+
+#[cfg(not(target_os = "windows"))]
+fn primary_monitor_bounds() -> Option<ScreenRect> {
+    None
+}
+
+#[cfg(not(target_os = "windows"))]
+fn monitor_bounds() -> Vec<ScreenRect> {
+    Vec::new()
+}
+
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn screen_rect_from_metrics(x: i32, y: i32, width: i32, height: i32) -> Option<ScreenRect> {
+    if width <= 0 || height <= 0 {
+        return None;
+    }
+
+    Some(ScreenRect {
+        x,
+        y,
+        width: width as u32,
+        height: height as u32,
+    })
+}
+
+/// 将录制区域配置解析为具体矩形；返回 `None` 表示录制整个桌面
+fn resolve_recording_rect(
+    region: RecordingRegion,
+    virtual_bounds: Option<ScreenRect>,
+    primary_bounds: Option<ScreenRect>,
+    monitors: &[ScreenRect],
+) -> Option<ScreenRect> {
+    let requested = match region {
+        RecordingRegion::FullDesktop => return None,
+        RecordingRegion::PrimaryMonitor => primary_bounds?,
+        RecordingRegion::Monitor { index } => match monitors.get(index as usize) {
+            Some(bounds) => *bounds,
+            None => {
+                log::warn!(
+                    "显示器 {} 不存在（共检测到 {} 个），回退到整个桌面",
+                    index,
+                    monitors.len()
+                );
+                return None;
             }
         },
-        None => false,
+        // libx264 要求宽高为偶数，奇数尺寸向下取偶
+        RecordingRegion::Custom {
+            x,
+            y,
+            width,
+            height,
+        } => ScreenRect {
+            x,
+            y,
+            width: width & !1,
+            height: height & !1,
+        },
     };
 
-    if should_clear {
-        *process_guard = None;
+    if requested.width == 0 || requested.height == 0 {
+        log::warn!("录制区域尺寸无效 {:?}，回退到整个桌面", requested);
+        return None;
+    }
+
+    match virtual_bounds {
+        Some(bounds) if bounds.contains(&requested) => Some(requested),
+        Some(bounds) => {
+            log::warn!(
+                "录制区域 {:?} 超出虚拟屏幕范围 {:?}，回退到整个桌面",
+                requested,
+                bounds
+            );
+            None
+        }
+        None => {
+            log::warn!("无法获取虚拟屏幕范围，回退到整个桌面");
+            None
+        }
     }
+}
 
-    is_running
+/// 屏幕录制的帧率、缩放与码率
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RecordingQuality {
+    framerate: u32,
+    scale_divisor: u32,
+    bitrate_kbps: u32,
 }
 
-pub fn is_screen_recording_running() -> bool {
-    let mut process_guard = FFMPEG_PROCESS.lock().unwrap();
-    refresh_screen_recording_state(&mut process_guard)
+impl RecordingQuality {
+    fn from_state(state: &crate::state::AppState) -> Self {
+        Self {
+            framerate: state.recording_framerate(),
+            scale_divisor: state.recording_scale_divisor(),
+            bitrate_kbps: state.recording_bitrate_kbps(),
+        }
+    }
+
+    /// 生成 libx264 编码参数；峰值码率为目标码率的 1.5 倍
+    fn encoder_args(&self) -> Vec<String> {
+        let mut args = vec![
+            "-c:v".to_string(),
+            "libx264".to_string(),
+            "-preset".to_string(),
+            "ultrafast".to_string(),
+        ];
+        if self.scale_divisor > 1 {
+            args.push("-vf".to_string());
+            // 先按偶数取整宽度，否则 1366/3 等奇数宽度会被 libx264 拒绝
+            args.push(format!("scale=trunc(iw/{}/2)*2:-2", self.scale_divisor));
+        }
+        args.extend([
+            "-b:v".to_string(),
+            format!("{}k", self.bitrate_kbps),
+            "-maxrate".to_string(),
+            format!("{}k", self.bitrate_kbps * 3 / 2),
+            "-bufsize".to_string(),
+            format!("{}k", self.bitrate_kbps),
+        ]);
+        args
+    }
 }
 
-/// 启动屏幕录制并拍照
-pub async fn start_screen_recording(app_handle: AppHandle) -> Result<(), String> {
-    start_screen_recording_with_options(app_handle, true).await
+/// 生成 gdigrab 的区域参数（需位于 `-i desktop` 之前）
+fn gdigrab_region_args(rect: Option<ScreenRect>) -> Vec<String> {
+    match rect {
+        Some(rect) => vec![
+            "-offset_x".to_string(),
+            rect.x.to_string(),
+            "-offset_y".to_string(),
+            rect.y.to_string(),
+            "-video_size".to_string(),
+            format!("{}x{}", rect.width, rect.height),
+        ],
+        None => Vec::new(),
+    }
+}
+
+pub fn is_screen_recording_running() -> bool {
+    is_recording(RecordingSource::Screen)
 }
 
 pub async fn start_screen_recording_with_options(
     app_handle: AppHandle,
     capture_photo: bool,
 ) -> Result<(), String> {
+    launch_screen_recording(app_handle, capture_photo, None)
+        .await
+        .map(|_| ())
+}
+
+/// 录制固定时长的屏幕（`capture_photo` 为 true 时先拍照），录制在 ffmpeg 达到时长后自行结束。
+/// 返回录像文件路径。
+pub async fn start_fixed_duration_recording(
+    app_handle: AppHandle,
+    duration_seconds: u32,
+    capture_photo: bool,
+) -> Result<String, String> {
+    log::info!("启动固定时长屏幕录制: {}秒", duration_seconds);
+    launch_screen_recording(app_handle, capture_photo, Some(duration_seconds))
+        .await?
+        .ok_or_else(|| "屏幕录制已在进行中".to_string())
+}
+
+/// 手动开始屏幕录制（不拍照、不限时长），返回输出文件路径
+pub async fn start_manual_recording(app_handle: AppHandle) -> Result<String, String> {
+    let output_path = launch_screen_recording(app_handle, false, None)
+        .await?
+        .ok_or_else(|| "屏幕录制已在进行中".to_string())?;
+    SCREEN_RECORDING_MANUAL.store(true, Ordering::SeqCst);
+    Ok(output_path)
+}
+
+/// 当前是否有手动开始的屏幕录制在运行
+pub fn is_manual_recording_running() -> bool {
+    SCREEN_RECORDING_MANUAL.load(Ordering::SeqCst) && is_screen_recording_running()
+}
+
+/// 停止手动开始的屏幕录制；监控流程启动的录制不受影响
+pub fn stop_manual_recording() {
+    if SCREEN_RECORDING_MANUAL.swap(false, Ordering::SeqCst) {
+        stop_recording(RecordingSource::Screen);
+    }
+}
+
+/// 等待当前屏幕录制进程结束
+pub async fn wait_for_screen_recording() {
+    while is_screen_recording_running() {
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    }
+}
+
+/// 启动 ffmpeg 屏幕录制；`duration_seconds` 为 `Some` 时以 `-t` 限定时长且不分段。
+/// 新启动录制时返回输出文件路径，已有录制在运行时返回 `None`。
+async fn launch_screen_recording(
+    app_handle: AppHandle,
+    capture_photo: bool,
+    duration_seconds: Option<u32>,
+) -> Result<Option<String>, String> {
     if SCREEN_RECORDING_STARTING
         .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
         .is_err()
     {
         log::info!("屏幕录制启动已在进行中，跳过重复请求");
-        return Ok(());
+        return Ok(None);
     }
 
     struct StartGuard;
@@ -108,35 +482,56 @@ pub async fn start_screen_recording_with_options(
 
     if is_screen_recording_running() {
         log::warn!("录制进程已在运行，跳过启动请求");
-        return Ok(());
+        return Ok(None);
     }
 
-    let (camera_id, save_path, effective_save_path) = {
+    let save_path = crate::storage::resolve_capture_save_path(&app_handle);
+    let (camera_id, max_segment_seconds, recording_region, photo_options, audio_device, quality) = {
         let state = app_handle.state::<crate::state::AppState>();
         (
             state.camera_id(),
-            state.save_path(),
-            state.get_effective_save_path(),
+            state.max_segment_seconds(),
+            state.recording_region(),
+            crate::camera::PhotoOptions::from_state(&state),
+            state.audio_device(),
+            RecordingQuality::from_state(&state),
         )
     };
+    let audio_device = available_audio_device(&app_handle, audio_device).await;
+    let effective_save_path = save_path
+        .clone()
+        .unwrap_or_else(crate::config::get_default_save_path);
 
     if capture_photo {
         log::info!("开始拍照后启动屏幕录制...");
-        if let Err(error) = crate::camera::take_photo(camera_id, save_path).await {
+        if let Err(error) =
+            crate::camera::take_photo(&app_handle, camera_id, save_path, photo_options).await
+        {
             log::error!("拍照失败: {}", error);
         } else {
             log::info!("拍照完成");
         }
     }
 
-    let mut process_guard = FFMPEG_PROCESS.lock().unwrap();
-    if refresh_screen_recording_state(&mut process_guard) {
+    let mut processes = RECORDING_PROCESSES.lock().unwrap();
+    prune_finished_processes(&mut processes);
+    if processes.contains_key(&RecordingSource::Screen) {
         log::warn!("录制进程已在运行，跳过启动请求");
-        return Ok(());
+        return Ok(None);
     }
 
+    // 固定时长录制输出单个文件，不使用分段
+    let max_segment_seconds = if duration_seconds.is_some() {
+        0
+    } else {
+        max_segment_seconds
+    };
     let timestamp = Local::now().format("%Y%m%d_%H%M%S").to_string();
-    let output_filename = format!("{}.mkv", timestamp);
+    let output_filename = if max_segment_seconds > 0 {
+        format!("{}_%03d.mkv", timestamp)
+    } else {
+        format!("{}.mkv", timestamp)
+    };
     let output_path = std::path::Path::new(&effective_save_path).join(output_filename);
     let output_path_str = output_path
         .to_str()
@@ -144,59 +539,82 @@ pub async fn start_screen_recording_with_options(
 
     log::info!("准备启动屏幕录制，保存至: {:?}", output_path);
 
-    let ffmpeg_path = match app_handle.path().resolve(
-        "libs/ffmpeg/bin/ffmpeg.exe",
-        tauri::path::BaseDirectory::Resource,
-    ) {
-        Ok(path) => path,
-        Err(e) => return Err(format!("无法解析ffmpeg路径: {}", e)),
-    };
+    let ffmpeg_path = crate::ffmpeg::resolve(&app_handle)?;
+
+    let monitors = monitor_bounds();
+    for (index, bounds) in monitors.iter().enumerate() {
+        log::info!("检测到显示器 {}: {:?}", index, bounds);
+    }
+    let recording_rect = resolve_recording_rect(
+        recording_region,
+        virtual_screen_bounds(),
+        primary_monitor_bounds(),
+        &monitors,
+    );
+    if let Some(rect) = recording_rect {
+        log::info!("屏幕录制区域: {:?}", rect);
+    }
 
     let mut command = Command::new(ffmpeg_path);
+    log::info!(
+        "屏幕录制参数: {} 帧/秒, 缩小 {} 倍, {} kbps",
+        quality.framerate,
+        quality.scale_divisor,
+        quality.bitrate_kbps
+    );
     command.args([
         "-f",
         "gdigrab",
         "-framerate",
-        "30",
-        "-i",
-        "desktop",
-        "-c:v",
-        "libx264",
-        "-preset",
-        "ultrafast",
-        "-vf",
-        "scale=iw/2:-2",
-        "-b:v",
-        "2000k",
-        "-maxrate",
-        "3000k",
-        "-bufsize",
-        "2000k",
-        output_path_str,
+        &quality.framerate.to_string(),
     ]);
+    command.args(gdigrab_region_args(recording_rect));
+    command.args(["-i", "desktop"]);
+    if let Some(audio_device) = &audio_device {
+        log::info!("屏幕录制同时录制麦克风: {}", audio_device);
+        command.args(["-f", "dshow", "-i"]);
+        command.arg(format!("audio={}", audio_device));
+        command.args(["-c:a", "aac", "-b:a", "128k"]);
+    }
+    command.args(quality.encoder_args());
+    if let Some(duration_seconds) = duration_seconds {
+        command.args(["-t", &duration_seconds.to_string()]);
+    }
+    if max_segment_seconds > 0 {
+        log::info!("屏幕录制启用分段，每段最长 {} 秒", max_segment_seconds);
+        command.args([
+            "-f",
+            "segment",
+            "-segment_time",
+            &max_segment_seconds.to_string(),
+            "-segment_format",
+            "matroska",
+            "-reset_timestamps",
+            "1",
+        ]);
+    }
+    command.arg(output_path_str);
     crate::process_utils::configure_background_command(&mut command);
+    // 保留标准输入以便通过 `q` 指令优雅停止，确保输出文件被正确封装
+    command.stdin(Stdio::piped());
     #[cfg(all(windows, not(debug_assertions)))]
     {
-        command
-            .stdin(Stdio::null())
-            .stdout(Stdio::null())
-            .stderr(Stdio::null());
+        command.stdout(Stdio::null()).stderr(Stdio::null());
     }
 
     match command.spawn() {
-        Ok(mut child) => {
+        Ok(child) => {
             log::info!("ffmpeg进程已成功启动，PID: {}", child.id());
-            if let Err(error) = crate::process_utils::assign_child_to_kill_on_close_job(&mut child)
-            {
-                log::error!("无法将 ffmpeg 进程纳入 Job Object: {}", error);
-                crate::process_utils::terminate_child_process(&mut child, "ffmpeg");
+            if let Err(error) = track_process(&mut processes, RecordingSource::Screen, child) {
                 mark_screen_recording_failure();
                 return Err(error);
             }
 
-            *process_guard = Some(child);
             clear_screen_recording_failure();
-            Ok(())
+            SCREEN_RECORDING_CONTINUOUS.store(duration_seconds.is_none(), Ordering::SeqCst);
+            SCREEN_RECORDING_MANUAL.store(false, Ordering::SeqCst);
+            SCREEN_RECORDING_EXITED_UNEXPECTEDLY.store(false, Ordering::SeqCst);
+            Ok(Some(output_path_str.to_string()))
         }
         Err(error) => {
             let err_msg = format!("启动ffmpeg失败: {}", error);
@@ -207,18 +625,433 @@ pub async fn start_screen_recording_with_options(
     }
 }
 
-/// 停止屏幕录制
-pub fn stop_screen_recording() {
-    let mut process_guard = FFMPEG_PROCESS.lock().unwrap();
-    if !refresh_screen_recording_state(&mut process_guard) {
-        log::info!("没有正在运行的ffmpeg录制进程");
-        return;
+/// 确认所选麦克风仍然存在；设备已拔出时只录制画面，避免 ffmpeg 因找不到音频设备而无法启动。
+/// 未选择麦克风时使用枚举到的第一个音频输入设备（即系统默认设备）。
+async fn available_audio_device(app_handle: &AppHandle, device: Option<String>) -> Option<String> {
+    let list_handle = app_handle.clone();
+    let devices =
+        tokio::task::spawn_blocking(move || crate::ffmpeg::list_audio_input_devices(&list_handle))
+            .await
+            .map_err(|e| format!("Task join error: {}", e))
+            .and_then(|result| result);
+
+    match (device, devices) {
+        (Some(device), Ok(devices)) if devices.contains(&device) => Some(device),
+        (Some(device), Ok(_)) => {
+            log::warn!("未找到麦克风 {}，本次屏幕录制不录制音频", device);
+            None
+        }
+        (Some(device), Err(error)) => {
+            // 无法确认时仍按配置尝试录音
+            log::warn!("无法确认麦克风是否可用: {}", error);
+            Some(device)
+        }
+        (None, Ok(devices)) => {
+            let default_device = devices.into_iter().next();
+            if let Some(device) = &default_device {
+                log::info!("未选择麦克风，使用默认设备: {}", device);
+            }
+            default_device
+        }
+        (None, Err(error)) => {
+            log::warn!("无法枚举麦克风，本次屏幕录制不录制音频: {}", error);
+            None
+        }
     }
+}
+
+/// 停止屏幕录制；优雅停止 ffmpeg 最多需要数秒，在阻塞线程中执行
+pub async fn stop_screen_recording() -> Result<(), String> {
+    tokio::task::spawn_blocking(|| stop_recording(RecordingSource::Screen))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+}
+
+/// 开始摄像头录像，`duration_seconds` 未指定时录制 5 秒
+pub async fn start_webcam_recording(
+    app_handle: AppHandle,
+    camera_id: u32,
+    save_path: Option<String>,
+    duration_seconds: Option<u32>,
+) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        crate::camera::validate_camera_id(camera_id)?;
+        let source = RecordingSource::Webcam(camera_id);
+        if is_recording(source) {
+            return Err(format!("Camera {} is already recording", camera_id));
+        }
+
+        let base_path = crate::camera::get_save_path(save_path)?;
+        let timestamp = Local::now().format("%Y%m%d_%H%M%S");
+        let filepath = base_path.join(format!("snaplock_video_{}.mkv", timestamp));
+
+        let ffmpeg_path = crate::ffmpeg::resolve(&app_handle)?;
+        let mut command =
+            webcam_recording_command(&ffmpeg_path, camera_id, &filepath, duration_seconds)?;
+
+        let mut processes = RECORDING_PROCESSES.lock().unwrap();
+        prune_finished_processes(&mut processes);
+        if processes.contains_key(&source) {
+            return Err(format!("Camera {} is already recording", camera_id));
+        }
+
+        let child = command.spawn().map_err(|e| {
+            format!(
+                "Failed to start video recording: {}. Command: {:?}",
+                e, command
+            )
+        })?;
+        track_process(&mut processes, source, child)?;
 
-    if let Some(mut child) = process_guard.take() {
-        log::info!("正在停止ffmpeg录制进程 (PID: {})...", child.id());
-        crate::process_utils::terminate_child_process(&mut child, "ffmpeg");
+        log::info!(
+            "摄像头 {} 开始录像，保存至: {}",
+            camera_id,
+            filepath.display()
+        );
+        Ok(filepath.to_string_lossy().to_string())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// 构建摄像头录像命令（Windows 使用 dshow，其他平台使用 v4l2）
+fn webcam_recording_command(
+    ffmpeg_path: &Path,
+    camera_id: u32,
+    filepath: &Path,
+    duration_seconds: Option<u32>,
+) -> Result<Command, String> {
+    let duration = duration_seconds.unwrap_or(5);
+    let mut command = Command::new(ffmpeg_path);
+
+    if cfg!(target_os = "windows") {
+        let camera_info = crate::camera::validate_camera_id(camera_id)?;
+        command.args(["-f", "dshow", "-i"]);
+        command.arg(format!("video={}", camera_info.human_name()));
     } else {
-        log::info!("没有正在运行的ffmpeg录制进程");
+        command.args(["-f", "v4l2", "-i"]);
+        command.arg(format!("/dev/video{}", camera_id));
+    }
+    command
+        .args([
+            "-c:v",
+            "libx264",
+            "-preset",
+            "ultrafast",
+            "-crf",
+            "25",
+            "-pix_fmt",
+            "yuv420p",
+            "-t",
+        ])
+        .arg(duration.to_string())
+        .arg("-y")
+        .arg(filepath);
+
+    log::debug!("摄像头录像命令: {:?}", command);
+    crate::process_utils::configure_background_command(&mut command);
+    // 保留标准输入以便通过 `q` 指令优雅停止，确保输出文件被正确封装
+    command.stdin(Stdio::piped());
+    #[cfg(all(windows, not(debug_assertions)))]
+    {
+        command.stdout(Stdio::null()).stderr(Stdio::null());
+    }
+
+    #[cfg(not(all(windows, not(debug_assertions))))]
+    {
+        command.stdout(Stdio::piped()).stderr(Stdio::piped());
+    }
+
+    Ok(command)
+}
+
+/// 抓取一帧桌面画面保存为 `snaplock_screen_<时间>.png`，返回文件路径
+pub async fn capture_screenshot(
+    app_handle: AppHandle,
+    save_path: Option<String>,
+) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        let base_path = crate::camera::get_save_path(save_path)?;
+        let timestamp = Local::now().format("%Y%m%d_%H%M%S");
+        let filepath = base_path.join(format!("snaplock_screen_{}.png", timestamp));
+
+        let ffmpeg_path = crate::ffmpeg::resolve(&app_handle)?;
+        let mut command = screenshot_command(&ffmpeg_path, &filepath);
+        let mut child = command
+            .spawn()
+            .map_err(|e| format!("无法启动截图进程: {}", e))?;
+
+        // 桌面无法访问时 ffmpeg 可能一直挂起，超时后终止进程，不能拖住后续锁屏
+        let deadline = Instant::now() + SCREENSHOT_TIMEOUT;
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) if Instant::now() < deadline => {
+                    std::thread::sleep(Duration::from_millis(50));
+                }
+                Ok(None) => {
+                    crate::process_utils::terminate_child_process(&mut child, "截图");
+                    return Err(format!("桌面截图超时 ({:?})", SCREENSHOT_TIMEOUT));
+                }
+                Err(error) => {
+                    crate::process_utils::terminate_child_process(&mut child, "截图");
+                    return Err(format!("等待截图进程失败: {}", error));
+                }
+            }
+        };
+
+        if !status.success() || !filepath.exists() {
+            let mut stderr = String::new();
+            if let Some(mut pipe) = child.stderr.take() {
+                let _ = pipe.read_to_string(&mut stderr);
+            }
+            return Err(format!("桌面截图失败 ({}): {}", status, stderr.trim()));
+        }
+
+        log::info!("桌面截图已保存: {}", filepath.display());
+        Ok(filepath.to_string_lossy().to_string())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// 构建单帧桌面截图命令（Windows 使用 gdigrab，其他平台使用 x11grab）
+fn screenshot_command(ffmpeg_path: &Path, filepath: &Path) -> Command {
+    let mut command = Command::new(ffmpeg_path);
+    // 只输出错误信息，避免 stderr 管道在进程退出前写满
+    command.args(["-hide_banner", "-loglevel", "error"]);
+    if cfg!(target_os = "windows") {
+        command.args(["-f", "gdigrab", "-i", "desktop"]);
+    } else {
+        let display = std::env::var("DISPLAY").unwrap_or_else(|_| ":0".to_string());
+        command.args(["-f", "x11grab", "-i"]).arg(display);
+    }
+    command.args(["-frames:v", "1", "-y"]).arg(filepath);
+    crate::process_utils::configure_background_command(&mut command);
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+    command
+}
+
+/// 停止所有摄像头录像
+pub async fn stop_webcam_recordings() -> Result<(), String> {
+    tokio::task::spawn_blocking(|| {
+        stop_matching(|source| matches!(source, RecordingSource::Webcam(_)))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RecordingQuality, ScreenRect, gdigrab_region_args, resolve_recording_rect};
+    use crate::config::RecordingRegion;
+
+    const VIRTUAL: ScreenRect = ScreenRect {
+        x: -1920,
+        y: 0,
+        width: 5760,
+        height: 1080,
+    };
+    const PRIMARY: ScreenRect = ScreenRect {
+        x: 0,
+        y: 0,
+        width: 1920,
+        height: 1080,
+    };
+
+    #[test]
+    fn full_desktop_produces_no_region_args() {
+        let rect = resolve_recording_rect(
+            RecordingRegion::FullDesktop,
+            Some(VIRTUAL),
+            Some(PRIMARY),
+            &[],
+        );
+        assert_eq!(rect, None);
+        assert!(gdigrab_region_args(rect).is_empty());
+    }
+
+    #[test]
+    fn primary_monitor_uses_primary_bounds() {
+        let rect = resolve_recording_rect(
+            RecordingRegion::PrimaryMonitor,
+            Some(VIRTUAL),
+            Some(PRIMARY),
+            &[],
+        );
+        assert_eq!(rect, Some(PRIMARY));
+        assert_eq!(
+            gdigrab_region_args(rect),
+            vec![
+                "-offset_x",
+                "0",
+                "-offset_y",
+                "0",
+                "-video_size",
+                "1920x1080"
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_region_inside_virtual_screen_is_kept() {
+        let region = RecordingRegion::Custom {
+            x: -1920,
+            y: 100,
+            width: 800,
+            height: 600,
+        };
+        let rect = resolve_recording_rect(region, Some(VIRTUAL), Some(PRIMARY), &[]);
+        assert_eq!(
+            rect,
+            Some(ScreenRect {
+                x: -1920,
+                y: 100,
+                width: 800,
+                height: 600,
+            })
+        );
+    }
+
+    #[test]
+    fn odd_custom_region_is_rounded_down_to_even() {
+        let region = RecordingRegion::Custom {
+            x: 0,
+            y: 0,
+            width: 801,
+            height: 601,
+        };
+        let rect = resolve_recording_rect(region, Some(VIRTUAL), Some(PRIMARY), &[]).unwrap();
+        assert_eq!((rect.width, rect.height), (800, 600));
+    }
+
+    #[test]
+    fn invalid_custom_region_falls_back_to_full_desktop() {
+        let outside = RecordingRegion::Custom {
+            x: 3000,
+            y: 500,
+            width: 1000,
+            height: 1000,
+        };
+        let empty = RecordingRegion::Custom {
+            x: 0,
+            y: 0,
+            width: 0,
+            height: 600,
+        };
+
+        assert_eq!(
+            resolve_recording_rect(outside, Some(VIRTUAL), Some(PRIMARY), &[]),
+            None
+        );
+        assert_eq!(
+            resolve_recording_rect(empty, Some(VIRTUAL), Some(PRIMARY), &[]),
+            None
+        );
+        assert_eq!(resolve_recording_rect(outside, None, None, &[]), None);
+    }
+
+    #[test]
+    fn monitor_index_selects_enumerated_monitor() {
+        let left = ScreenRect {
+            x: -1920,
+            y: 0,
+            width: 1920,
+            height: 1080,
+        };
+        let monitors = [PRIMARY, left];
+        let rect = resolve_recording_rect(
+            RecordingRegion::Monitor { index: 1 },
+            Some(VIRTUAL),
+            Some(PRIMARY),
+            &monitors,
+        );
+        assert_eq!(rect, Some(left));
+        assert_eq!(
+            gdigrab_region_args(rect),
+            vec![
+                "-offset_x",
+                "-1920",
+                "-offset_y",
+                "0",
+                "-video_size",
+                "1920x1080"
+            ]
+        );
+
+        // 显示器已拔除时回退到整个桌面
+        assert_eq!(
+            resolve_recording_rect(
+                RecordingRegion::Monitor { index: 2 },
+                Some(VIRTUAL),
+                Some(PRIMARY),
+                &monitors,
+            ),
+            None
+        );
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn webcam_command_records_requested_device_and_duration() {
+        let command = super::webcam_recording_command(
+            std::path::Path::new("ffmpeg"),
+            2,
+            std::path::Path::new("out.mkv"),
+            Some(7),
+        )
+        .unwrap();
+        let args: Vec<String> = command
+            .get_args()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect();
+
+        assert!(args.windows(2).any(|pair| pair == ["-i", "/dev/video2"]));
+        assert!(args.windows(2).any(|pair| pair == ["-t", "7"]));
+        assert_eq!(args.last().map(String::as_str), Some("out.mkv"));
+    }
+
+    #[test]
+    fn screenshot_command_grabs_a_single_frame() {
+        let command = super::screenshot_command(
+            std::path::Path::new("ffmpeg"),
+            std::path::Path::new("snaplock_screen.png"),
+        );
+        let args: Vec<String> = command
+            .get_args()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect();
+
+        assert!(args.windows(2).any(|pair| pair == ["-frames:v", "1"]));
+        assert_eq!(args.last().map(String::as_str), Some("snaplock_screen.png"));
+    }
+
+    #[test]
+    fn recording_quality_builds_encoder_args() {
+        let default_quality = RecordingQuality {
+            framerate: 30,
+            scale_divisor: 2,
+            bitrate_kbps: 2000,
+        };
+        let args = default_quality.encoder_args();
+        assert!(
+            args.windows(2)
+                .any(|pair| pair == ["-vf", "scale=trunc(iw/2/2)*2:-2"])
+        );
+        assert!(args.windows(2).any(|pair| pair == ["-b:v", "2000k"]));
+        assert!(args.windows(2).any(|pair| pair == ["-maxrate", "3000k"]));
+
+        // 不缩放时不添加滤镜
+        let full_size = RecordingQuality {
+            scale_divisor: 1,
+            bitrate_kbps: 8000,
+            ..default_quality
+        };
+        let args = full_size.encoder_args();
+        assert!(!args.iter().any(|arg| arg == "-vf"));
+        assert!(args.windows(2).any(|pair| pair == ["-bufsize", "8000k"]));
     }
 }
@@ -0,0 +1,151 @@
+//! 写入 config.json 的凭据密码：Windows 上使用 DPAPI 按当前用户加密后保存，
+//! 其他平台原样保存。读取时兼容旧版本写入的明文密码，下次保存配置时自动加密。
+
+use base64::{Engine as _, engine::general_purpose};
+use serde::{Deserialize, Deserializer, Serializer};
+
+/// 加密后的密码以该前缀标记，没有前缀的值视为明文
+const PROTECTED_PREFIX: &str = "dpapi:";
+
+/// 将密码转换为写入配置文件的形式
+pub fn protect(secret: &str) -> Result<String, String> {
+    if secret.is_empty() || !cfg!(target_os = "windows") {
+        return Ok(secret.to_string());
+    }
+
+    let encrypted = dpapi_protect(secret.as_bytes())?;
+    Ok(format!(
+        "{}{}",
+        PROTECTED_PREFIX,
+        general_purpose::STANDARD.encode(encrypted)
+    ))
+}
+
+/// 从配置文件中的值还原密码
+pub fn unprotect(stored: &str) -> Result<String, String> {
+    let Some(encoded) = stored.strip_prefix(PROTECTED_PREFIX) else {
+        return Ok(stored.to_string());
+    };
+
+    let encrypted = general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|e| format!("密码数据格式错误: {}", e))?;
+    let decrypted = dpapi_unprotect(&encrypted)?;
+    String::from_utf8(decrypted).map_err(|e| format!("密码数据格式错误: {}", e))
+}
+
+/// 供 `#[serde(with = "crate::secret_store::protected")]` 使用
+pub mod protected {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(secret: &str, serializer: S) -> Result<S::Ok, S::Error> {
+        let stored = protect(secret).map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(&stored)
+    }
+
+    /// 无法解密（例如配置文件复制自其他用户）时按未设置密码处理，不影响其他配置加载
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+        let stored = String::deserialize(deserializer)?;
+        Ok(unprotect(&stored).unwrap_or_else(|error| {
+            log::warn!("无法解密配置中保存的密码，需要重新设置: {}", error);
+            String::new()
+        }))
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn dpapi_protect(data: &[u8]) -> Result<Vec<u8>, String> {
+    use windows::Win32::Security::Cryptography::{
+        CRYPT_INTEGER_BLOB, CRYPTPROTECT_UI_FORBIDDEN, CryptProtectData,
+    };
+    use windows::core::PCWSTR;
+
+    let input = CRYPT_INTEGER_BLOB {
+        cbData: data.len() as u32,
+        pbData: data.as_ptr() as *mut u8,
+    };
+    let mut output = CRYPT_INTEGER_BLOB::default();
+    unsafe {
+        CryptProtectData(
+            &input,
+            PCWSTR::null(),
+            None,
+            None,
+            None,
+            CRYPTPROTECT_UI_FORBIDDEN,
+            &mut output,
+        )
+    }
+    .map_err(|e| format!("加密密码失败: {}", e))?;
+
+    Ok(take_blob(output))
+}
+
+#[cfg(target_os = "windows")]
+fn dpapi_unprotect(data: &[u8]) -> Result<Vec<u8>, String> {
+    use windows::Win32::Security::Cryptography::{
+        CRYPT_INTEGER_BLOB, CRYPTPROTECT_UI_FORBIDDEN, CryptUnprotectData,
+    };
+
+    let input = CRYPT_INTEGER_BLOB {
+        cbData: data.len() as u32,
+        pbData: data.as_ptr() as *mut u8,
+    };
+    let mut output = CRYPT_INTEGER_BLOB::default();
+    unsafe {
+        CryptUnprotectData(
+            &input,
+            None,
+            None,
+            None,
+            None,
+            CRYPTPROTECT_UI_FORBIDDEN,
+            &mut output,
+        )
+    }
+    .map_err(|e| format!("解密密码失败: {}", e))?;
+
+    Ok(take_blob(output))
+}
+
+/// 复制 DPAPI 分配的输出缓冲区并释放
+#[cfg(target_os = "windows")]
+fn take_blob(blob: windows::Win32::Security::Cryptography::CRYPT_INTEGER_BLOB) -> Vec<u8> {
+    use windows::Win32::Foundation::{HLOCAL, LocalFree};
+
+    let bytes = unsafe { std::slice::from_raw_parts(blob.pbData, blob.cbData as usize) }.to_vec();
+    unsafe {
+        let _ = LocalFree(Some(HLOCAL(blob.pbData.cast())));
+    }
+    bytes
+}
+
+#[cfg(not(target_os = "windows"))]
+fn dpapi_protect(_data: &[u8]) -> Result<Vec<u8>, String> {
+    Err("当前平台不支持加密保存密码".to_string())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn dpapi_unprotect(_data: &[u8]) -> Result<Vec<u8>, String> {
+    Err("当前平台无法解密 Windows 上保存的密码".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{protect, unprotect};
+
+    #[test]
+    fn legacy_plaintext_password_is_read_as_is() {
+        assert_eq!(unprotect("hunter2"), Ok("hunter2".to_string()));
+        assert_eq!(unprotect(""), Ok(String::new()));
+    }
+
+    #[test]
+    fn protected_password_round_trips() {
+        let stored = protect("hunter2").unwrap();
+        if cfg!(target_os = "windows") {
+            assert!(!stored.contains("hunter2"));
+        }
+        assert_eq!(unprotect(&stored), Ok("hunter2".to_string()));
+    }
+}
@@ -9,6 +9,7 @@ use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
 #[cfg(target_os = "windows")]
 use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowTextW};
 
+use crate::config::UnlockAction;
 use crate::state::{AppState, MonitoringState};
 
 /// Windows会话监控器，用于检测系统锁定/解锁状态
@@ -229,12 +230,27 @@ impl SessionMonitor {
             .clone();
         let _lifecycle_guard = lifecycle_lock.lock_owned().await;
         let current_status = app_handle.state::<AppState>().status();
+        let unlock_action = app_handle.state::<AppState>().on_unlock_action();
 
-        log::info!("当前应用状态: {:?}", current_status);
+        log::info!(
+            "当前应用状态: {:?}，解锁后动作: {:?}",
+            current_status,
+            unlock_action
+        );
 
-        // 停止任何可能在运行的屏幕录制
-        crate::recorder::stop_screen_recording();
-        if let Err(e) = crate::camera::stop_video_recording().await {
+        if unlock_action == UnlockAction::DoNothing {
+            log::info!("已配置解锁后不做处理，保持当前状态");
+            return;
+        }
+
+        // 停止任何可能在运行的屏幕录制与预录缓冲
+        if let Err(e) = tokio::task::spawn_blocking(crate::prebuffer::stop).await {
+            log::error!("停止预录缓冲失败: {}", e);
+        }
+        if let Err(e) = crate::recorder::stop_screen_recording().await {
+            log::error!("停止屏幕录制失败: {}", e);
+        }
+        if let Err(e) = crate::recorder::stop_webcam_recordings().await {
             log::error!("停止摄像头录像失败: {}", e);
         }
 
@@ -275,21 +291,32 @@ impl SessionMonitor {
                 log::info!("已发送状态重置事件到前端");
             }
 
-            // 显示通知
-            #[cfg(target_os = "windows")]
-            {
-                use tauri_plugin_notification::NotificationExt;
-                if app_handle.state::<AppState>().enable_notifications() {
-                    if let Err(e) = app_handle
-                        .notification()
-                        .builder()
-                        .title("SnapLock")
-                        .body("系统已解锁，应用状态已重置")
-                        .show()
-                    {
-                        log::error!("无法显示解锁通知: {}", e);
-                    }
+            // 解锁前处于空闲时不会被重新警戒
+            let should_rearm = match unlock_action {
+                UnlockAction::ReArm => current_status != MonitoringState::Idle,
+                _ => {
+                    app_handle.state::<AppState>().rearm_after_trigger()
+                        && matches!(
+                            current_status,
+                            MonitoringState::Active | MonitoringState::Triggered
+                        )
                 }
+            };
+
+            if should_rearm {
+                // 解锁后重新经过准备期再进入警戒，用户可在准备期内通过快捷键解除
+                crate::notifications::show(
+                    app_handle,
+                    &crate::i18n::tr(app_handle, crate::i18n::Message::UnlockedRearming),
+                );
+                crate::monitoring::schedule_rearm(app_handle.clone());
+            } else {
+                crate::handlers::persist_armed_state(app_handle, false);
+                // 显示通知
+                crate::notifications::show(
+                    app_handle,
+                    &crate::i18n::tr(app_handle, crate::i18n::Message::UnlockedReset),
+                );
             }
         }
     }
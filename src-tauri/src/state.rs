@@ -1,11 +1,18 @@
-use crate::config::{CaptureMode, PostTriggerAction};
+use crate::config::{
+    CameraFourcc, CaptureMode, ImageFormat, LocalApiConfig, MonitoringBackend,
+    NetworkShareCredentials, PostTriggerAction, RecordingRegion, SmtpConfig, TriggerMode,
+    UnlockAction, WindowGeometry,
+};
+use crate::i18n::Language;
+use serde::Serialize;
 use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::task::JoinHandle;
 
 pub type MonitoringLifecycleLock = tokio::sync::Mutex<()>;
 
 /// Represents the monitoring status of the application.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum MonitoringState {
     /// The application is idle and not monitoring for input.
     Idle,
@@ -18,22 +25,57 @@ pub enum MonitoringState {
 }
 
 impl MonitoringState {
+    /// All states, in lifecycle order.
+    pub const ALL: [MonitoringState; 4] = [
+        MonitoringState::Idle,
+        MonitoringState::Preparing,
+        MonitoringState::Active,
+        MonitoringState::Triggered,
+    ];
+
     /// Transitions to a new state, enforcing valid state transitions.
+    ///
+    /// Allowed transitions:
+    /// - `Idle -> Preparing`: the user arms monitoring.
+    /// - `Preparing -> Active`: the preparation delay elapsed.
+    /// - `Active -> Triggered`: input was detected while armed.
+    /// - `* -> Idle`: stopping or resetting is always allowed, including the
+    ///   idempotent `Idle -> Idle` used by cleanup paths.
+    ///
+    /// Everything else is rejected. In particular `Triggered -> Active` must go
+    /// through `Idle -> Preparing` again so re-arming always honours the
+    /// preparation delay, and `Triggered -> Triggered` is rejected so that only
+    /// the first input event starts a lockdown flow.
     pub fn transition_to(
         &self,
         next_state: MonitoringState,
     ) -> Result<MonitoringState, &'static str> {
         match (self, next_state) {
-            (MonitoringState::Idle, MonitoringState::Preparing) => Ok(next_state),
-            (MonitoringState::Preparing, MonitoringState::Active) => Ok(next_state),
-            (MonitoringState::Preparing, MonitoringState::Idle) => Ok(next_state),
-            (MonitoringState::Active, MonitoringState::Triggered) => Ok(next_state),
-            (MonitoringState::Active, MonitoringState::Idle) => Ok(next_state),
-            (MonitoringState::Triggered, MonitoringState::Idle) => Ok(next_state),
-            (_, MonitoringState::Idle) => Ok(next_state),
+            (MonitoringState::Idle, MonitoringState::Preparing)
+            | (MonitoringState::Preparing, MonitoringState::Active)
+            | (MonitoringState::Active, MonitoringState::Triggered)
+            | (_, MonitoringState::Idle) => Ok(next_state),
             _ => Err("Invalid state transition"),
         }
     }
+
+    /// States reachable from this one in a single transition.
+    pub fn valid_next_states(&self) -> Vec<MonitoringState> {
+        Self::ALL
+            .into_iter()
+            .filter(|next_state| self.transition_to(*next_state).is_ok())
+            .collect()
+    }
+
+    /// Label used by the frontend (matches `monitoring_status_changed` payloads).
+    pub fn label(&self) -> &'static str {
+        match self {
+            MonitoringState::Idle => "空闲",
+            MonitoringState::Preparing => "准备中",
+            MonitoringState::Active => "警戒中",
+            MonitoringState::Triggered => "锁定中",
+        }
+    }
 }
 
 /// Holds the shared state of the Tauri application.
@@ -66,6 +108,148 @@ pub struct AppState {
     pub(crate) capture_delay_seconds: Mutex<u32>,
     /// Capture mode setting
     pub(crate) capture_mode: Mutex<CaptureMode>,
+    /// Maximum length of a screen recording segment in seconds (0 = unlimited)
+    pub(crate) max_segment_seconds: Mutex<u32>,
+    /// Minimum free disk space (MB) required before capturing to the save path
+    pub(crate) min_free_mb: Mutex<u64>,
+    /// Keys that never count as intrusion activity while monitoring
+    pub(crate) ignored_keys: Mutex<Vec<String>>,
+    /// Delay in seconds between arming and active monitoring
+    pub(crate) preparation_delay_seconds: Mutex<u32>,
+    /// Custom notification title template ({time}, {hostname})
+    pub(crate) notification_title: Mutex<Option<String>>,
+    /// Custom security alert body template ({time}, {hostname})
+    pub(crate) notification_body: Mutex<Option<String>>,
+    /// Minimum interval in milliseconds between two triggers
+    pub(crate) retrigger_cooldown_ms: Mutex<u64>,
+    /// Flag to re-enter monitoring automatically after a trigger has been handled
+    pub(crate) rearm_after_trigger: Mutex<bool>,
+    /// Flag to launch the application automatically on login
+    pub(crate) autostart_enabled: Mutex<bool>,
+    /// Flag to arm monitoring automatically when the application starts
+    pub(crate) auto_arm_on_start: Mutex<bool>,
+    /// Flag to hide the window to the tray instead of exiting when it is closed
+    pub(crate) close_to_tray: Mutex<bool>,
+    /// Flag to keep monitoring usable (lock/record only) when no camera is present
+    pub(crate) allow_no_camera: Mutex<bool>,
+    /// Flag to show a notification when the application starts and exits
+    pub(crate) notify_on_startup: Mutex<bool>,
+    /// Screen area captured by screen recording
+    pub(crate) recording_region: Mutex<RecordingRegion>,
+    /// Cameras to capture from on trigger (empty = the selected camera only)
+    pub(crate) capture_camera_ids: Mutex<Vec<u32>>,
+    /// Flag to re-arm automatically on startup if the app was armed before it exited
+    pub(crate) restore_armed_state: Mutex<bool>,
+    /// Whether monitoring was armed when the state was last persisted
+    pub(crate) was_armed: Mutex<bool>,
+    /// JPEG quality (1-100) used for captured photos and previews
+    pub(crate) jpeg_quality: Mutex<u8>,
+    /// Number of retries when the camera is held by another application
+    pub(crate) camera_busy_retry_attempts: Mutex<u32>,
+    /// Base delay in milliseconds between camera busy retries
+    pub(crate) camera_busy_retry_backoff_ms: Mutex<u64>,
+    /// Seconds of webcam footage kept in memory while armed (0 = disabled)
+    pub(crate) prebuffer_seconds: Mutex<u32>,
+    /// Minimum interval in milliseconds between two shortcut toggles
+    pub(crate) shortcut_debounce_ms: Mutex<u64>,
+    /// Delay in milliseconds before the shortcut-in-progress flag is cleared
+    pub(crate) shortcut_flag_clear_delay_ms: Mutex<u64>,
+    /// Window in milliseconds after a shortcut during which input events are ignored
+    pub(crate) event_ignore_window_ms: Mutex<u64>,
+    /// Language of backend notifications, None follows the system locale
+    pub(crate) language: Mutex<Option<Language>>,
+    /// Maximum time in milliseconds to wait for a photo before giving up, 0 disables the limit
+    pub(crate) capture_timeout_ms: Mutex<u64>,
+    /// Fixed screen recording length in seconds after a trigger, 0 keeps the idle-managed recording
+    pub(crate) recording_duration_seconds: Mutex<u32>,
+    /// User-specified ffmpeg executable, None uses the bundled binary or PATH
+    pub(crate) ffmpeg_path: Mutex<Option<String>>,
+    /// Whether monitoring triggers on activity or on prolonged inactivity
+    pub(crate) trigger_mode: Mutex<TriggerMode>,
+    /// Minutes without any input before an idle trigger fires
+    pub(crate) idle_trigger_minutes: Mutex<u32>,
+    /// Whether captures are saved into per-day subfolders
+    pub(crate) organize_by_date: Mutex<bool>,
+    /// Microphone used when recording the screen, None records no audio
+    pub(crate) audio_device: Mutex<Option<String>>,
+    /// 合盖或睡眠时是否视为触发（仅 Windows）
+    pub(crate) trigger_on_lid_close: Mutex<bool>,
+    /// Whether the OS denied notification permission (runtime only, checked at startup)
+    pub(crate) notification_permission_denied: Mutex<bool>,
+    /// Toggle shortcut that is actually registered with the OS (runtime only), None if registration failed
+    pub(crate) active_shortcut: Mutex<Option<String>>,
+    /// Raw input device that monitoring is restricted to, None accepts all devices
+    pub(crate) monitored_device_id: Mutex<Option<String>>,
+    /// Seconds of screen recording captured before RecordAndLock locks the screen
+    pub(crate) record_before_lock_seconds: Mutex<u32>,
+    /// Whether webcam photos are taken concurrently with trigger screen recordings
+    pub(crate) photo_with_screen_recording: Mutex<bool>,
+    /// Action taken when the system is unlocked
+    pub(crate) on_unlock_action: Mutex<UnlockAction>,
+    /// Optional panic shortcut that captures, locks and arms immediately
+    pub(crate) panic_shortcut_key: Mutex<Option<String>>,
+    /// Last known main window position and size
+    pub(crate) window_geometry: Mutex<Option<WindowGeometry>>,
+    /// Hide the main window from the taskbar and Alt-Tab
+    pub(crate) stealth_mode: Mutex<bool>,
+    /// Hide the system tray icon; launching SnapLock again still shows the window
+    pub(crate) hide_tray_icon: Mutex<bool>,
+    /// Lock the screen before capturing for CaptureAndLock
+    pub(crate) lock_first: Mutex<bool>,
+    /// Minimum seconds between security alerts; triggers inside the window are coalesced (0 disables).
+    pub(crate) alert_min_interval_seconds: Mutex<u64>,
+    /// Extra attempts to register the main shortcut before falling back.
+    pub(crate) shortcut_retry_attempts: Mutex<u32>,
+    /// Base delay between shortcut registration attempts, doubled after each failure.
+    pub(crate) shortcut_retry_interval_ms: Mutex<u64>,
+    /// Milliseconds after entering Active during which input only updates activity time.
+    pub(crate) arm_settle_ms: Mutex<u64>,
+    /// Global input listener implementation, takes effect when the listener is next started.
+    pub(crate) monitoring_backend: Mutex<MonitoringBackend>,
+    /// Whether keyboard input triggers the lockdown.
+    pub(crate) trigger_on_keyboard: Mutex<bool>,
+    /// Whether mouse button presses trigger the lockdown.
+    pub(crate) trigger_on_mouse_buttons: Mutex<bool>,
+    /// Whether mouse movement triggers the lockdown.
+    pub(crate) trigger_on_mouse_move: Mutex<bool>,
+    /// Whether mouse wheel scrolling triggers the lockdown.
+    pub(crate) trigger_on_scroll: Mutex<bool>,
+    /// Pixel format requested from the webcam for captures.
+    pub(crate) camera_fourcc: Mutex<CameraFourcc>,
+    /// Settings of the optional localhost HTTP API.
+    pub(crate) local_api: Mutex<LocalApiConfig>,
+    /// Credentials used to connect to an SMB share save path.
+    pub(crate) share_credentials: Mutex<Option<NetworkShareCredentials>>,
+    /// Whether webcam motion detection triggers while armed.
+    pub(crate) motion_detection_enabled: Mutex<bool>,
+    /// Percentage of changed pixels that counts as motion.
+    pub(crate) motion_threshold_percent: Mutex<u8>,
+    /// Interval between motion detection samples in milliseconds.
+    pub(crate) motion_sample_interval_ms: Mutex<u64>,
+    /// Frames read and discarded after opening the camera for a photo
+    pub(crate) photo_warmup_frames: Mutex<u32>,
+    /// Encoding used when saving captured photos
+    pub(crate) capture_image_format: Mutex<ImageFormat>,
+    /// Whether captured photos get a timestamp watermark
+    pub(crate) watermark_enabled: Mutex<bool>,
+    /// Seconds without input before idle-managed screen recording pauses; 0 keeps it running.
+    pub(crate) recording_idle_timeout_secs: Mutex<u32>,
+    /// Frames per second captured for screen recordings.
+    pub(crate) recording_framerate: Mutex<u32>,
+    /// Screen recordings are downscaled to 1/n of the captured width.
+    pub(crate) recording_scale_divisor: Mutex<u32>,
+    /// Target video bitrate for screen recordings, in kbit/s.
+    pub(crate) recording_bitrate_kbps: Mutex<u32>,
+    /// Whether a desktop screenshot is saved when the lock is triggered
+    pub(crate) capture_screenshot_on_trigger: Mutex<bool>,
+    /// URL that receives a JSON POST when the lock is triggered
+    pub(crate) webhook_url: Mutex<Option<String>>,
+    /// SMTP settings for emailing the intruder photo on trigger
+    pub(crate) smtp: Mutex<SmtpConfig>,
+    /// Maximum number of trigger events kept in the history file
+    pub(crate) event_history_limit: Mutex<u32>,
+    /// Length in seconds of the webcam video recorded on trigger
+    pub(crate) camera_recording_seconds: Mutex<u32>,
 }
 
 impl AppState {
@@ -84,7 +268,98 @@ impl AppState {
             enable_notifications: Mutex::new(true),
             post_trigger_action: Mutex::new(PostTriggerAction::CaptureAndLock),
             capture_delay_seconds: Mutex::new(0),
-            capture_mode: Mutex::new(CaptureMode::Video),
+            capture_mode: Mutex::new(CaptureMode::PhotoOnly),
+            max_segment_seconds: Mutex::new(0),
+            min_free_mb: Mutex::new(500),
+            ignored_keys: Mutex::new(Vec::new()),
+            preparation_delay_seconds: Mutex::new(
+                crate::constants::DEFAULT_PREPARATION_DELAY_SECONDS,
+            ),
+            notification_title: Mutex::new(None),
+            notification_body: Mutex::new(None),
+            retrigger_cooldown_ms: Mutex::new(crate::constants::DEFAULT_RETRIGGER_COOLDOWN_MS),
+            rearm_after_trigger: Mutex::new(false),
+            autostart_enabled: Mutex::new(false),
+            auto_arm_on_start: Mutex::new(false),
+            close_to_tray: Mutex::new(true),
+            allow_no_camera: Mutex::new(false),
+            notify_on_startup: Mutex::new(false),
+            recording_region: Mutex::new(RecordingRegion::FullDesktop),
+            capture_camera_ids: Mutex::new(Vec::new()),
+            restore_armed_state: Mutex::new(false),
+            was_armed: Mutex::new(false),
+            jpeg_quality: Mutex::new(crate::constants::DEFAULT_JPEG_QUALITY),
+            camera_busy_retry_attempts: Mutex::new(
+                crate::constants::DEFAULT_CAMERA_BUSY_RETRY_ATTEMPTS,
+            ),
+            camera_busy_retry_backoff_ms: Mutex::new(
+                crate::constants::DEFAULT_CAMERA_BUSY_RETRY_BACKOFF_MS,
+            ),
+            prebuffer_seconds: Mutex::new(0),
+            shortcut_debounce_ms: Mutex::new(crate::constants::DEFAULT_SHORTCUT_DEBOUNCE_MS),
+            shortcut_flag_clear_delay_ms: Mutex::new(
+                crate::constants::DEFAULT_SHORTCUT_FLAG_CLEAR_DELAY_MS,
+            ),
+            event_ignore_window_ms: Mutex::new(crate::constants::DEFAULT_EVENT_IGNORE_WINDOW_MS),
+            language: Mutex::new(None),
+            capture_timeout_ms: Mutex::new(crate::constants::DEFAULT_CAPTURE_TIMEOUT_MS),
+            recording_duration_seconds: Mutex::new(0),
+            ffmpeg_path: Mutex::new(None),
+            trigger_mode: Mutex::new(TriggerMode::Activity),
+            idle_trigger_minutes: Mutex::new(crate::constants::DEFAULT_IDLE_TRIGGER_MINUTES),
+            organize_by_date: Mutex::new(false),
+            audio_device: Mutex::new(None),
+            trigger_on_lid_close: Mutex::new(false),
+            notification_permission_denied: Mutex::new(false),
+            active_shortcut: Mutex::new(None),
+            monitored_device_id: Mutex::new(None),
+            record_before_lock_seconds: Mutex::new(
+                crate::constants::DEFAULT_RECORD_BEFORE_LOCK_SECONDS,
+            ),
+            photo_with_screen_recording: Mutex::new(false),
+            on_unlock_action: Mutex::new(UnlockAction::ResetToIdle),
+            panic_shortcut_key: Mutex::new(None),
+            window_geometry: Mutex::new(None),
+            stealth_mode: Mutex::new(false),
+            hide_tray_icon: Mutex::new(false),
+            lock_first: Mutex::new(false),
+            alert_min_interval_seconds: Mutex::new(0),
+            shortcut_retry_attempts: Mutex::new(crate::constants::DEFAULT_SHORTCUT_RETRY_ATTEMPTS),
+            shortcut_retry_interval_ms: Mutex::new(
+                crate::constants::DEFAULT_SHORTCUT_RETRY_INTERVAL_MS,
+            ),
+            arm_settle_ms: Mutex::new(crate::constants::DEFAULT_ARM_SETTLE_MS),
+            monitoring_backend: Mutex::new(MonitoringBackend::Rdev),
+            trigger_on_keyboard: Mutex::new(true),
+            trigger_on_mouse_buttons: Mutex::new(true),
+            trigger_on_mouse_move: Mutex::new(true),
+            trigger_on_scroll: Mutex::new(true),
+            camera_fourcc: Mutex::new(CameraFourcc::Auto),
+            local_api: Mutex::new(LocalApiConfig::default()),
+            share_credentials: Mutex::new(None),
+            motion_detection_enabled: Mutex::new(false),
+            motion_threshold_percent: Mutex::new(
+                crate::constants::DEFAULT_MOTION_THRESHOLD_PERCENT,
+            ),
+            motion_sample_interval_ms: Mutex::new(
+                crate::constants::DEFAULT_MOTION_SAMPLE_INTERVAL_MS,
+            ),
+            photo_warmup_frames: Mutex::new(crate::constants::DEFAULT_PHOTO_WARMUP_FRAMES),
+            capture_image_format: Mutex::new(ImageFormat::default()),
+            watermark_enabled: Mutex::new(true),
+            recording_idle_timeout_secs: Mutex::new(
+                crate::constants::DEFAULT_RECORDING_IDLE_TIMEOUT_SECS,
+            ),
+            recording_framerate: Mutex::new(crate::constants::DEFAULT_RECORDING_FRAMERATE),
+            recording_scale_divisor: Mutex::new(crate::constants::DEFAULT_RECORDING_SCALE_DIVISOR),
+            recording_bitrate_kbps: Mutex::new(crate::constants::DEFAULT_RECORDING_BITRATE_KBPS),
+            capture_screenshot_on_trigger: Mutex::new(false),
+            webhook_url: Mutex::new(None),
+            smtp: Mutex::new(SmtpConfig::default()),
+            event_history_limit: Mutex::new(crate::constants::DEFAULT_EVENT_HISTORY_LIMIT),
+            camera_recording_seconds: Mutex::new(
+                crate::constants::DEFAULT_CAMERA_RECORDING_SECONDS,
+            ),
         }
     }
 
@@ -199,6 +474,13 @@ impl AppState {
         self.post_trigger_action.lock().unwrap().clone()
     }
 
+    /// 屏幕录制模式下未设置固定时长时，由空闲检测循环按活动启停录制
+    pub fn idle_managed_recording(&self) -> bool {
+        self.trigger_mode() == TriggerMode::Activity
+            && self.post_trigger_action() == PostTriggerAction::ScreenRecording
+            && self.recording_duration_seconds() == 0
+    }
+
     pub fn set_post_trigger_action(&self, action: PostTriggerAction) {
         *self.post_trigger_action.lock().unwrap() = action;
     }
@@ -212,12 +494,591 @@ impl AppState {
     }
 
     pub fn capture_mode(&self) -> CaptureMode {
-        self.capture_mode.lock().unwrap().clone()
+        *self.capture_mode.lock().unwrap()
     }
 
     pub fn set_capture_mode(&self, mode: CaptureMode) {
         *self.capture_mode.lock().unwrap() = mode;
     }
+
+    pub fn max_segment_seconds(&self) -> u32 {
+        *self.max_segment_seconds.lock().unwrap()
+    }
+
+    pub fn set_max_segment_seconds(&self, seconds: u32) {
+        *self.max_segment_seconds.lock().unwrap() = seconds;
+    }
+
+    pub fn min_free_mb(&self) -> u64 {
+        *self.min_free_mb.lock().unwrap()
+    }
+
+    pub fn set_min_free_mb(&self, min_free_mb: u64) {
+        *self.min_free_mb.lock().unwrap() = min_free_mb;
+    }
+
+    pub fn ignored_keys(&self) -> Vec<String> {
+        self.ignored_keys.lock().unwrap().clone()
+    }
+
+    pub fn set_ignored_keys(&self, keys: Vec<String>) {
+        *self.ignored_keys.lock().unwrap() = keys;
+    }
+
+    pub fn preparation_delay_seconds(&self) -> u32 {
+        *self.preparation_delay_seconds.lock().unwrap()
+    }
+
+    pub fn set_preparation_delay_seconds(&self, delay: u32) {
+        *self.preparation_delay_seconds.lock().unwrap() = delay;
+    }
+
+    pub fn notification_title(&self) -> Option<String> {
+        self.notification_title.lock().unwrap().clone()
+    }
+
+    pub fn set_notification_title(&self, title: Option<String>) {
+        *self.notification_title.lock().unwrap() = title;
+    }
+
+    pub fn notification_body(&self) -> Option<String> {
+        self.notification_body.lock().unwrap().clone()
+    }
+
+    pub fn set_notification_body(&self, body: Option<String>) {
+        *self.notification_body.lock().unwrap() = body;
+    }
+
+    pub fn retrigger_cooldown_ms(&self) -> u64 {
+        *self.retrigger_cooldown_ms.lock().unwrap()
+    }
+
+    pub fn set_retrigger_cooldown_ms(&self, cooldown_ms: u64) {
+        *self.retrigger_cooldown_ms.lock().unwrap() = cooldown_ms;
+    }
+
+    pub fn rearm_after_trigger(&self) -> bool {
+        *self.rearm_after_trigger.lock().unwrap()
+    }
+
+    pub fn set_rearm_after_trigger(&self, enabled: bool) {
+        *self.rearm_after_trigger.lock().unwrap() = enabled;
+    }
+
+    pub fn autostart_enabled(&self) -> bool {
+        *self.autostart_enabled.lock().unwrap()
+    }
+
+    pub fn set_autostart_enabled(&self, enabled: bool) {
+        *self.autostart_enabled.lock().unwrap() = enabled;
+    }
+
+    pub fn auto_arm_on_start(&self) -> bool {
+        *self.auto_arm_on_start.lock().unwrap()
+    }
+
+    pub fn set_auto_arm_on_start(&self, enabled: bool) {
+        *self.auto_arm_on_start.lock().unwrap() = enabled;
+    }
+
+    pub fn close_to_tray(&self) -> bool {
+        *self.close_to_tray.lock().unwrap()
+    }
+
+    pub fn set_close_to_tray(&self, enabled: bool) {
+        *self.close_to_tray.lock().unwrap() = enabled;
+    }
+
+    pub fn allow_no_camera(&self) -> bool {
+        *self.allow_no_camera.lock().unwrap()
+    }
+
+    pub fn set_allow_no_camera(&self, enabled: bool) {
+        *self.allow_no_camera.lock().unwrap() = enabled;
+    }
+
+    pub fn notify_on_startup(&self) -> bool {
+        *self.notify_on_startup.lock().unwrap()
+    }
+
+    pub fn set_notify_on_startup(&self, enabled: bool) {
+        *self.notify_on_startup.lock().unwrap() = enabled;
+    }
+
+    pub fn recording_region(&self) -> RecordingRegion {
+        *self.recording_region.lock().unwrap()
+    }
+
+    pub fn set_recording_region(&self, region: RecordingRegion) {
+        *self.recording_region.lock().unwrap() = region;
+    }
+
+    pub fn capture_camera_ids(&self) -> Vec<u32> {
+        self.capture_camera_ids.lock().unwrap().clone()
+    }
+
+    pub fn set_capture_camera_ids(&self, camera_ids: Vec<u32>) {
+        *self.capture_camera_ids.lock().unwrap() = camera_ids;
+    }
+
+    pub fn restore_armed_state(&self) -> bool {
+        *self.restore_armed_state.lock().unwrap()
+    }
+
+    pub fn set_restore_armed_state(&self, enabled: bool) {
+        *self.restore_armed_state.lock().unwrap() = enabled;
+    }
+
+    pub fn was_armed(&self) -> bool {
+        *self.was_armed.lock().unwrap()
+    }
+
+    pub fn set_was_armed(&self, enabled: bool) {
+        *self.was_armed.lock().unwrap() = enabled;
+    }
+
+    pub fn jpeg_quality(&self) -> u8 {
+        *self.jpeg_quality.lock().unwrap()
+    }
+
+    pub fn set_jpeg_quality(&self, quality: u8) {
+        *self.jpeg_quality.lock().unwrap() = quality;
+    }
+
+    pub fn camera_busy_retry_attempts(&self) -> u32 {
+        *self.camera_busy_retry_attempts.lock().unwrap()
+    }
+
+    pub fn set_camera_busy_retry_attempts(&self, attempts: u32) {
+        *self.camera_busy_retry_attempts.lock().unwrap() = attempts;
+    }
+
+    pub fn camera_busy_retry_backoff_ms(&self) -> u64 {
+        *self.camera_busy_retry_backoff_ms.lock().unwrap()
+    }
+
+    pub fn set_camera_busy_retry_backoff_ms(&self, backoff_ms: u64) {
+        *self.camera_busy_retry_backoff_ms.lock().unwrap() = backoff_ms;
+    }
+
+    pub fn prebuffer_seconds(&self) -> u32 {
+        *self.prebuffer_seconds.lock().unwrap()
+    }
+
+    pub fn set_prebuffer_seconds(&self, seconds: u32) {
+        *self.prebuffer_seconds.lock().unwrap() = seconds;
+    }
+
+    pub fn shortcut_debounce_ms(&self) -> u64 {
+        *self.shortcut_debounce_ms.lock().unwrap()
+    }
+
+    pub fn set_shortcut_debounce_ms(&self, value: u64) {
+        *self.shortcut_debounce_ms.lock().unwrap() = value;
+    }
+
+    pub fn shortcut_flag_clear_delay_ms(&self) -> u64 {
+        *self.shortcut_flag_clear_delay_ms.lock().unwrap()
+    }
+
+    pub fn set_shortcut_flag_clear_delay_ms(&self, value: u64) {
+        *self.shortcut_flag_clear_delay_ms.lock().unwrap() = value;
+    }
+
+    pub fn event_ignore_window_ms(&self) -> u64 {
+        *self.event_ignore_window_ms.lock().unwrap()
+    }
+
+    pub fn set_event_ignore_window_ms(&self, value: u64) {
+        *self.event_ignore_window_ms.lock().unwrap() = value;
+    }
+
+    pub fn language(&self) -> Option<Language> {
+        *self.language.lock().unwrap()
+    }
+
+    pub fn set_language(&self, language: Option<Language>) {
+        *self.language.lock().unwrap() = language;
+    }
+
+    pub fn capture_timeout_ms(&self) -> u64 {
+        *self.capture_timeout_ms.lock().unwrap()
+    }
+
+    pub fn set_capture_timeout_ms(&self, timeout_ms: u64) {
+        *self.capture_timeout_ms.lock().unwrap() = timeout_ms;
+    }
+
+    pub fn recording_duration_seconds(&self) -> u32 {
+        *self.recording_duration_seconds.lock().unwrap()
+    }
+
+    pub fn set_recording_duration_seconds(&self, seconds: u32) {
+        *self.recording_duration_seconds.lock().unwrap() = seconds;
+    }
+
+    pub fn ffmpeg_path(&self) -> Option<String> {
+        self.ffmpeg_path.lock().unwrap().clone()
+    }
+
+    pub fn set_ffmpeg_path(&self, path: Option<String>) {
+        *self.ffmpeg_path.lock().unwrap() = path;
+    }
+
+    pub fn trigger_mode(&self) -> TriggerMode {
+        *self.trigger_mode.lock().unwrap()
+    }
+
+    pub fn set_trigger_mode(&self, mode: TriggerMode) {
+        *self.trigger_mode.lock().unwrap() = mode;
+    }
+
+    pub fn idle_trigger_minutes(&self) -> u32 {
+        *self.idle_trigger_minutes.lock().unwrap()
+    }
+
+    pub fn set_idle_trigger_minutes(&self, minutes: u32) {
+        *self.idle_trigger_minutes.lock().unwrap() = minutes;
+    }
+
+    pub fn organize_by_date(&self) -> bool {
+        *self.organize_by_date.lock().unwrap()
+    }
+
+    pub fn set_organize_by_date(&self, enabled: bool) {
+        *self.organize_by_date.lock().unwrap() = enabled;
+    }
+
+    pub fn audio_device(&self) -> Option<String> {
+        self.audio_device.lock().unwrap().clone()
+    }
+
+    pub fn set_audio_device(&self, device: Option<String>) {
+        *self.audio_device.lock().unwrap() = device;
+    }
+
+    pub fn trigger_on_lid_close(&self) -> bool {
+        *self.trigger_on_lid_close.lock().unwrap()
+    }
+
+    pub fn set_trigger_on_lid_close(&self, enabled: bool) {
+        *self.trigger_on_lid_close.lock().unwrap() = enabled;
+    }
+
+    pub fn notification_permission_denied(&self) -> bool {
+        *self.notification_permission_denied.lock().unwrap()
+    }
+
+    pub fn set_notification_permission_denied(&self, denied: bool) {
+        *self.notification_permission_denied.lock().unwrap() = denied;
+    }
+
+    pub fn active_shortcut(&self) -> Option<String> {
+        self.active_shortcut.lock().unwrap().clone()
+    }
+
+    pub fn set_active_shortcut(&self, shortcut: Option<String>) {
+        *self.active_shortcut.lock().unwrap() = shortcut;
+    }
+
+    pub fn monitored_device_id(&self) -> Option<String> {
+        self.monitored_device_id.lock().unwrap().clone()
+    }
+
+    pub fn set_monitored_device_id(&self, device_id: Option<String>) {
+        *self.monitored_device_id.lock().unwrap() = device_id;
+    }
+
+    pub fn record_before_lock_seconds(&self) -> u32 {
+        *self.record_before_lock_seconds.lock().unwrap()
+    }
+
+    pub fn set_record_before_lock_seconds(&self, seconds: u32) {
+        *self.record_before_lock_seconds.lock().unwrap() = seconds;
+    }
+
+    pub fn photo_with_screen_recording(&self) -> bool {
+        *self.photo_with_screen_recording.lock().unwrap()
+    }
+
+    pub fn set_photo_with_screen_recording(&self, enabled: bool) {
+        *self.photo_with_screen_recording.lock().unwrap() = enabled;
+    }
+
+    pub fn on_unlock_action(&self) -> UnlockAction {
+        *self.on_unlock_action.lock().unwrap()
+    }
+
+    pub fn set_on_unlock_action(&self, action: UnlockAction) {
+        *self.on_unlock_action.lock().unwrap() = action;
+    }
+
+    pub fn panic_shortcut_key(&self) -> Option<String> {
+        self.panic_shortcut_key.lock().unwrap().clone()
+    }
+
+    pub fn set_panic_shortcut_key(&self, shortcut: Option<String>) {
+        *self.panic_shortcut_key.lock().unwrap() = shortcut;
+    }
+
+    pub fn window_geometry(&self) -> Option<WindowGeometry> {
+        *self.window_geometry.lock().unwrap()
+    }
+
+    pub fn set_window_geometry(&self, geometry: Option<WindowGeometry>) {
+        *self.window_geometry.lock().unwrap() = geometry;
+    }
+
+    pub fn stealth_mode(&self) -> bool {
+        *self.stealth_mode.lock().unwrap()
+    }
+
+    pub fn set_stealth_mode(&self, enabled: bool) {
+        *self.stealth_mode.lock().unwrap() = enabled;
+    }
+
+    pub fn hide_tray_icon(&self) -> bool {
+        *self.hide_tray_icon.lock().unwrap()
+    }
+
+    pub fn set_hide_tray_icon(&self, enabled: bool) {
+        *self.hide_tray_icon.lock().unwrap() = enabled;
+    }
+
+    pub fn lock_first(&self) -> bool {
+        *self.lock_first.lock().unwrap()
+    }
+
+    pub fn set_lock_first(&self, enabled: bool) {
+        *self.lock_first.lock().unwrap() = enabled;
+    }
+
+    pub fn alert_min_interval_seconds(&self) -> u64 {
+        *self.alert_min_interval_seconds.lock().unwrap()
+    }
+
+    pub fn set_alert_min_interval_seconds(&self, seconds: u64) {
+        *self.alert_min_interval_seconds.lock().unwrap() = seconds;
+    }
+
+    pub fn shortcut_retry_attempts(&self) -> u32 {
+        *self.shortcut_retry_attempts.lock().unwrap()
+    }
+
+    pub fn set_shortcut_retry_attempts(&self, attempts: u32) {
+        *self.shortcut_retry_attempts.lock().unwrap() = attempts;
+    }
+
+    pub fn shortcut_retry_interval_ms(&self) -> u64 {
+        *self.shortcut_retry_interval_ms.lock().unwrap()
+    }
+
+    pub fn set_shortcut_retry_interval_ms(&self, interval_ms: u64) {
+        *self.shortcut_retry_interval_ms.lock().unwrap() = interval_ms;
+    }
+
+    pub fn arm_settle_ms(&self) -> u64 {
+        *self.arm_settle_ms.lock().unwrap()
+    }
+
+    pub fn set_arm_settle_ms(&self, settle_ms: u64) {
+        *self.arm_settle_ms.lock().unwrap() = settle_ms;
+    }
+
+    pub fn monitoring_backend(&self) -> MonitoringBackend {
+        *self.monitoring_backend.lock().unwrap()
+    }
+
+    pub fn set_monitoring_backend(&self, backend: MonitoringBackend) {
+        *self.monitoring_backend.lock().unwrap() = backend;
+    }
+
+    pub fn trigger_on_keyboard(&self) -> bool {
+        *self.trigger_on_keyboard.lock().unwrap()
+    }
+
+    pub fn set_trigger_on_keyboard(&self, enabled: bool) {
+        *self.trigger_on_keyboard.lock().unwrap() = enabled;
+    }
+
+    pub fn trigger_on_mouse_buttons(&self) -> bool {
+        *self.trigger_on_mouse_buttons.lock().unwrap()
+    }
+
+    pub fn set_trigger_on_mouse_buttons(&self, enabled: bool) {
+        *self.trigger_on_mouse_buttons.lock().unwrap() = enabled;
+    }
+
+    pub fn trigger_on_mouse_move(&self) -> bool {
+        *self.trigger_on_mouse_move.lock().unwrap()
+    }
+
+    pub fn set_trigger_on_mouse_move(&self, enabled: bool) {
+        *self.trigger_on_mouse_move.lock().unwrap() = enabled;
+    }
+
+    pub fn trigger_on_scroll(&self) -> bool {
+        *self.trigger_on_scroll.lock().unwrap()
+    }
+
+    pub fn set_trigger_on_scroll(&self, enabled: bool) {
+        *self.trigger_on_scroll.lock().unwrap() = enabled;
+    }
+
+    pub fn camera_fourcc(&self) -> CameraFourcc {
+        *self.camera_fourcc.lock().unwrap()
+    }
+
+    pub fn set_camera_fourcc(&self, fourcc: CameraFourcc) {
+        *self.camera_fourcc.lock().unwrap() = fourcc;
+    }
+
+    pub fn local_api(&self) -> LocalApiConfig {
+        self.local_api.lock().unwrap().clone()
+    }
+
+    pub fn set_local_api(&self, config: LocalApiConfig) {
+        *self.local_api.lock().unwrap() = config;
+    }
+
+    pub fn share_credentials(&self) -> Option<NetworkShareCredentials> {
+        self.share_credentials.lock().unwrap().clone()
+    }
+
+    pub fn set_share_credentials(&self, credentials: Option<NetworkShareCredentials>) {
+        *self.share_credentials.lock().unwrap() = credentials;
+    }
+
+    pub fn motion_detection_enabled(&self) -> bool {
+        *self.motion_detection_enabled.lock().unwrap()
+    }
+
+    pub fn set_motion_detection_enabled(&self, enabled: bool) {
+        *self.motion_detection_enabled.lock().unwrap() = enabled;
+    }
+
+    pub fn motion_threshold_percent(&self) -> u8 {
+        *self.motion_threshold_percent.lock().unwrap()
+    }
+
+    pub fn set_motion_threshold_percent(&self, percent: u8) {
+        *self.motion_threshold_percent.lock().unwrap() = percent;
+    }
+
+    pub fn motion_sample_interval_ms(&self) -> u64 {
+        *self.motion_sample_interval_ms.lock().unwrap()
+    }
+
+    pub fn set_motion_sample_interval_ms(&self, interval_ms: u64) {
+        *self.motion_sample_interval_ms.lock().unwrap() = interval_ms;
+    }
+
+    pub fn photo_warmup_frames(&self) -> u32 {
+        *self.photo_warmup_frames.lock().unwrap()
+    }
+
+    pub fn set_photo_warmup_frames(&self, frames: u32) {
+        *self.photo_warmup_frames.lock().unwrap() = frames;
+    }
+
+    pub fn capture_image_format(&self) -> ImageFormat {
+        *self.capture_image_format.lock().unwrap()
+    }
+
+    pub fn set_capture_image_format(&self, format: ImageFormat) {
+        *self.capture_image_format.lock().unwrap() = format;
+    }
+
+    pub fn watermark_enabled(&self) -> bool {
+        *self.watermark_enabled.lock().unwrap()
+    }
+
+    pub fn set_watermark_enabled(&self, enabled: bool) {
+        *self.watermark_enabled.lock().unwrap() = enabled;
+    }
+
+    pub fn recording_idle_timeout_secs(&self) -> u32 {
+        *self.recording_idle_timeout_secs.lock().unwrap()
+    }
+
+    pub fn set_recording_idle_timeout_secs(&self, seconds: u32) {
+        *self.recording_idle_timeout_secs.lock().unwrap() = seconds;
+    }
+
+    pub fn recording_framerate(&self) -> u32 {
+        *self.recording_framerate.lock().unwrap()
+    }
+
+    pub fn set_recording_framerate(&self, framerate: u32) {
+        *self.recording_framerate.lock().unwrap() = framerate;
+    }
+
+    pub fn recording_scale_divisor(&self) -> u32 {
+        *self.recording_scale_divisor.lock().unwrap()
+    }
+
+    pub fn set_recording_scale_divisor(&self, divisor: u32) {
+        *self.recording_scale_divisor.lock().unwrap() = divisor;
+    }
+
+    pub fn recording_bitrate_kbps(&self) -> u32 {
+        *self.recording_bitrate_kbps.lock().unwrap()
+    }
+
+    pub fn set_recording_bitrate_kbps(&self, bitrate_kbps: u32) {
+        *self.recording_bitrate_kbps.lock().unwrap() = bitrate_kbps;
+    }
+
+    pub fn capture_screenshot_on_trigger(&self) -> bool {
+        *self.capture_screenshot_on_trigger.lock().unwrap()
+    }
+
+    pub fn set_capture_screenshot_on_trigger(&self, enabled: bool) {
+        *self.capture_screenshot_on_trigger.lock().unwrap() = enabled;
+    }
+
+    pub fn webhook_url(&self) -> Option<String> {
+        self.webhook_url.lock().unwrap().clone()
+    }
+
+    pub fn set_webhook_url(&self, url: Option<String>) {
+        *self.webhook_url.lock().unwrap() = url;
+    }
+
+    pub fn smtp(&self) -> SmtpConfig {
+        self.smtp.lock().unwrap().clone()
+    }
+
+    pub fn set_smtp(&self, config: SmtpConfig) {
+        *self.smtp.lock().unwrap() = config;
+    }
+
+    pub fn event_history_limit(&self) -> u32 {
+        *self.event_history_limit.lock().unwrap()
+    }
+
+    pub fn set_event_history_limit(&self, limit: u32) {
+        *self.event_history_limit.lock().unwrap() = limit;
+    }
+
+    pub fn camera_recording_seconds(&self) -> u32 {
+        *self.camera_recording_seconds.lock().unwrap()
+    }
+
+    pub fn set_camera_recording_seconds(&self, seconds: u32) {
+        *self.camera_recording_seconds.lock().unwrap() = seconds;
+    }
+}
+
+/// Whether the keys of the last shortcut are still held down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortcutKeyState {
+    /// No key was seen held when the shortcut fired; fall back to the timed ignore window.
+    Untracked,
+    /// Waiting for every key held at shortcut time to be released.
+    Held,
+    /// All shortcut keys have been released.
+    Released,
 }
 
 /// Holds the monitoring flags for the application.
@@ -228,18 +1089,34 @@ pub struct MonitoringFlags {
     pub(crate) shortcut_in_progress: std::sync::atomic::AtomicBool,
     /// Timestamp of last shortcut activation (in milliseconds since epoch)
     pub(crate) last_shortcut_time: std::sync::atomic::AtomicU64,
+    /// Keys currently held down, as seen by the global input listener.
+    pub(crate) pressed_keys: Mutex<Vec<rdev::Key>>,
+    /// Release tracking for the keys of the last shortcut.
+    pub(crate) shortcut_key_state: Mutex<ShortcutKeyState>,
     /// Timestamp of last user activity (in milliseconds since epoch)
     pub(crate) last_activity_time: std::sync::atomic::AtomicU64,
+    /// Wakes the idle recording timer when new activity is recorded.
+    pub(crate) activity_notify: tokio::sync::Notify,
+    /// Timestamp of the last lockdown trigger (in milliseconds since epoch)
+    pub(crate) last_trigger_time: std::sync::atomic::AtomicU64,
+    /// Timestamp at which monitoring last entered Active (in milliseconds since epoch)
+    pub(crate) armed_at: std::sync::atomic::AtomicU64,
     /// Whether the global input listener is ready to be used.
     pub(crate) listener_ready: std::sync::atomic::AtomicBool,
+    /// Set when the listener died while armed so the watchdog re-arms monitoring.
+    pub(crate) recovery_pending: std::sync::atomic::AtomicBool,
     /// Generation counter used to cancel stale trigger flows.
     pub(crate) action_generation: std::sync::atomic::AtomicU64,
+    /// Generation of the running simulated trigger flow, `u64::MAX` when none.
+    pub(crate) simulated_generation: std::sync::atomic::AtomicU64,
     /// Latest listener startup/runtime error, if any.
     pub(crate) listener_error: Mutex<Option<String>>,
     /// Handle to the long-lived input listener thread.
     pub(crate) listener_handle: Mutex<Option<std::thread::JoinHandle<()>>>,
     /// Handle to the idle check task for lifecycle management
     pub(crate) idle_check_handle: Mutex<Option<JoinHandle<()>>>,
+    /// Handle to the screen recording ffmpeg watchdog, running only while armed
+    pub(crate) ffmpeg_watchdog_handle: Mutex<Option<JoinHandle<()>>>,
 }
 
 impl MonitoringFlags {
@@ -248,12 +1125,20 @@ impl MonitoringFlags {
             monitoring_active: std::sync::atomic::AtomicBool::new(false),
             shortcut_in_progress: std::sync::atomic::AtomicBool::new(false),
             last_shortcut_time: std::sync::atomic::AtomicU64::new(0),
+            pressed_keys: Mutex::new(Vec::new()),
+            shortcut_key_state: Mutex::new(ShortcutKeyState::Untracked),
             last_activity_time: std::sync::atomic::AtomicU64::new(0),
+            activity_notify: tokio::sync::Notify::new(),
+            last_trigger_time: std::sync::atomic::AtomicU64::new(0),
+            armed_at: std::sync::atomic::AtomicU64::new(0),
             listener_ready: std::sync::atomic::AtomicBool::new(false),
+            recovery_pending: std::sync::atomic::AtomicBool::new(false),
             action_generation: std::sync::atomic::AtomicU64::new(0),
+            simulated_generation: std::sync::atomic::AtomicU64::new(u64::MAX),
             listener_error: Mutex::new(None),
             listener_handle: Mutex::new(None),
             idle_check_handle: Mutex::new(None),
+            ffmpeg_watchdog_handle: Mutex::new(None),
         }
     }
 
@@ -287,6 +1172,49 @@ impl MonitoringFlags {
             .store(value, std::sync::atomic::Ordering::SeqCst);
     }
 
+    /// Record a key press/release. Returns true when this release lets go of
+    /// the last key held since the shortcut fired, ending its ignore window.
+    pub fn track_key_event(&self, event_type: &rdev::EventType) -> bool {
+        let mut pressed_keys = self.pressed_keys.lock().unwrap();
+        match event_type {
+            rdev::EventType::KeyPress(key) => {
+                if !pressed_keys.contains(key) {
+                    pressed_keys.push(*key);
+                }
+                false
+            }
+            rdev::EventType::KeyRelease(key) => {
+                pressed_keys.retain(|pressed| pressed != key);
+                if !pressed_keys.is_empty() {
+                    return false;
+                }
+
+                let mut key_state = self.shortcut_key_state.lock().unwrap();
+                if *key_state != ShortcutKeyState::Held {
+                    return false;
+                }
+                *key_state = ShortcutKeyState::Released;
+                self.set_shortcut_in_progress(false);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Start tracking the release of the keys held when a shortcut fired.
+    pub fn begin_shortcut_key_tracking(&self) {
+        let held = !self.pressed_keys.lock().unwrap().is_empty();
+        *self.shortcut_key_state.lock().unwrap() = if held {
+            ShortcutKeyState::Held
+        } else {
+            ShortcutKeyState::Untracked
+        };
+    }
+
+    pub fn shortcut_key_state(&self) -> ShortcutKeyState {
+        *self.shortcut_key_state.lock().unwrap()
+    }
+
     pub fn last_activity_time(&self) -> u64 {
         self.last_activity_time
             .load(std::sync::atomic::Ordering::SeqCst)
@@ -295,6 +1223,34 @@ impl MonitoringFlags {
     pub fn set_last_activity_time(&self, value: u64) {
         self.last_activity_time
             .store(value, std::sync::atomic::Ordering::SeqCst);
+        self.activity_notify.notify_one();
+    }
+
+    /// Waits until the next call to `set_last_activity_time`.
+    pub async fn activity_recorded(&self) {
+        self.activity_notify.notified().await;
+    }
+
+    pub fn last_trigger_time(&self) -> u64 {
+        self.last_trigger_time
+            .load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    pub fn set_last_trigger_time(&self, value: u64) {
+        self.last_trigger_time
+            .store(value, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    /// Whether input at `now_ms` falls inside the settling period right after arming.
+    pub fn is_within_arm_settle(&self, now_ms: u64, settle_ms: u64) -> bool {
+        let armed_at = self.armed_at.load(std::sync::atomic::Ordering::SeqCst);
+        armed_at != 0 && now_ms.saturating_sub(armed_at) < settle_ms
+    }
+
+    /// Whether a new trigger at `now_ms` falls inside the cooldown of the previous one.
+    pub fn is_within_retrigger_cooldown(&self, now_ms: u64, cooldown_ms: u64) -> bool {
+        let last_trigger = self.last_trigger_time();
+        last_trigger != 0 && now_ms.saturating_sub(last_trigger) < cooldown_ms
     }
 
     pub fn listener_ready(&self) -> bool {
@@ -317,11 +1273,48 @@ impl MonitoringFlags {
         self.current_action_generation() == generation
     }
 
+    /// Start a simulated trigger flow, cancelling any stale flow.
+    pub fn begin_simulation(&self) -> u64 {
+        let generation = self.invalidate_action_generation();
+        self.simulated_generation
+            .store(generation, std::sync::atomic::Ordering::SeqCst);
+        generation
+    }
+
+    pub fn end_simulation(&self, generation: u64) {
+        let _ = self.simulated_generation.compare_exchange(
+            generation,
+            u64::MAX,
+            std::sync::atomic::Ordering::SeqCst,
+            std::sync::atomic::Ordering::SeqCst,
+        );
+    }
+
+    /// Whether the given flow is a still-running simulated trigger.
+    pub fn is_simulation_current(&self, generation: u64) -> bool {
+        self.is_action_generation_current(generation)
+            && self
+                .simulated_generation
+                .load(std::sync::atomic::Ordering::SeqCst)
+                == generation
+    }
+
     pub fn set_listener_ready(&self, value: bool) {
         self.listener_ready
             .store(value, std::sync::atomic::Ordering::SeqCst);
     }
 
+    pub fn set_recovery_pending(&self, value: bool) {
+        self.recovery_pending
+            .store(value, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    /// Returns whether a recovery was requested and clears the request.
+    pub fn take_recovery_pending(&self) -> bool {
+        self.recovery_pending
+            .swap(false, std::sync::atomic::Ordering::SeqCst)
+    }
+
     pub fn listener_error(&self) -> Option<String> {
         self.listener_error.lock().unwrap().clone()
     }
@@ -360,6 +1353,27 @@ impl MonitoringFlags {
         }
     }
 
+    pub fn replace_ffmpeg_watchdog_handle(&self, handle: JoinHandle<()>) {
+        let mut guard = self.ffmpeg_watchdog_handle.lock().unwrap();
+        if let Some(existing) = guard.take() {
+            if !existing.is_finished() {
+                existing.abort();
+            }
+        }
+        *guard = Some(handle);
+    }
+
+    pub fn stop_ffmpeg_watchdog(&self) {
+        if let Ok(mut handle_guard) = self.ffmpeg_watchdog_handle.lock() {
+            if let Some(handle) = handle_guard.take() {
+                if !handle.is_finished() {
+                    log::info!("中止 ffmpeg 看门狗");
+                    handle.abort();
+                }
+            }
+        }
+    }
+
     pub fn is_listener_thread_alive(&self) -> bool {
         if let Ok(handle_guard) = self.listener_handle.lock() {
             if let Some(handle) = handle_guard.as_ref() {
@@ -384,6 +1398,14 @@ impl MonitoringFlags {
         }
 
         self.stop_idle_check_thread();
+        self.stop_ffmpeg_watchdog();
+        self.armed_at.store(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            std::sync::atomic::Ordering::SeqCst,
+        );
         self.set_monitoring_active(true);
         true
     }
@@ -392,7 +1414,9 @@ impl MonitoringFlags {
     pub fn stop_monitoring(&self) {
         log::info!("停止监控状态...");
         self.stop_idle_check_thread();
+        self.stop_ffmpeg_watchdog();
         self.set_monitoring_active(false);
+        self.set_recovery_pending(false);
         self.invalidate_action_generation();
         log::info!("监控状态已重置为非激活");
     }
@@ -416,6 +1440,7 @@ impl MonitoringFlags {
             );
             self.set_monitoring_active(false);
             self.invalidate_action_generation();
+            self.set_recovery_pending(true);
         }
 
         is_healthy
@@ -424,7 +1449,7 @@ impl MonitoringFlags {
 
 #[cfg(test)]
 mod tests {
-    use super::{MonitoringFlags, MonitoringState};
+    use super::{MonitoringFlags, MonitoringState, ShortcutKeyState};
 
     #[test]
     fn monitoring_state_transitions_allow_expected_flow() {
@@ -454,6 +1479,68 @@ mod tests {
         );
     }
 
+    #[test]
+    fn monitoring_state_transition_table_is_exhaustive() {
+        use MonitoringState::{Active, Idle, Preparing, Triggered};
+
+        let allowed = [
+            (Idle, Idle),
+            (Idle, Preparing),
+            (Preparing, Idle),
+            (Preparing, Active),
+            (Active, Idle),
+            (Active, Triggered),
+            (Triggered, Idle),
+        ];
+
+        for from in MonitoringState::ALL {
+            for to in MonitoringState::ALL {
+                let expected = allowed.contains(&(from, to));
+                assert_eq!(
+                    from.transition_to(to).is_ok(),
+                    expected,
+                    "{:?} -> {:?}",
+                    from,
+                    to
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn triggered_cannot_return_to_active_directly() {
+        assert!(
+            MonitoringState::Triggered
+                .transition_to(MonitoringState::Active)
+                .is_err()
+        );
+        assert!(
+            MonitoringState::Triggered
+                .transition_to(MonitoringState::Triggered)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn valid_next_states_match_transition_rules() {
+        assert_eq!(
+            MonitoringState::Idle.valid_next_states(),
+            vec![MonitoringState::Idle, MonitoringState::Preparing]
+        );
+        assert_eq!(
+            MonitoringState::Preparing.valid_next_states(),
+            vec![MonitoringState::Idle, MonitoringState::Active]
+        );
+        assert_eq!(
+            MonitoringState::Active.valid_next_states(),
+            vec![MonitoringState::Idle, MonitoringState::Triggered]
+        );
+        assert_eq!(
+            MonitoringState::Triggered.valid_next_states(),
+            vec![MonitoringState::Idle]
+        );
+    }
+
     #[test]
     fn monitoring_flags_fail_health_check_when_listener_missing() {
         let flags = MonitoringFlags::new();
@@ -463,6 +1550,20 @@ mod tests {
         assert!(!flags.monitoring_active());
     }
 
+    #[test]
+    fn failed_health_check_requests_recovery_until_monitoring_stops() {
+        let flags = MonitoringFlags::new();
+        flags.set_monitoring_active(true);
+        assert!(!flags.health_check());
+
+        assert!(flags.take_recovery_pending());
+        assert!(!flags.take_recovery_pending());
+
+        flags.set_recovery_pending(true);
+        flags.stop_monitoring();
+        assert!(!flags.take_recovery_pending());
+    }
+
     #[test]
     fn monitoring_flags_require_listener_before_activation() {
         let flags = MonitoringFlags::new();
@@ -470,6 +1571,45 @@ mod tests {
         assert!(!flags.monitoring_active());
     }
 
+    #[test]
+    fn retrigger_cooldown_blocks_triggers_inside_window() {
+        let flags = MonitoringFlags::new();
+        assert!(!flags.is_within_retrigger_cooldown(10_000, 3_000));
+
+        flags.set_last_trigger_time(10_000);
+
+        assert!(flags.is_within_retrigger_cooldown(12_999, 3_000));
+        assert!(!flags.is_within_retrigger_cooldown(13_000, 3_000));
+        assert!(!flags.is_within_retrigger_cooldown(10_500, 0));
+    }
+
+    #[test]
+    fn arm_settle_ignores_input_right_after_arming() {
+        let flags = MonitoringFlags::new();
+        assert!(!flags.is_within_arm_settle(10_000, 500));
+
+        flags
+            .armed_at
+            .store(10_000, std::sync::atomic::Ordering::SeqCst);
+        assert!(flags.is_within_arm_settle(10_499, 500));
+        assert!(!flags.is_within_arm_settle(10_500, 500));
+        assert!(!flags.is_within_arm_settle(10_100, 0));
+    }
+
+    #[test]
+    fn stopping_monitoring_cancels_simulation() {
+        let flags = MonitoringFlags::new();
+        let generation = flags.begin_simulation();
+        assert!(flags.is_simulation_current(generation));
+
+        flags.stop_monitoring();
+        assert!(!flags.is_simulation_current(generation));
+
+        let next_generation = flags.begin_simulation();
+        flags.end_simulation(next_generation);
+        assert!(!flags.is_simulation_current(next_generation));
+    }
+
     #[test]
     fn invalidating_action_generation_cancels_stale_flow() {
         let flags = MonitoringFlags::new();
@@ -481,4 +1621,29 @@ mod tests {
         assert!(!flags.is_action_generation_current(initial_generation));
         assert!(flags.is_action_generation_current(next_generation));
     }
+
+    #[test]
+    fn shortcut_ignore_window_ends_when_all_keys_are_released() {
+        use rdev::{EventType, Key};
+
+        let flags = MonitoringFlags::new();
+        flags.begin_shortcut_key_tracking();
+        assert_eq!(flags.shortcut_key_state(), ShortcutKeyState::Untracked);
+
+        assert!(!flags.track_key_event(&EventType::KeyPress(Key::Alt)));
+        assert!(!flags.track_key_event(&EventType::KeyPress(Key::KeyL)));
+        flags.set_shortcut_in_progress(true);
+        flags.begin_shortcut_key_tracking();
+        assert_eq!(flags.shortcut_key_state(), ShortcutKeyState::Held);
+
+        assert!(!flags.track_key_event(&EventType::KeyRelease(Key::KeyL)));
+        assert_eq!(flags.shortcut_key_state(), ShortcutKeyState::Held);
+        assert!(flags.track_key_event(&EventType::KeyRelease(Key::Alt)));
+        assert_eq!(flags.shortcut_key_state(), ShortcutKeyState::Released);
+        assert!(!flags.shortcut_in_progress());
+
+        // 之后的按键不再属于快捷键
+        assert!(!flags.track_key_event(&EventType::KeyPress(Key::KeyA)));
+        assert!(!flags.track_key_event(&EventType::KeyRelease(Key::KeyA)));
+    }
 }
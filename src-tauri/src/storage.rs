@@ -0,0 +1,244 @@
+use chrono::{Local, NaiveDate};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::state::AppState;
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// 找到路径中第一个已存在的祖先目录（保存目录可能尚未创建）
+fn existing_ancestor(path: &Path) -> Option<&Path> {
+    path.ancestors().find(|candidate| candidate.exists())
+}
+
+/// 查询目标目录所在磁盘的剩余空间（MB）
+pub fn available_mb(path: &Path) -> Option<u64> {
+    let target = existing_ancestor(path)?;
+    match fs2::available_space(target) {
+        Ok(bytes) => Some(bytes / BYTES_PER_MB),
+        Err(error) => {
+            log::warn!("查询磁盘剩余空间失败 ({}): {}", target.display(), error);
+            None
+        }
+    }
+}
+
+/// 校验保存路径：必须为绝对路径，不存在时创建，并通过创建/删除临时文件确认可写
+pub fn validate_save_path(path: &str) -> Result<PathBuf, String> {
+    let path = path.trim();
+    if path.is_empty() {
+        return Err("保存路径不能为空".to_string());
+    }
+
+    let path = PathBuf::from(path);
+    if !path.is_absolute() {
+        return Err(format!("保存路径必须为绝对路径: {}", path.display()));
+    }
+    if path.exists() && !path.is_dir() {
+        return Err(format!("保存路径不是文件夹: {}", path.display()));
+    }
+
+    std::fs::create_dir_all(&path)
+        .map_err(|e| format!("无法创建保存路径 {}: {}", path.display(), e))?;
+
+    let probe = path.join(format!(".snaplock_write_test_{}", std::process::id()));
+    std::fs::write(&probe, b"").map_err(|e| format!("保存路径不可写 {}: {}", path.display(), e))?;
+    if let Err(error) = std::fs::remove_file(&probe) {
+        log::warn!("删除写入测试文件失败 ({}): {}", probe.display(), error);
+    }
+
+    Ok(path)
+}
+
+fn has_enough_space(path: &Path, min_free_mb: u64) -> bool {
+    match available_mb(path) {
+        Some(free_mb) => free_mb >= min_free_mb,
+        // 无法查询时不阻止拍摄
+        None => true,
+    }
+}
+
+/// 按日期归档时使用的子目录 `YYYY-MM-DD`
+fn dated_subfolder(base: &Path, date: NaiveDate) -> PathBuf {
+    base.join(date.format("%Y-%m-%d").to_string())
+}
+
+/// 解析本次拍摄/录制的保存目录。
+///
+/// 返回值与 `AppState::save_path` 语义相同。启用按日期归档时返回当天的子目录，
+/// 子目录创建失败则记录日志并回退到基础目录。
+pub fn resolve_capture_save_path(app_handle: &AppHandle) -> Option<String> {
+    let save_path = resolve_base_save_path(app_handle);
+    if !app_handle.state::<AppState>().organize_by_date() {
+        return save_path;
+    }
+
+    let base = save_path
+        .clone()
+        .unwrap_or_else(crate::config::get_default_save_path);
+    let dated = dated_subfolder(Path::new(&base), Local::now().date_naive());
+    match std::fs::create_dir_all(&dated) {
+        Ok(()) => Some(dated.to_string_lossy().to_string()),
+        Err(error) => {
+            log::warn!(
+                "创建日期子目录失败 ({})，使用基础保存目录: {}",
+                dated.display(),
+                error
+            );
+            save_path
+        }
+    }
+}
+
+/// 保存目录为需要认证的网络共享时，写入前先用配置的凭据连接；会阻塞，不能在触发流程中调用
+pub(crate) fn connect_network_share(state: &AppState) -> Result<(), String> {
+    match (state.save_path(), state.share_credentials()) {
+        (Some(save_path), Some(credentials)) => {
+            crate::network_share::ensure_connected(&save_path, &credentials)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// 触发流程使用：共享尚未连接时在后台发起连接并返回 false，不等待网络
+fn network_share_ready(state: &AppState) -> bool {
+    match (state.save_path(), state.share_credentials()) {
+        (Some(save_path), Some(credentials)) => {
+            let connected = crate::network_share::is_connected(&save_path);
+            // 已连接时也在后台重新验证，连接断开后下一次触发可以恢复
+            crate::network_share::connect_in_background(&save_path, &credentials);
+            connected
+        }
+        _ => true,
+    }
+}
+
+/// 拍摄/录制前的磁盘空间预检。
+///
+/// 当保存目录所在磁盘剩余空间低于 `min_free_mb` 时，回退到默认保存路径或系统临时目录。
+fn resolve_base_save_path(app_handle: &AppHandle) -> Option<String> {
+    let state = app_handle.state::<AppState>();
+    let save_path = state.save_path();
+    if !network_share_ready(&state) {
+        // 共享尚未连接时先保存到本机，避免等待网络延误锁屏或丢失本次拍摄
+        let fallback = crate::config::get_default_save_path();
+        log::warn!("网络共享尚未连接，本次文件改为保存到: {}", fallback);
+        crate::notifications::show(
+            app_handle,
+            &crate::i18n::tr_with(
+                app_handle,
+                crate::i18n::Message::ShareUnavailableFallback,
+                &[("path", &fallback)],
+            ),
+        );
+        return Some(fallback);
+    }
+
+    let min_free_mb = state.min_free_mb();
+    if min_free_mb == 0 {
+        return save_path;
+    }
+
+    let primary = PathBuf::from(state.get_effective_save_path());
+    if has_enough_space(&primary, min_free_mb) {
+        return save_path;
+    }
+
+    log::warn!(
+        "保存目录 '{}' 剩余空间低于 {} MB，尝试使用备用路径",
+        primary.display(),
+        min_free_mb
+    );
+
+    let candidates = [
+        PathBuf::from(crate::config::get_default_save_path()),
+        std::env::temp_dir().join("SnapLock"),
+    ];
+
+    for candidate in candidates {
+        if candidate == primary || !has_enough_space(&candidate, min_free_mb) {
+            continue;
+        }
+
+        if let Err(error) = std::fs::create_dir_all(&candidate) {
+            log::warn!("创建备用保存目录失败 ({}): {}", candidate.display(), error);
+            continue;
+        }
+
+        log::warn!("已切换到备用保存路径: {}", candidate.display());
+        crate::notifications::show(
+            app_handle,
+            &crate::i18n::tr_with(
+                app_handle,
+                crate::i18n::Message::SaveDirFallback,
+                &[("path", &candidate.display().to_string())],
+            ),
+        );
+        return Some(candidate.to_string_lossy().to_string());
+    }
+
+    log::error!(
+        "所有保存路径的剩余空间均不足 {} MB，继续使用原路径",
+        min_free_mb
+    );
+    crate::notifications::show(
+        app_handle,
+        &crate::i18n::tr(app_handle, crate::i18n::Message::DiskSpaceLow),
+    );
+    save_path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{available_mb, dated_subfolder, existing_ancestor, validate_save_path};
+    use chrono::NaiveDate;
+    use std::path::Path;
+
+    #[test]
+    fn dated_subfolder_uses_iso_date() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 7).unwrap();
+        assert_eq!(
+            dated_subfolder(Path::new("captures"), date),
+            Path::new("captures").join("2024-03-07")
+        );
+    }
+
+    #[test]
+    fn existing_ancestor_skips_missing_directories() {
+        let base = std::env::temp_dir();
+        let missing = base.join("snaplock_missing_dir").join("nested");
+
+        assert_eq!(existing_ancestor(&missing), Some(base.as_path()));
+    }
+
+    #[test]
+    fn available_mb_reports_space_for_missing_subdirectory() {
+        let missing = std::env::temp_dir().join("snaplock_missing_dir");
+
+        assert!(available_mb(&missing).is_some());
+    }
+
+    #[test]
+    fn validate_save_path_creates_writable_directory() {
+        let dir = std::env::temp_dir()
+            .join(format!("snaplock_save_path_{}", std::process::id()))
+            .join("nested");
+
+        assert_eq!(validate_save_path(&dir.to_string_lossy()), Ok(dir.clone()));
+        assert!(dir.is_dir());
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+
+        std::fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn validate_save_path_rejects_relative_and_file_paths() {
+        assert!(validate_save_path("").is_err());
+        assert!(validate_save_path("captures").is_err());
+
+        let file = std::env::temp_dir().join(format!("snaplock_save_file_{}", std::process::id()));
+        std::fs::write(&file, b"").unwrap();
+        assert!(validate_save_path(&file.to_string_lossy()).is_err());
+        std::fs::remove_file(file).unwrap();
+    }
+}
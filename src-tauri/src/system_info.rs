@@ -0,0 +1,129 @@
+//! 本机信息：供通知模板、事件记录与远程告警使用
+
+use serde::Serialize;
+
+#[cfg(target_os = "windows")]
+use windows::Win32::System::Registry::{HKEY_LOCAL_MACHINE, RRF_RT_REG_SZ, RegGetValueW};
+#[cfg(target_os = "windows")]
+use windows::core::{PCWSTR, w};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SystemInfo {
+    pub hostname: String,
+    pub os_name: String,
+    pub os_version: String,
+    pub username: String,
+}
+
+/// 获取当前登录用户名
+pub fn current_username() -> String {
+    let from_env = if cfg!(target_os = "windows") {
+        std::env::var("USERNAME")
+    } else {
+        std::env::var("USER")
+    };
+
+    from_env
+        .ok()
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// 从 /etc/os-release 内容中读取发行版名称与版本
+#[cfg_attr(target_os = "windows", allow(dead_code))]
+fn parse_os_release(content: &str) -> (Option<String>, Option<String>) {
+    let value_of = |key: &str| {
+        content.lines().find_map(|line| {
+            line.strip_prefix(key)
+                .and_then(|rest| rest.strip_prefix('='))
+                .map(|value| value.trim().trim_matches('"').to_string())
+                .filter(|value| !value.is_empty())
+        })
+    };
+
+    (value_of("NAME"), value_of("VERSION_ID"))
+}
+
+#[cfg(target_os = "windows")]
+fn read_current_version_value(name: &str) -> Option<String> {
+    let value_name: Vec<u16> = name.encode_utf16().chain(std::iter::once(0)).collect();
+    let mut buffer = [0u16; 256];
+    let mut size = std::mem::size_of_val(&buffer) as u32;
+    let result = unsafe {
+        RegGetValueW(
+            HKEY_LOCAL_MACHINE,
+            w!("SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion"),
+            PCWSTR(value_name.as_ptr()),
+            RRF_RT_REG_SZ,
+            None,
+            Some(buffer.as_mut_ptr().cast()),
+            Some(&mut size),
+        )
+    };
+    if result.is_err() {
+        return None;
+    }
+
+    let length = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    Some(String::from_utf16_lossy(&buffer[..length])).filter(|value| !value.is_empty())
+}
+
+#[cfg(target_os = "windows")]
+fn os_name_and_version() -> (String, String) {
+    let build = read_current_version_value("CurrentBuild");
+    let version = match (read_current_version_value("DisplayVersion"), &build) {
+        (Some(display), Some(build)) => format!("{} (build {})", display, build),
+        (None, Some(build)) => format!("build {}", build),
+        (Some(display), None) => display,
+        (None, None) => "unknown".to_string(),
+    };
+    // 注册表中的 ProductName 在 Windows 11 上仍为 "Windows 10"，按内部版本号区分
+    let name = match build.and_then(|build| build.parse::<u32>().ok()) {
+        Some(build) if build >= 22_000 => "Windows 11",
+        Some(_) => "Windows 10",
+        None => "Windows",
+    };
+    (name.to_string(), version)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn os_name_and_version() -> (String, String) {
+    let (name, version) = std::fs::read_to_string("/etc/os-release")
+        .map(|content| parse_os_release(&content))
+        .unwrap_or((None, None));
+    (
+        name.unwrap_or_else(|| std::env::consts::OS.to_string()),
+        version.unwrap_or_else(|| "unknown".to_string()),
+    )
+}
+
+pub fn collect() -> SystemInfo {
+    let (os_name, os_version) = os_name_and_version();
+    SystemInfo {
+        hostname: crate::notifications::system_hostname(),
+        os_name,
+        os_version,
+        username: current_username(),
+    }
+}
+
+/// 获取计算机名、操作系统与当前用户
+#[tauri::command]
+pub fn get_system_info() -> SystemInfo {
+    collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_os_release;
+
+    #[test]
+    fn reads_name_and_version_from_os_release() {
+        let content = "PRETTY_NAME=\"Ubuntu 24.04 LTS\"\nNAME=\"Ubuntu\"\nVERSION_ID=\"24.04\"\n";
+        assert_eq!(
+            parse_os_release(content),
+            (Some("Ubuntu".to_string()), Some("24.04".to_string()))
+        );
+        assert_eq!(parse_os_release("ID=arch\n"), (None, None));
+    }
+}
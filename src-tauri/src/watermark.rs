@@ -0,0 +1,129 @@
+//! 在照片左下角绘制拍摄时间水印，背景为半透明黑框，深浅背景下都能看清。
+//! 时间戳只包含数字、`-`、`:` 与空格，使用内置的 5x7 点阵字体绘制，不引入字体渲染依赖。
+
+use image::{Rgb, RgbImage};
+
+const GLYPH_WIDTH: u32 = 5;
+const GLYPH_HEIGHT: u32 = 7;
+/// 字符之间留 1 列空白
+const GLYPH_ADVANCE: u32 = GLYPH_WIDTH + 1;
+/// 以 240 像素高为基准按比例放大字体
+const SCALE_BASE_HEIGHT: u32 = 240;
+const TEXT_COLOR: Rgb<u8> = Rgb([255, 255, 255]);
+
+/// 5x7 点阵，每行低 5 位从左到右表示像素
+fn glyph(c: char) -> [u8; 7] {
+    match c {
+        '0' => [
+            0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110,
+        ],
+        '1' => [
+            0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110,
+        ],
+        '2' => [
+            0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111,
+        ],
+        '3' => [
+            0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110,
+        ],
+        '4' => [
+            0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010,
+        ],
+        '5' => [
+            0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110,
+        ],
+        '6' => [
+            0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110,
+        ],
+        '7' => [
+            0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000,
+        ],
+        '8' => [
+            0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110,
+        ],
+        '9' => [
+            0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100,
+        ],
+        '-' => [
+            0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000,
+        ],
+        ':' => [
+            0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b01100, 0b00000,
+        ],
+        _ => [0; 7],
+    }
+}
+
+/// 将背景调暗一半，相当于叠加 50% 透明度的黑色
+fn darken(pixel: &mut Rgb<u8>) {
+    for channel in pixel.0.iter_mut() {
+        *channel /= 2;
+    }
+}
+
+/// 在图像左下角绘制文字水印；图像太小放不下时超出部分被裁剪
+pub fn draw_timestamp(image: &mut RgbImage, text: &str) {
+    let (width, height) = image.dimensions();
+    let scale = (height / SCALE_BASE_HEIGHT).max(1);
+    let padding = 2 * scale;
+    let char_count = text.chars().count() as u32;
+    if char_count == 0 {
+        return;
+    }
+
+    let box_width = char_count * GLYPH_ADVANCE * scale - scale + 2 * padding;
+    let box_height = GLYPH_HEIGHT * scale + 2 * padding;
+    let box_left = padding;
+    let box_top = height.saturating_sub(padding + box_height);
+
+    for y in box_top..(box_top + box_height).min(height) {
+        for x in box_left..(box_left + box_width).min(width) {
+            darken(image.get_pixel_mut(x, y));
+        }
+    }
+
+    let text_left = box_left + padding;
+    let text_top = box_top + padding;
+    for (index, c) in text.chars().enumerate() {
+        let glyph_left = text_left + index as u32 * GLYPH_ADVANCE * scale;
+        for (row, bits) in glyph(c).iter().enumerate() {
+            for column in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - column)) == 0 {
+                    continue;
+                }
+                let x0 = glyph_left + column * scale;
+                let y0 = text_top + row as u32 * scale;
+                for y in y0..(y0 + scale).min(height) {
+                    for x in x0..(x0 + scale).min(width) {
+                        image.put_pixel(x, y, TEXT_COLOR);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::draw_timestamp;
+    use image::{Rgb, RgbImage};
+
+    #[test]
+    fn timestamp_is_drawn_in_bottom_left_corner() {
+        let mut image = RgbImage::from_pixel(320, 240, Rgb([200, 200, 200]));
+        draw_timestamp(&mut image, "2024-03-07 12:00:00");
+
+        // 右上角不受影响，左下角背景被调暗，并有白色文字像素
+        assert_eq!(*image.get_pixel(319, 0), Rgb([200, 200, 200]));
+        assert_eq!(*image.get_pixel(3, 236), Rgb([100, 100, 100]));
+        let mut bottom_left = (0..120).flat_map(|x| (225..240).map(move |y| (x, y)));
+        assert!(bottom_left.any(|(x, y)| *image.get_pixel(x, y) == Rgb([255, 255, 255])));
+    }
+
+    #[test]
+    fn tiny_images_are_clipped_without_panicking() {
+        let mut image = RgbImage::new(8, 4);
+        draw_timestamp(&mut image, "2024-03-07 12:00:00");
+        draw_timestamp(&mut image, "");
+    }
+}
@@ -0,0 +1,110 @@
+// snaplock/src-tauri/src/webhook.rs
+//! 触发锁定时向用户配置的地址 POST 一条 JSON 通知，便于接入家庭自动化等外部系统。
+//!
+//! 请求在后台任务中发送并有超时限制，失败只记录日志，不影响锁定流程。
+//! 与系统警报共用最小间隔，间隔内的多次触发合并为一次请求。
+
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::config::PostTriggerAction;
+use crate::constants::WEBHOOK_TIMEOUT;
+use crate::notifications::RemoteAlertThrottle;
+
+static WEBHOOK_THROTTLE: Mutex<RemoteAlertThrottle<(String, WebhookPayload)>> =
+    Mutex::new(RemoteAlertThrottle::new());
+
+/// Webhook 请求体
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookPayload {
+    pub timestamp: String,
+    pub camera_id: u32,
+    pub action: PostTriggerAction,
+    pub photo_path: Option<String>,
+    pub hostname: String,
+    /// 合并发送时为间隔内的触发次数，否则为 1
+    pub trigger_count: u32,
+    /// 由模拟触发发送的测试请求
+    pub test: bool,
+}
+
+/// 校验 Webhook 地址，只接受 http 与 https
+pub fn validate_url(url: &str) -> Result<(), String> {
+    let rest = url
+        .strip_prefix("http://")
+        .or_else(|| url.strip_prefix("https://"))
+        .ok_or_else(|| "Webhook 地址必须以 http:// 或 https:// 开头".to_string())?;
+
+    let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    if host.is_empty() || host.contains(char::is_whitespace) {
+        return Err("Webhook 地址缺少有效的主机名".to_string());
+    }
+    Ok(())
+}
+
+/// 按警报最小间隔发送触发通知，间隔内的触发合并后带次数发送
+pub fn send_throttled(app_handle: &AppHandle, url: String, payload: WebhookPayload) {
+    crate::notifications::throttle_remote_alert(
+        app_handle,
+        &WEBHOOK_THROTTLE,
+        (url, payload),
+        |(url, payload), trigger_count| {
+            send(
+                url,
+                WebhookPayload {
+                    trigger_count,
+                    ..payload
+                },
+            )
+        },
+    );
+}
+
+/// 在后台发送通知，不等待结果
+pub fn send(url: String, payload: WebhookPayload) {
+    tauri::async_runtime::spawn(async move {
+        match post(&url, &payload).await {
+            Ok(status) => log::info!("Webhook 通知已发送，响应状态: {}", status),
+            Err(error) => log::error!("Webhook 通知发送失败: {}", error),
+        }
+    });
+}
+
+async fn post(url: &str, payload: &WebhookPayload) -> Result<reqwest::StatusCode, String> {
+    let client = reqwest::Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()
+        .map_err(|e| format!("无法创建 HTTP 客户端: {}", e))?;
+
+    let response = client
+        .post(url)
+        .json(payload)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let status = response.status();
+    if status.is_success() {
+        Ok(status)
+    } else {
+        Err(format!("服务器返回 {}", status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::validate_url;
+
+    #[test]
+    fn only_http_and_https_urls_are_accepted() {
+        assert!(validate_url("http://homeassistant.local:8123/api/webhook/snaplock").is_ok());
+        assert!(validate_url("https://example.com/hook?token=abc").is_ok());
+
+        assert!(validate_url("ftp://example.com/hook").is_err());
+        assert!(validate_url("example.com/hook").is_err());
+        assert!(validate_url("https://").is_err());
+        assert!(validate_url("https:///path").is_err());
+    }
+}
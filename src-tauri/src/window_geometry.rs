@@ -0,0 +1,166 @@
+//! 记住主窗口的位置与大小，启动时恢复并限制在当前显示器范围内
+
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, WebviewWindow};
+
+use crate::config::WindowGeometry;
+use crate::state::AppState;
+
+/// 将窗口限制在与其重叠最多的显示器内；与所有显示器都不重叠时移到第一个（主）显示器
+fn clamp_to_monitors(geometry: WindowGeometry, monitors: &[WindowGeometry]) -> WindowGeometry {
+    let overlap = |monitor: &WindowGeometry| {
+        let left = geometry.x.max(monitor.x) as i64;
+        let top = geometry.y.max(monitor.y) as i64;
+        let right = (geometry.x as i64 + geometry.width as i64)
+            .min(monitor.x as i64 + monitor.width as i64);
+        let bottom = (geometry.y as i64 + geometry.height as i64)
+            .min(monitor.y as i64 + monitor.height as i64);
+        (right - left).max(0) * (bottom - top).max(0)
+    };
+
+    let Some(monitor) = monitors
+        .iter()
+        .filter(|monitor| overlap(monitor) > 0)
+        .max_by_key(|monitor| overlap(monitor))
+        .or_else(|| monitors.first())
+    else {
+        return geometry;
+    };
+
+    let width = geometry.width.min(monitor.width);
+    let height = geometry.height.min(monitor.height);
+    WindowGeometry {
+        x: geometry
+            .x
+            .clamp(monitor.x, monitor.x + (monitor.width - width) as i32),
+        y: geometry
+            .y
+            .clamp(monitor.y, monitor.y + (monitor.height - height) as i32),
+        width,
+        height,
+    }
+}
+
+fn monitor_layout(window: &WebviewWindow) -> Vec<WindowGeometry> {
+    let primary = window.primary_monitor().ok().flatten();
+    let mut monitors: Vec<WindowGeometry> = window
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|monitor| WindowGeometry {
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+        })
+        .collect();
+
+    // 主显示器放在首位，作为窗口完全离屏时的落点
+    if let Some(primary) = primary {
+        if let Some(index) = monitors.iter().position(|monitor| {
+            monitor.x == primary.position().x && monitor.y == primary.position().y
+        }) {
+            monitors.swap(0, index);
+        }
+    }
+    monitors
+}
+
+/// 启动时恢复上次保存的窗口位置与大小
+pub fn restore(app_handle: &AppHandle) {
+    let Some(geometry) = app_handle.state::<AppState>().window_geometry() else {
+        return;
+    };
+    let Some(window) = app_handle.get_webview_window("main") else {
+        return;
+    };
+
+    let geometry = clamp_to_monitors(geometry, &monitor_layout(&window));
+    log::debug!("恢复主窗口位置与大小: {:?}", geometry);
+    if let Err(error) = window.set_size(PhysicalSize::new(geometry.width, geometry.height)) {
+        log::warn!("恢复窗口大小失败: {}", error);
+    }
+    if let Err(error) = window.set_position(PhysicalPosition::new(geometry.x, geometry.y)) {
+        log::warn!("恢复窗口位置失败: {}", error);
+    }
+}
+
+/// 窗口移动或缩放后记录当前几何信息（仅更新内存，关闭窗口或退出时写入配置）
+pub fn record(window: &tauri::Window) {
+    // 最小化时 Windows 会把窗口移到 (-32000, -32000) 且尺寸为 0，不应记录
+    if window.is_minimized().unwrap_or(false) {
+        return;
+    }
+    let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size()) else {
+        return;
+    };
+    if size.width == 0 || size.height == 0 {
+        return;
+    }
+
+    window
+        .state::<AppState>()
+        .set_window_geometry(Some(WindowGeometry {
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        }));
+}
+
+/// 将记录的窗口位置与大小写入配置
+pub fn persist(app_handle: &AppHandle) {
+    if app_handle.state::<AppState>().window_geometry().is_none() {
+        return;
+    }
+    if let Err(error) = crate::config::save_config(app_handle.clone()) {
+        log::warn!("保存窗口位置失败: {}", error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::clamp_to_monitors;
+    use crate::config::WindowGeometry;
+
+    fn rect(x: i32, y: i32, width: u32, height: u32) -> WindowGeometry {
+        WindowGeometry {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn window_inside_a_monitor_is_unchanged() {
+        let monitors = [rect(0, 0, 1920, 1080), rect(1920, 0, 1920, 1080)];
+        let window = rect(2000, 100, 800, 600);
+        assert_eq!(clamp_to_monitors(window, &monitors), window);
+    }
+
+    #[test]
+    fn offscreen_window_moves_to_primary_monitor() {
+        let monitors = [rect(0, 0, 1920, 1080)];
+        assert_eq!(
+            clamp_to_monitors(rect(3000, 200, 800, 600), &monitors),
+            rect(1120, 200, 800, 600)
+        );
+        assert_eq!(
+            clamp_to_monitors(rect(-500, -50, 2560, 1440), &monitors),
+            rect(0, 0, 1920, 1080)
+        );
+    }
+
+    #[test]
+    fn partially_visible_window_is_pulled_inside() {
+        let monitors = [rect(0, 0, 1920, 1080)];
+        assert_eq!(
+            clamp_to_monitors(rect(1700, 900, 800, 600), &monitors),
+            rect(1120, 480, 800, 600)
+        );
+        assert_eq!(
+            clamp_to_monitors(rect(10, 10, 800, 600), &[]),
+            rect(10, 10, 800, 600)
+        );
+    }
+}
@@ -5,6 +5,13 @@ export interface CameraInfo {
   name: string;
 }
 
+export interface CameraFormat {
+  width: number;
+  height: number;
+  frame_rate: number;
+  fourcc: string;
+}
+
 export interface AppConfig {
   shortcut_key: string;
   save_path: string | null;
@@ -13,12 +20,139 @@ export interface AppConfig {
   dark_mode: boolean;
   exit_on_lock: boolean;
   enable_notifications: boolean;
-  post_trigger_action: 'CaptureAndLock' | 'CaptureOnly' | 'ScreenRecording';
+  post_trigger_action: 'CaptureAndLock' | 'CaptureOnly' | 'ScreenRecording' | 'RecordAndLock' | 'RecordCamera';
   default_camera_id: number | null;
   capture_delay_seconds: number;
-  capture_mode: 'Video';
+  capture_mode: CaptureMode;
+  max_segment_seconds: number;
+  min_free_mb: number;
+  ignored_keys: string[];
+  preparation_delay_seconds: number;
+  notification_title: string | null;
+  notification_body: string | null;
+  retrigger_cooldown_ms: number;
+  rearm_after_trigger: boolean;
+  autostart_enabled: boolean;
+  auto_arm_on_start: boolean;
+  close_to_tray: boolean;
+  allow_no_camera: boolean;
+  notify_on_startup: boolean;
+  recording_region: RecordingRegion;
+  capture_camera_ids: number[];
+  restore_armed_state: boolean;
+  was_armed: boolean;
+  jpeg_quality: number;
+  camera_busy_retry_attempts: number;
+  camera_busy_retry_backoff_ms: number;
+  prebuffer_seconds: number;
+  shortcut_debounce_ms: number;
+  shortcut_flag_clear_delay_ms: number;
+  event_ignore_window_ms: number;
+  language: 'zh' | 'en' | null;
+  capture_timeout_ms: number;
+  recording_duration_seconds: number;
+  ffmpeg_path: string | null;
+  trigger_mode: 'Activity' | 'Idle';
+  idle_trigger_minutes: number;
+  organize_by_date: boolean;
+  audio_device: string | null;
+  trigger_on_lid_close: boolean;
+  monitored_device_id: string | null;
+  record_before_lock_seconds: number;
+  photo_with_screen_recording: boolean;
+  on_unlock_action: 'ResetToIdle' | 'ReArm' | 'DoNothing';
+  panic_shortcut_key: string | null;
+  window_geometry: WindowGeometry | null;
+  stealth_mode: boolean;
+  hide_tray_icon: boolean;
+  lock_first: boolean;
+  alert_min_interval_seconds: number;
+  shortcut_retry_attempts: number;
+  shortcut_retry_interval_ms: number;
+  arm_settle_ms: number;
+  monitoring_backend: 'Rdev' | 'WindowsHook';
+  config_version: number;
+  trigger_on_keyboard: boolean;
+  trigger_on_mouse_buttons: boolean;
+  trigger_on_mouse_move: boolean;
+  trigger_on_scroll: boolean;
+  camera_fourcc: 'Auto' | 'Mjpg' | 'Yuy2' | 'Nv12';
+  local_api: LocalApiConfig;
+  share_credentials: NetworkShareCredentials | null;
+  motion_detection_enabled: boolean;
+  motion_threshold_percent: number;
+  motion_sample_interval_ms: number;
+  photo_warmup_frames: number;
+  capture_image_format: 'Jpeg' | 'Png' | 'WebP';
+  watermark_enabled: boolean;
+  recording_idle_timeout_secs: number;
+  recording_framerate: number;
+  recording_scale_divisor: number;
+  recording_bitrate_kbps: number;
+  capture_screenshot_on_trigger: boolean;
+  webhook_url: string | null;
+  smtp: SmtpConfig;
+  event_history_limit: number;
+  camera_recording_seconds: number;
+}
+
+export type CaptureMode =
+  | 'PhotoOnly'
+  | 'Video'
+  | 'PhotoThenVideo'
+  | { Burst: { count: number; interval_ms: number } };
+
+export interface RecordingQualitySettings {
+  framerate: number;
+  scale_divisor: number;
+  bitrate_kbps: number;
+}
+
+export interface LocalApiConfig {
+  enabled: boolean;
+  port: number;
+  token: string | null;
+}
+
+export interface SmtpConfig {
+  host: string | null;
+  port: number;
+  username: string | null;
+  password: string | null;
+  alert_email_to: string | null;
 }
 
+export interface SmtpConfigInfo {
+  host: string | null;
+  port: number;
+  username: string | null;
+  has_password: boolean;
+  alert_email_to: string | null;
+}
+
+export interface NetworkShareCredentials {
+  username: string;
+  password: string;
+}
+
+export interface NetworkShareCredentialsInfo {
+  username: string;
+  has_password: boolean;
+}
+
+export interface WindowGeometry {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
+export type RecordingRegion =
+  | 'FullDesktop'
+  | 'PrimaryMonitor'
+  | { Monitor: { index: number } }
+  | { Custom: { x: number; y: number; width: number; height: number } };
+
 export interface LogEntry {
   timestamp: string;
   level: string;
@@ -26,10 +160,65 @@ export interface LogEntry {
   target: string;
 }
 
+export interface LockdownTriggeredEvent {
+  trigger_source: 'Keyboard' | 'Mouse' | 'Idle' | 'LidClose' | 'Simulated' | 'Motion';
+  timestamp: string;
+  photo_path: string | null;
+  photo_paths: string[];
+  video_path: string | null;
+  prebuffer_video_path: string | null;
+  screenshot_path: string | null;
+  post_trigger_action: 'CaptureAndLock' | 'CaptureOnly' | 'ScreenRecording' | 'RecordAndLock' | 'RecordCamera';
+}
+
+export interface CameraBusyRetryEvent {
+  camera_id: number;
+  attempt: number;
+  max_attempts: number;
+}
+
 export type MonitoringStatus = '空闲' | '准备中' | '警戒中' | '锁定中';
 
+export type MonitoringStateName = 'Idle' | 'Preparing' | 'Active' | 'Triggered';
+
+export interface MonitoringStatusInfo {
+  status: MonitoringStateName;
+  label: MonitoringStatus;
+  listener_alive: boolean;
+  valid_next_states: MonitoringStateName[];
+}
+
+export interface SystemInfo {
+  hostname: string;
+  os_name: string;
+  os_version: string;
+  username: string;
+}
+
+export type ShortcutRegistrationEvent =
+  | { status: 'registered'; shortcut: string }
+  | { status: 'fallback_registered'; configured: string; shortcut: string }
+  | { status: 'failed'; configured: string };
+
+export type SelfCheckStatus = 'Passed' | 'Failed' | 'Skipped';
+
+export interface SelfCheckItem {
+  name: 'camera' | 'save_path' | 'ffmpeg' | 'notifications' | 'shortcut';
+  status: SelfCheckStatus;
+  detail: string;
+}
+
+export interface SelfCheckReport {
+  passed: boolean;
+  items: SelfCheckItem[];
+}
+
 export type PermissionStatus = '未检查' | '已授权' | '被拒绝';
 
+export type TestNotificationError =
+  | { kind: 'permission_denied' }
+  | { kind: 'failed'; message: string };
+
 export type LogLevel = 'error' | 'warn' | 'info' | 'debug';
 
 // 拖拽相关接口